@@ -1,8 +1,25 @@
 use syn::{
     parse::ParseStream, punctuated::Punctuated, token::Comma, Attribute, Expr, Field, Lit, Meta,
-    Result, Variant,
+    MetaList, Result, Variant,
 };
 
+use crate::pattern;
+
+/// Field validation rule.
+pub enum Validation {
+    Range {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    Length {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    NonEmpty,
+    Pattern(String),
+    Custom(String),
+}
+
 /// Get the rename attribute for a given field or the field name.
 pub fn get_field_name(field: &Field) -> String {
     if let Some(v) = get_attr_value(&field.attrs, "rename") {
@@ -29,6 +46,19 @@ pub fn get_skip_field_serializing_if(field: &Field) -> Option<String> {
     }
 }
 
+/// Get the module path given by the `with` attribute (if present).
+fn get_field_with_module(field: &Field) -> Option<String> {
+    if let Some(v) = get_attr_value(&field.attrs, "with") {
+        if let Some(Lit::Str(n)) = v.lit() {
+            Some(n.value())
+        } else {
+            panic!("invalid with attribute");
+        }
+    } else {
+        None
+    }
+}
+
 /// Get field serializer path (if present).
 pub fn get_field_serializer(field: &Field) -> Option<String> {
     if let Some(v) = get_attr_value(&field.attrs, "serialize_with") {
@@ -37,6 +67,10 @@ pub fn get_field_serializer(field: &Field) -> Option<String> {
         } else {
             panic!("invalid serialize_with attribute");
         }
+    } else if let Some(module) = get_field_with_module(field) {
+        Some(format!("{}::serialize", module))
+    } else if has_flag(&field.attrs, "bytes") {
+        Some(String::from("serde_lite::bytes::serialize"))
     } else {
         None
     }
@@ -50,6 +84,10 @@ pub fn get_field_deserializer(field: &Field) -> Option<String> {
         } else {
             panic!("invalid deserialize_with attribute");
         }
+    } else if let Some(module) = get_field_with_module(field) {
+        Some(format!("{}::deserialize", module))
+    } else if has_flag(&field.attrs, "bytes") {
+        Some(String::from("serde_lite::bytes::deserialize"))
     } else {
         None
     }
@@ -63,6 +101,144 @@ pub fn get_field_updater(field: &Field) -> Option<String> {
         } else {
             panic!("invalid update_with attribute");
         }
+    } else if let Some(module) = get_field_with_module(field) {
+        Some(format!("{}::update", module))
+    } else if has_flag(&field.attrs, "bytes") {
+        Some(String::from("serde_lite::bytes::update"))
+    } else {
+        None
+    }
+}
+
+/// Get field describer path (if present).
+pub fn get_field_describer(field: &Field) -> Option<String> {
+    if let Some(v) = get_attr_value(&field.attrs, "describe_with") {
+        if let Some(Lit::Str(n)) = v.lit() {
+            Some(n.value())
+        } else {
+            panic!("invalid describe_with attribute");
+        }
+    } else {
+        None
+    }
+}
+
+/// Get validation rules given by the `validate` attribute of a given field.
+pub fn get_field_validations(field: &Field) -> Vec<Validation> {
+    let mut res = Vec::new();
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("serde") {
+            if let Ok(nested) = attr.parse_args_with(parse_nested_meta) {
+                for meta in nested {
+                    if let Meta::List(list) = meta {
+                        if list.path.is_ident("validate") {
+                            let rules = list
+                                .parse_args_with(parse_nested_meta)
+                                .expect("invalid validate attribute");
+
+                            res.extend(rules.into_iter().map(parse_validation));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    res
+}
+
+/// Parse a given validation rule.
+fn parse_validation(meta: Meta) -> Validation {
+    match meta {
+        Meta::Path(p) if p.is_ident("non_empty") => Validation::NonEmpty,
+        Meta::List(l) if l.path.is_ident("range") => {
+            let (min, max) = parse_validation_bounds(&l);
+
+            Validation::Range { min, max }
+        }
+        Meta::List(l) if l.path.is_ident("length") => {
+            let (min, max) = parse_validation_bounds(&l);
+
+            Validation::Length { min, max }
+        }
+        Meta::NameValue(a) if a.path.is_ident("pattern") => {
+            if let Some(Lit::Str(n)) = a.value.lit() {
+                let pattern = n.value();
+
+                if let Err(err) = pattern::check(&pattern) {
+                    panic!("invalid pattern validation: {} ({})", pattern, err);
+                }
+
+                Validation::Pattern(pattern)
+            } else {
+                panic!("invalid pattern validation");
+            }
+        }
+        Meta::NameValue(a) if a.path.is_ident("custom") => {
+            if let Some(Lit::Str(n)) = a.value.lit() {
+                Validation::Custom(n.value())
+            } else {
+                panic!("invalid custom validation");
+            }
+        }
+        _ => panic!("unknown validation rule"),
+    }
+}
+
+/// Parse the min and max bounds of a given validation rule.
+fn parse_validation_bounds(list: &MetaList) -> (Option<Expr>, Option<Expr>) {
+    let args = list
+        .parse_args_with(parse_nested_meta)
+        .expect("invalid validation bounds");
+
+    let mut min = None;
+    let mut max = None;
+
+    for arg in args {
+        match arg {
+            Meta::NameValue(a) if a.path.is_ident("min") => min = Some(a.value),
+            Meta::NameValue(a) if a.path.is_ident("max") => max = Some(a.value),
+            _ => panic!("invalid validation bounds"),
+        }
+    }
+
+    if min.is_none() && max.is_none() {
+        panic!("invalid validation bounds");
+    }
+
+    (min, max)
+}
+
+/// Get the non-finite number policy for a given field (if present).
+pub fn get_field_non_finite_policy(field: &Field) -> Option<&'static str> {
+    if let Some(v) = get_attr_value(&field.attrs, "non_finite") {
+        if let Some(Lit::Str(n)) = v.lit() {
+            let policy = match n.value().as_str() {
+                "allow" => "Allow",
+                "error" => "Error",
+                "null" => "Null",
+                "string" => "String",
+                _ => panic!("invalid non_finite attribute"),
+            };
+
+            Some(policy)
+        } else {
+            panic!("invalid non_finite attribute");
+        }
+    } else {
+        None
+    }
+}
+
+/// Get the container validator path (if present).
+pub fn get_container_validator(attrs: &[Attribute]) -> Option<String> {
+    if let Some(v) = get_attr_value(attrs, "validate") {
+        if let Some(Lit::Str(n)) = v.lit() {
+            Some(n.value())
+        } else {
+            panic!("invalid validate attribute");
+        }
     } else {
         None
     }
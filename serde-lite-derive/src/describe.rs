@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Attribute, Data, DataEnum, DeriveInput, Field, Fields, Generics, Ident};
+
+use crate::attributes;
+
+/// Expand derive Describe.
+pub fn derive_describe(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    let expanded = match input.data {
+        Data::Struct(data) => expand_for_struct(&input.ident, &input.generics, &data.fields),
+        Data::Enum(data) => expand_for_enum(&input.ident, &input.generics, data, &input.attrs),
+        Data::Union(_) => panic!("derive Describe is not supported for union types"),
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+/// Expand Describe for a given struct.
+fn expand_for_struct(name: &Ident, generics: &Generics, fields: &Fields) -> TokenStream {
+    let lname = Literal::string(&name.to_string());
+    let fields = describe_fields(fields);
+
+    let describe = quote! {
+        serde_lite::describe::Shape::Struct(serde_lite::describe::Struct::new(
+            #lname,
+            std::any::type_name::<Self>(),
+            #fields,
+        ))
+    };
+
+    expand_impl(name, generics, describe)
+}
+
+/// Expand Describe for a given enum.
+fn expand_for_enum(
+    name: &Ident,
+    generics: &Generics,
+    data: DataEnum,
+    attrs: &[Attribute],
+) -> TokenStream {
+    let lname = Literal::string(&name.to_string());
+
+    let repr = if let Some(tag) = attributes::get_enum_tag(attrs) {
+        let ltag = Literal::string(&tag);
+
+        if let Some(content) = attributes::get_enum_content(attrs) {
+            let lcontent = Literal::string(&content);
+
+            quote! {
+                serde_lite::describe::EnumRepr::Adjacent {
+                    tag: #ltag,
+                    content: #lcontent,
+                }
+            }
+        } else {
+            quote! {
+                serde_lite::describe::EnumRepr::Internal { tag: #ltag }
+            }
+        }
+    } else {
+        quote! {
+            serde_lite::describe::EnumRepr::External
+        }
+    };
+
+    let mut variants = TokenStream::new();
+
+    for variant in &data.variants {
+        let sname = attributes::get_variant_name(variant);
+        let lname = Literal::string(&sname);
+        let fields = describe_fields(&variant.fields);
+
+        variants.extend(quote! {
+            serde_lite::describe::Variant::new(#lname, #fields),
+        });
+    }
+
+    let describe = quote! {
+        serde_lite::describe::Shape::Enum(serde_lite::describe::Enum::new(
+            #lname,
+            std::any::type_name::<Self>(),
+            #repr,
+            vec![#variants],
+        ))
+    };
+
+    expand_impl(name, generics, describe)
+}
+
+/// Generate the Describe implementation with a given body.
+fn expand_impl(name: &Ident, generics: &Generics, describe: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics serde_lite::Describe for #name #ty_generics #where_clause {
+            fn describe() -> serde_lite::describe::Shape {
+                #describe
+            }
+        }
+    }
+}
+
+/// Generate description of given struct or enum variant fields.
+fn describe_fields(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(fields) => {
+            let fields = fields.named.iter().map(describe_named_field);
+
+            quote! {
+                serde_lite::describe::Fields::Named(vec![#(#fields),*])
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let shapes = fields.unnamed.iter().map(|field| {
+                let ty = &field.ty;
+
+                quote! {
+                    <#ty as serde_lite::Describe>::describe
+                }
+            });
+
+            quote! {
+                serde_lite::describe::Fields::Unnamed(vec![#(#shapes),*])
+            }
+        }
+        Fields::Unit => quote! {
+            serde_lite::describe::Fields::Unit
+        },
+    }
+}
+
+/// Generate description of a given named field.
+fn describe_named_field(field: &Field) -> TokenStream {
+    let ty = &field.ty;
+    let sname = attributes::get_field_name(field);
+    let lname = Literal::string(&sname);
+
+    let skip = attributes::has_flag(&field.attrs, "skip");
+    let skip_serializing = skip || attributes::has_flag(&field.attrs, "skip_serializing");
+    let skip_deserializing = skip || attributes::has_flag(&field.attrs, "skip_deserializing");
+
+    let custom = attributes::get_field_serializer(field).is_some()
+        || attributes::get_field_deserializer(field).is_some();
+
+    let shape = if let Some(path) = attributes::get_field_describer(field) {
+        TokenStream::from_str(&path).expect("invalid path given for the describe_with attribute")
+    } else if skip_serializing && skip_deserializing {
+        quote! {
+            || serde_lite::describe::Shape::Any
+        }
+    } else if attributes::has_flag(&field.attrs, "bytes") {
+        quote! {
+            || serde_lite::describe::Shape::Bytes
+        }
+    } else if custom {
+        quote! {
+            || serde_lite::describe::Shape::Any
+        }
+    } else {
+        quote! {
+            <#ty as serde_lite::Describe>::describe
+        }
+    };
+
+    let mut flags = Vec::new();
+
+    if attributes::has_flag(&field.attrs, "default") {
+        flags.push(quote! { serde_lite::describe::Field::DEFAULT });
+    }
+
+    if skip_serializing {
+        flags.push(quote! { serde_lite::describe::Field::SKIP_SERIALIZING });
+    }
+
+    if skip_deserializing {
+        flags.push(quote! { serde_lite::describe::Field::SKIP_DESERIALIZING });
+    }
+
+    if attributes::get_skip_field_serializing_if(field).is_some() {
+        flags.push(quote! { serde_lite::describe::Field::SKIP_SERIALIZING_IF });
+    }
+
+    if attributes::has_flag(&field.attrs, "flatten") {
+        flags.push(quote! { serde_lite::describe::Field::FLATTEN });
+    }
+
+    if flags.is_empty() {
+        quote! {
+            serde_lite::describe::Field::new(#lname, #shape)
+        }
+    } else {
+        quote! {
+            serde_lite::describe::Field::new(#lname, #shape).with_flags(#(#flags)|*)
+        }
+    }
+}
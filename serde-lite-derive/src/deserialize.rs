@@ -4,18 +4,117 @@ use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use syn::{
     Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields, FieldsNamed, FieldsUnnamed,
-    Generics, Ident, Variant,
+    GenericParam, Generics, Ident, Type, Variant,
 };
 
-use crate::attributes;
+use crate::{attributes, validate};
+
+/// Target of the generated code.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Flavor {
+    /// Deserialize (or DeserializeBorrowed) from an intermediate value.
+    Intermediate,
+    /// Deserialize (or DeserializeBorrowed) from an intermediate value using
+    /// the table-driven runtime for named fields (the `compact` attribute).
+    Compact,
+    /// DeserializeView from a value view.
+    View,
+}
+
+impl Flavor {
+    /// Generate path to the default deserializer for a given type.
+    fn deserializer(self, ty: &Type) -> TokenStream {
+        match self {
+            Self::Intermediate | Self::Compact => quote! {
+                <#ty as serde_lite::DeserializeBorrowed<'_>>::deserialize_borrowed
+            },
+            Self::View => quote! {
+                <#ty as serde_lite::view::DeserializeView>::deserialize_view
+            },
+        }
+    }
+
+    /// Generate a deserializer calling a given deserialize_with function.
+    ///
+    /// The function always takes an intermediate value, so value views are
+    /// converted at first.
+    fn custom_deserializer(self, path: TokenStream) -> TokenStream {
+        match self {
+            Self::Intermediate | Self::Compact => path,
+            Self::View => quote! {
+                (|__v: &dyn serde_lite::view::ValueView| {
+                    #path(&serde_lite::view::ViewRef::new(__v).to_intermediate())
+                })
+            },
+        }
+    }
+
+    /// Generate a function applicable to the result of a map lookup.
+    fn lookup_fn(self, deserializer: &TokenStream) -> TokenStream {
+        match self {
+            Self::Intermediate | Self::Compact => deserializer.clone(),
+            Self::View => quote! {
+                |__v| #deserializer(&*__v)
+            },
+        }
+    }
+
+    /// Generate code replacing the current value with the variant content.
+    fn enter_content(self) -> TokenStream {
+        match self {
+            Self::Intermediate | Self::Compact => quote! {
+                let __val = __content;
+            },
+            Self::View => quote! {
+                let __val = &*__content;
+            },
+        }
+    }
+
+    /// Generate code deserializing an array element with a given index.
+    fn element(self, index: &Literal, deserializer: &TokenStream) -> TokenStream {
+        match self {
+            Self::Intermediate | Self::Compact => quote! {
+                #deserializer(&__arr[#index])
+            },
+            Self::View => quote! {
+                __arr
+                    .element(#index)
+                    .ok_or_else(|| serde_lite::Error::invalid_value_static("array"))
+                    .and_then(|__v| #deserializer(&*__v))
+            },
+        }
+    }
+}
 
 /// Expand the derive Deserialize.
 pub fn derive_deserialize(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input, Flavor::Intermediate)
+}
+
+/// Expand the derive DeserializeView.
+pub fn derive_deserialize_view(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input, Flavor::View)
+}
+
+/// Expand a given flavor of the derive Deserialize.
+fn expand(input: proc_macro::TokenStream, flavor: Flavor) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as DeriveInput);
 
+    let flavor = if flavor == Flavor::Intermediate && attributes::has_flag(&input.attrs, "compact")
+    {
+        Flavor::Compact
+    } else {
+        flavor
+    };
+
     let expanded = match input.data {
-        Data::Struct(data) => expand_for_struct(input.ident, input.generics, data, &input.attrs),
-        Data::Enum(data) => expand_for_enum(input.ident, input.generics, data, &input.attrs),
+        Data::Struct(data) => {
+            expand_for_struct(input.ident, input.generics, data, &input.attrs, flavor)
+        }
+        Data::Enum(data) => {
+            expand_for_enum(input.ident, input.generics, data, &input.attrs, flavor)
+        }
         Data::Union(_) => panic!("derive Deserialize is not supported for union types"),
     };
 
@@ -27,33 +126,33 @@ fn expand_for_struct(
     name: Ident,
     generics: Generics,
     data: DataStruct,
-    _: &[Attribute],
+    attrs: &[Attribute],
+    flavor: Flavor,
 ) -> TokenStream {
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
     let deserialize = match data.fields {
-        Fields::Named(fields) => expand_struct_named_fields(fields),
-        Fields::Unnamed(fields) => expand_struct_unnamed_fields(fields),
+        Fields::Named(fields) => expand_struct_named_fields(fields, flavor),
+        Fields::Unnamed(fields) => expand_struct_unnamed_fields(fields, flavor),
         Fields::Unit => quote! {
             Ok(Self)
         },
     };
 
+    let deserialize = validate::validate_deserialized(attrs, deserialize);
+    let deserialize = expand_impl(&name, &generics, deserialize, flavor);
+    let serde_compat = expand_serde_compat(&name, &generics, attrs, flavor);
+
     let expanded = quote! {
-        #[allow(unused_assignments, unused_variables)]
-        impl #impl_generics serde_lite::Deserialize for #name #ty_generics #where_clause {
-            fn deserialize(__val: &serde_lite::Intermediate) -> Result<Self, serde_lite::Error> {
-                #deserialize
-            }
-        }
+        #deserialize
+
+        #serde_compat
     };
 
     expanded
 }
 
 /// Expand Deserialize for named struct fields.
-fn expand_struct_named_fields(fields: FieldsNamed) -> TokenStream {
-    let (deserialize, constructor) = deserialize_named_fields(&fields);
+fn expand_struct_named_fields(fields: FieldsNamed, flavor: Flavor) -> TokenStream {
+    let (deserialize, constructor) = deserialize_named_fields(&fields, flavor);
 
     quote! {
         #deserialize
@@ -65,8 +164,8 @@ fn expand_struct_named_fields(fields: FieldsNamed) -> TokenStream {
 }
 
 /// Expand Deserialize for unnamed struct fields.
-fn expand_struct_unnamed_fields(fields: FieldsUnnamed) -> TokenStream {
-    let (deserialize, constructor) = deserialize_unnamed_fields(&fields);
+fn expand_struct_unnamed_fields(fields: FieldsUnnamed, flavor: Flavor) -> TokenStream {
+    let (deserialize, constructor) = deserialize_unnamed_fields(&fields, flavor);
 
     quote! {
         #deserialize
@@ -81,9 +180,8 @@ fn expand_for_enum(
     generics: Generics,
     data: DataEnum,
     attrs: &[Attribute],
+    flavor: Flavor,
 ) -> TokenStream {
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
     if data.variants.is_empty() {
         panic!("enum with no variants cannot be deserialized")
     }
@@ -91,16 +189,118 @@ fn expand_for_enum(
     let deserialize = if let Some(tag) = attributes::get_enum_tag(attrs) {
         let content = attributes::get_enum_content(attrs);
 
-        expand_internally_tagged_enum(&tag, content.as_deref(), data)
+        expand_internally_tagged_enum(&tag, content.as_deref(), data, flavor)
     } else {
-        expand_externally_tagged_enum(data)
+        expand_externally_tagged_enum(data, flavor)
     };
 
+    let deserialize = validate::validate_deserialized(attrs, deserialize);
+    let deserialize = expand_impl(&name, &generics, deserialize, flavor);
+    let serde_compat = expand_serde_compat(&name, &generics, attrs, flavor);
+
+    quote! {
+        #deserialize
+
+        #serde_compat
+    }
+}
+
+/// Expand the Deserialize impl with a given body.
+///
+/// Types with lifetime parameters get a DeserializeBorrowed impl instead
+/// because they may borrow data from the intermediate representation.
+fn expand_impl(
+    name: &Ident,
+    generics: &Generics,
+    body: TokenStream,
+    flavor: Flavor,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if flavor == Flavor::View {
+        return quote! {
+            #[allow(unused_assignments, unused_variables)]
+            impl #impl_generics serde_lite::view::DeserializeView for #name #ty_generics #where_clause {
+                fn deserialize_view(
+                    __val: &dyn serde_lite::view::ValueView,
+                ) -> Result<Self, serde_lite::Error> {
+                    #body
+                }
+            }
+        };
+    }
+
+    if generics.lifetimes().next().is_none() {
+        return quote! {
+            #[allow(unused_assignments, unused_variables)]
+            impl #impl_generics serde_lite::Deserialize for #name #ty_generics #where_clause {
+                fn deserialize(__val: &serde_lite::Intermediate) -> Result<Self, serde_lite::Error> {
+                    #body
+                }
+            }
+        };
+    }
+
+    let mut borrowed_generics = generics.clone();
+
+    let lifetimes = generics
+        .lifetimes()
+        .map(|param| &param.lifetime)
+        .collect::<Vec<_>>();
+
+    borrowed_generics
+        .params
+        .insert(0, GenericParam::Lifetime(syn::parse_quote!('__de)));
+
+    borrowed_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!('__de: #(#lifetimes)+*));
+
+    let (impl_generics, _, where_clause) = borrowed_generics.split_for_impl();
+
     quote! {
         #[allow(unused_assignments, unused_variables)]
-        impl #impl_generics serde_lite::Deserialize for #name #ty_generics #where_clause {
-            fn deserialize(__val: &serde_lite::Intermediate) -> Result<Self, serde_lite::Error> {
-                #deserialize
+        impl #impl_generics serde_lite::DeserializeBorrowed<'__de> for #name #ty_generics #where_clause {
+            fn deserialize_borrowed(
+                __val: &'__de serde_lite::Intermediate<'__de>,
+            ) -> Result<Self, serde_lite::Error> {
+                #body
+            }
+        }
+    }
+}
+
+/// Expand serde::Deserialize if the serde_compat attribute is present.
+fn expand_serde_compat(
+    name: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    flavor: Flavor,
+) -> TokenStream {
+    if flavor == Flavor::View || !attributes::has_flag(attrs, "serde_compat") {
+        return TokenStream::new();
+    }
+
+    let mut serde_generics = generics.clone();
+
+    serde_generics
+        .params
+        .insert(0, GenericParam::Lifetime(syn::parse_quote!('__de)));
+
+    let (impl_generics, _, _) = serde_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics serde_lite::compat::serde::Deserialize<'__de> for #name #ty_generics #where_clause {
+            fn deserialize<__D>(__deserializer: __D) -> Result<Self, __D::Error>
+            where
+                __D: serde_lite::compat::serde::Deserializer<'__de>,
+            {
+                <serde_lite::compat::SerdeCompat<Self> as serde_lite::compat::serde::Deserialize<'__de>>::deserialize(
+                    __deserializer,
+                )
+                .map(serde_lite::compat::SerdeCompat::into_inner)
             }
         }
     }
@@ -112,6 +312,7 @@ fn expand_internally_tagged_enum(
     tag_field: &str,
     content_field: Option<&str>,
     data: DataEnum,
+    flavor: Flavor,
 ) -> TokenStream {
     let mut deserialize = TokenStream::new();
 
@@ -123,11 +324,11 @@ fn expand_internally_tagged_enum(
             // is in a sub-field and we don't know yet if the field exists.
             // Therefore, we have to use the construct_enum_variant function
             // here which will check if the field exists.
-            construct_enum_variant(&variant, content_field)
+            construct_enum_variant(&variant, content_field, flavor)
         } else {
             // Here the enum content is a part of the currently deserialized
             // object, so we don't need to check anything.
-            construct_enum_variant_with_content(&variant)
+            construct_enum_variant_with_content(&variant, flavor)
         };
 
         deserialize.extend(quote! {
@@ -171,15 +372,16 @@ fn expand_internally_tagged_enum(
 }
 
 /// Expand Deserialize for an externally tagged enum.
-fn expand_externally_tagged_enum(data: DataEnum) -> TokenStream {
+fn expand_externally_tagged_enum(data: DataEnum, flavor: Flavor) -> TokenStream {
     let mut plain = TokenStream::new();
     let mut with_content = TokenStream::new();
 
     for (index, variant) in data.variants.into_iter().enumerate() {
         let sname = attributes::get_variant_name(&variant);
         let lname = Literal::string(&sname);
-        let constructor_with_content = construct_enum_variant_with_content(&variant);
-        let constructor_without_content = construct_enum_variant_without_content(&variant, None);
+        let constructor_with_content = construct_enum_variant_with_content(&variant, flavor);
+        let constructor_without_content =
+            construct_enum_variant_without_content(&variant, None, flavor);
 
         plain.extend(quote! {
             #lname => { #constructor_without_content }
@@ -218,9 +420,13 @@ fn expand_externally_tagged_enum(data: DataEnum) -> TokenStream {
 }
 
 /// Generate code for constructing a given enum variant.
-fn construct_enum_variant(variant: &Variant, content_field: Option<&str>) -> TokenStream {
-    let with_content = construct_enum_variant_with_content(variant);
-    let without_content = construct_enum_variant_without_content(variant, content_field);
+fn construct_enum_variant(
+    variant: &Variant,
+    content_field: Option<&str>,
+    flavor: Flavor,
+) -> TokenStream {
+    let with_content = construct_enum_variant_with_content(variant, flavor);
+    let without_content = construct_enum_variant_without_content(variant, content_field, flavor);
 
     quote! {
         if let Some(__content) = __content {
@@ -233,10 +439,10 @@ fn construct_enum_variant(variant: &Variant, content_field: Option<&str>) -> Tok
 
 /// Generate code for constructing a given enum variant and use the available
 /// variant content.
-fn construct_enum_variant_with_content(variant: &Variant) -> TokenStream {
+fn construct_enum_variant_with_content(variant: &Variant, flavor: Flavor) -> TokenStream {
     match &variant.fields {
-        Fields::Named(fields) => construct_struct_enum_variant(variant, fields),
-        Fields::Unnamed(fields) => construct_tuple_enum_variant(variant, fields),
+        Fields::Named(fields) => construct_struct_enum_variant(variant, fields, flavor),
+        Fields::Unnamed(fields) => construct_tuple_enum_variant(variant, fields, flavor),
         Fields::Unit => construct_unit_enum_variant(variant),
     }
 }
@@ -245,13 +451,14 @@ fn construct_enum_variant_with_content(variant: &Variant) -> TokenStream {
 fn construct_enum_variant_without_content(
     variant: &Variant,
     content_field: Option<&str>,
+    flavor: Flavor,
 ) -> TokenStream {
     match &variant.fields {
         Fields::Named(fields) if fields.named.is_empty() => {
-            return construct_struct_enum_variant(variant, fields);
+            return construct_struct_enum_variant(variant, fields, flavor);
         }
         Fields::Unnamed(fields) if fields.unnamed.is_empty() => {
-            return construct_tuple_enum_variant(variant, fields);
+            return construct_tuple_enum_variant(variant, fields, flavor);
         }
         Fields::Unit => return construct_unit_enum_variant(variant),
         _ => (),
@@ -273,16 +480,18 @@ fn construct_enum_variant_without_content(
 }
 
 /// Generate code for constructing a given struct-like enum variant.
-fn construct_struct_enum_variant(variant: &Variant, fields: &FieldsNamed) -> TokenStream {
+fn construct_struct_enum_variant(
+    variant: &Variant,
+    fields: &FieldsNamed,
+    flavor: Flavor,
+) -> TokenStream {
     let mut init = TokenStream::new();
 
     if !fields.named.is_empty() {
-        init.extend(quote! {
-            let __val = __content;
-        });
+        init.extend(flavor.enter_content());
     }
 
-    let (deserialize, constructor) = deserialize_named_fields(fields);
+    let (deserialize, constructor) = deserialize_named_fields(fields, flavor);
 
     let ident = &variant.ident;
 
@@ -297,16 +506,18 @@ fn construct_struct_enum_variant(variant: &Variant, fields: &FieldsNamed) -> Tok
 }
 
 /// Generate code for constructing a given tuple-like enum variant.
-fn construct_tuple_enum_variant(variant: &Variant, fields: &FieldsUnnamed) -> TokenStream {
+fn construct_tuple_enum_variant(
+    variant: &Variant,
+    fields: &FieldsUnnamed,
+    flavor: Flavor,
+) -> TokenStream {
     let mut init = TokenStream::new();
 
     if !fields.unnamed.is_empty() {
-        init.extend(quote! {
-            let __val = __content;
-        });
+        init.extend(flavor.enter_content());
     }
 
-    let (deserialize, constructor) = deserialize_unnamed_fields(fields);
+    let (deserialize, constructor) = deserialize_unnamed_fields(fields, flavor);
 
     let ident = &variant.ident;
 
@@ -328,7 +539,11 @@ fn construct_unit_enum_variant(variant: &Variant) -> TokenStream {
 }
 
 /// Generate code for deserializing given named fields.
-fn deserialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
+fn deserialize_named_fields(fields: &FieldsNamed, flavor: Flavor) -> (TokenStream, TokenStream) {
+    if flavor == Flavor::Compact && !fields.named.is_empty() {
+        return deserialize_named_fields_compact(fields);
+    }
+
     let mut deserialize = TokenStream::new();
     let mut constructor = TokenStream::new();
 
@@ -350,11 +565,9 @@ fn deserialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream)
         let deserializer = attributes::get_field_deserializer(field)
             .map(|path| TokenStream::from_str(&path))
             .map(|res| res.expect("invalid path given for the deserialize_with attribute"))
-            .unwrap_or_else(|| {
-                quote! {
-                    <#ty as serde_lite::Deserialize>::deserialize
-                }
-            });
+            .map(|path| flavor.custom_deserializer(path))
+            .unwrap_or_else(|| flavor.deserializer(ty));
+        let lookup = flavor.lookup_fn(&deserializer);
         let skip = attributes::has_flag(&field.attrs, "skip")
             || attributes::has_flag(&field.attrs, "skip_deserializing");
 
@@ -377,7 +590,7 @@ fn deserialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream)
             deserialize.extend(quote! {
                 let #name = __obj
                     .get(#lname)
-                    .map(#deserializer)
+                    .map(#lookup)
                     .unwrap_or_else(|| Ok(Default::default()))
                     .map_err(|err| __field_errors.push(serde_lite::NamedFieldError::new_static(#lname, err)))
                     .ok();
@@ -386,13 +599,21 @@ fn deserialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream)
             deserialize.extend(quote! {
                 let #name = __obj
                     .get(#lname)
-                    .map(#deserializer)
+                    .map(#lookup)
                     .unwrap_or_else(|| Err(serde_lite::Error::MissingField))
                     .map_err(|err| __field_errors.push(serde_lite::NamedFieldError::new_static(#lname, err)))
                     .ok();
             });
         }
 
+        if let Some(validation) = validate::validate_field(field, &lname) {
+            deserialize.extend(quote! {
+                if let Some(__field) = #name.as_ref() {
+                    #validation
+                }
+            });
+        }
+
         if skip {
             constructor.extend(quote! {
                 #name,
@@ -415,12 +636,137 @@ fn deserialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream)
     (deserialize, constructor)
 }
 
+/// Generate code for deserializing given named fields using the
+/// table-driven runtime.
+fn deserialize_named_fields_compact(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
+    let mut deserialize = TokenStream::new();
+    let mut constructor = TokenStream::new();
+    let mut table = TokenStream::new();
+    let mut targets = TokenStream::new();
+    let mut validate = TokenStream::new();
+
+    for field in &fields.named {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let sname = attributes::get_field_name(field);
+        let lname = Literal::string(&sname);
+        let deserializer = attributes::get_field_deserializer(field)
+            .map(|path| TokenStream::from_str(&path))
+            .map(|res| res.expect("invalid path given for the deserialize_with attribute"));
+        let flatten = attributes::has_flag(&field.attrs, "flatten");
+        let default = attributes::has_flag(&field.attrs, "default");
+
+        if attributes::has_flag(&field.attrs, "skip")
+            || attributes::has_flag(&field.attrs, "skip_deserializing")
+        {
+            constructor.extend(quote! {
+                #name: Default::default(),
+            });
+
+            continue;
+        }
+
+        let flags = match (flatten, default) {
+            (false, false) => quote! { 0 },
+            (true, false) => quote! { serde_lite::compact::Field::FLATTEN },
+            (false, true) => quote! { serde_lite::compact::Field::DEFAULT },
+            (true, true) => quote! {
+                serde_lite::compact::Field::FLATTEN | serde_lite::compact::Field::DEFAULT
+            },
+        };
+
+        table.extend(quote! {
+            serde_lite::compact::Field::new(#lname, #flags),
+        });
+
+        let value = if let Some(path) = deserializer {
+            deserialize.extend(quote! {
+                let mut #name = serde_lite::compact::DeserializeWith::new(|__v| #path(__v));
+            });
+
+            quote! { #name.into_inner() }
+        } else {
+            deserialize.extend(quote! {
+                let mut #name: Option<#ty> = None;
+            });
+
+            quote! { #name }
+        };
+
+        targets.extend(quote! {
+            &mut #name as &mut dyn serde_lite::compact::DeserializeField<'_>,
+        });
+
+        let value = if let Some(validation) = validate::validate_field(field, &lname) {
+            let value = if default {
+                quote! { Some(#value.unwrap_or_default()) }
+            } else {
+                value
+            };
+
+            // the value must be taken out of the target before the validation
+            validate.extend(quote! {
+                let #name = #value;
+
+                if let Some(__field) = #name.as_ref() {
+                    #validation
+                }
+            });
+
+            quote! { unsafe { #name.unwrap_unchecked() } }
+        } else if default {
+            quote! { #value.unwrap_or_default() }
+        } else {
+            quote! { unsafe { #value.unwrap_unchecked() } }
+        };
+
+        constructor.extend(quote! {
+            #name: #value,
+        });
+    }
+
+    deserialize.extend(quote! {
+        static __FIELDS: &[serde_lite::compact::Field] = &[#table];
+    });
+
+    if validate.is_empty() {
+        deserialize.extend(quote! {
+            serde_lite::compact::deserialize_fields(__FIELDS, &mut [#targets], __val)?;
+        });
+    } else {
+        // validation errors are reported together with the other field
+        // errors
+        deserialize.extend(quote! {
+            let mut __field_errors = match serde_lite::compact::deserialize_fields(
+                __FIELDS,
+                &mut [#targets],
+                __val,
+            ) {
+                Ok(()) => serde_lite::ErrorList::new(),
+                Err(serde_lite::Error::NamedFieldErrors(errors)) => errors,
+                Err(err) => return Err(err),
+            };
+
+            #validate
+
+            if !__field_errors.is_empty() {
+                return Err(serde_lite::Error::NamedFieldErrors(__field_errors));
+            }
+        });
+    }
+
+    (deserialize, constructor)
+}
+
 /// Generate code for deserializing given unnamed fields.
-fn deserialize_unnamed_fields(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+fn deserialize_unnamed_fields(
+    fields: &FieldsUnnamed,
+    flavor: Flavor,
+) -> (TokenStream, TokenStream) {
     match fields.unnamed.len() {
         0 => deserialize_unnamed_fields_0(),
-        1 => deserialize_unnamed_fields_1(fields),
-        _ => deserialize_unnamed_fields_n(fields),
+        1 => deserialize_unnamed_fields_1(fields, flavor),
+        _ => deserialize_unnamed_fields_n(fields, flavor),
     }
 }
 
@@ -435,16 +781,20 @@ fn deserialize_unnamed_fields_0() -> (TokenStream, TokenStream) {
 
 /// Generate code for deserializing given unnamed fields where the actual
 /// number of fields is one (e.g. single-element tuple struct).
-fn deserialize_unnamed_fields_1(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+fn deserialize_unnamed_fields_1(
+    fields: &FieldsUnnamed,
+    flavor: Flavor,
+) -> (TokenStream, TokenStream) {
     let mut deserialize = TokenStream::new();
     let mut constructor = TokenStream::new();
 
     let field = &fields.unnamed[0];
     let ty = &field.ty;
     let name = Ident::new("f0", Span::call_site());
+    let deserializer = flavor.deserializer(ty);
 
     deserialize.extend(quote! {
-        let #name = <#ty as serde_lite::Deserialize>::deserialize(__val)?;
+        let #name = #deserializer(__val)?;
     });
 
     constructor.extend(quote! {
@@ -456,7 +806,10 @@ fn deserialize_unnamed_fields_1(fields: &FieldsUnnamed) -> (TokenStream, TokenSt
 
 /// Generate code for deserializing given unnamed fields where the actual
 /// number of fields is greater than one (e.g. multiple-element tuple struct).
-fn deserialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+fn deserialize_unnamed_fields_n(
+    fields: &FieldsUnnamed,
+    flavor: Flavor,
+) -> (TokenStream, TokenStream) {
     let mut deserialize = TokenStream::new();
     let mut constructor = TokenStream::new();
 
@@ -479,9 +832,11 @@ fn deserialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenSt
         let sname = format!("f{}", index);
         let name = Ident::new(&sname, Span::call_site());
         let lindex = Literal::usize_unsuffixed(index);
+        let deserializer = flavor.deserializer(ty);
+        let element = flavor.element(&lindex, &deserializer);
 
         deserialize.extend(quote! {
-            let #name = <#ty as serde_lite::Deserialize>::deserialize(&__arr[#lindex])
+            let #name = #element
                 .map_err(|err| __field_errors.push(serde_lite::UnnamedFieldError::new(#lindex, err)))
                 .ok();
         });
@@ -1,7 +1,10 @@
 mod attributes;
+mod describe;
 mod deserialize;
+mod pattern;
 mod serialize;
 mod update;
+mod validate;
 
 use proc_macro::TokenStream;
 
@@ -15,6 +18,16 @@ pub fn derive_deserialize(input: TokenStream) -> TokenStream {
     deserialize::derive_deserialize(input)
 }
 
+#[proc_macro_derive(DeserializeView, attributes(serde))]
+pub fn derive_deserialize_view(input: TokenStream) -> TokenStream {
+    deserialize::derive_deserialize_view(input)
+}
+
+#[proc_macro_derive(Describe, attributes(serde))]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    describe::derive_describe(input)
+}
+
 #[proc_macro_derive(Update, attributes(serde))]
 pub fn derive_update(input: TokenStream) -> TokenStream {
     update::derive_update(input)
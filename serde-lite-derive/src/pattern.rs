@@ -0,0 +1,221 @@
+//! Syntax check of patterns given by the `pattern` validation rule.
+//!
+//! The checker follows the grammar of `serde_lite::pattern::Pattern`, so
+//! that invalid patterns are reported at compile time. The limits of
+//! compiled patterns are checked by the runtime only.
+
+/// Maximum nesting of groups (the same as in the runtime).
+const MAX_NESTING: usize = 64;
+
+/// Check the syntax of a given pattern.
+pub fn check(pattern: &str) -> Result<(), &'static str> {
+    let mut checker = Checker {
+        input: pattern.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+
+    checker.check_alternatives()?;
+
+    if checker.pos < checker.input.len() {
+        return Err("unmatched parenthesis");
+    }
+
+    Ok(())
+}
+
+/// Pattern syntax checker.
+struct Checker {
+    input: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Checker {
+    /// Check alternatives until the end of the input or a closing
+    /// parenthesis.
+    fn check_alternatives(&mut self) -> Result<(), &'static str> {
+        self.check_sequence()?;
+
+        while self.peek() == Some('|') {
+            self.pos += 1;
+
+            self.check_sequence()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a sequence of atoms until the end of the input, an alternative
+    /// separator or a closing parenthesis.
+    fn check_sequence(&mut self) -> Result<(), &'static str> {
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+
+            self.check_atom()?;
+            self.check_quantifier()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a single atom.
+    fn check_atom(&mut self) -> Result<(), &'static str> {
+        match self.next().ok_or("unexpected end")? {
+            '[' => self.check_class(),
+            '(' => {
+                if self.peek() == Some('?') {
+                    self.pos += 1;
+
+                    if self.next() != Some(':') {
+                        return Err("unsupported group");
+                    }
+                }
+
+                if self.depth >= MAX_NESTING {
+                    return Err("too deeply nested");
+                }
+
+                self.depth += 1;
+
+                self.check_alternatives()?;
+
+                self.depth -= 1;
+
+                if self.next() != Some(')') {
+                    return Err("unmatched parenthesis");
+                }
+
+                Ok(())
+            }
+            '\\' => self.check_escape().map(|_| ()),
+            '*' | '+' | '?' | '{' => Err("nothing to repeat"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an optional quantifier.
+    fn check_quantifier(&mut self) -> Result<(), &'static str> {
+        match self.peek() {
+            Some('*' | '+' | '?') => {
+                self.pos += 1;
+            }
+            Some('{') => {
+                self.pos += 1;
+
+                let min = self.parse_number()?;
+
+                match self.next() {
+                    Some('}') => (),
+                    Some(',') if self.peek() == Some('}') => {
+                        self.pos += 1;
+                    }
+                    Some(',') => {
+                        let max = self.parse_number()?;
+
+                        if self.next() != Some('}') || max < min {
+                            return Err("invalid quantifier");
+                        }
+                    }
+                    _ => return Err("invalid quantifier"),
+                }
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Parse a decimal number.
+    fn parse_number(&mut self) -> Result<usize, &'static str> {
+        let start = self.pos;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.input[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| "invalid quantifier")
+    }
+
+    /// Check a character class (after the opening bracket).
+    fn check_class(&mut self) -> Result<(), &'static str> {
+        if self.peek() == Some('^') {
+            self.pos += 1;
+        }
+
+        let mut empty = true;
+
+        loop {
+            let c = self.next().ok_or("unterminated character class")?;
+
+            // a closing bracket at the beginning is a literal
+            if c == ']' && !empty {
+                return Ok(());
+            }
+
+            empty = false;
+
+            let start = if c == '\\' {
+                self.check_escape()?
+            } else {
+                Some(c)
+            };
+
+            let Some(start) = start else {
+                continue;
+            };
+
+            if self.peek() == Some('-') && self.input.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+
+                let end = match self.next() {
+                    Some('\\') => self.check_escape()?,
+                    Some(c) => Some(c),
+                    None => return Err("unterminated character class"),
+                };
+
+                match end {
+                    Some(end) if start <= end => (),
+                    _ => return Err("invalid character range"),
+                }
+            }
+        }
+    }
+
+    /// Check an escape sequence (after the backslash) and return the
+    /// escaped character (if it is not a class escape).
+    fn check_escape(&mut self) -> Result<Option<char>, &'static str> {
+        let res = match self.next().ok_or("unexpected end")? {
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' => None,
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            c if c.is_ascii_alphanumeric() => return Err("unsupported escape sequence"),
+            c => Some(c),
+        };
+
+        Ok(res)
+    }
+
+    /// Peek at the next character.
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.pos).copied()
+    }
+
+    /// Take the next character.
+    fn next(&mut self) -> Option<char> {
+        let res = self.peek();
+
+        if res.is_some() {
+            self.pos += 1;
+        }
+
+        res
+    }
+}
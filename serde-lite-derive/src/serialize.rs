@@ -3,7 +3,7 @@ use std::str::FromStr;
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use syn::{
-    Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields, FieldsNamed, FieldsUnnamed,
+    Attribute, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed,
     Generics, Ident, Variant,
 };
 
@@ -27,52 +27,93 @@ fn expand_for_struct(
     name: Ident,
     generics: Generics,
     data: DataStruct,
-    _: &[Attribute],
+    attrs: &[Attribute],
 ) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let serialize = match data.fields {
-        Fields::Named(fields) => expand_struct_named_fields(&fields),
+    let compact = attributes::has_flag(attrs, "compact");
+
+    let (serialize, serialize_to) = match data.fields {
+        Fields::Named(fields) => expand_struct_named_fields(&fields, compact),
         Fields::Unnamed(fields) => expand_struct_unnamed_fields(&fields),
-        Fields::Unit => quote! {
-            Ok(serde_lite::Intermediate::None)
-        },
+        Fields::Unit => (
+            quote! {
+                Ok(serde_lite::Intermediate::None)
+            },
+            quote! {
+                __emitter.scalar(serde_lite::emit::Scalar::None)
+            },
+        ),
     };
 
+    let serde_compat = expand_serde_compat(&name, &generics, attrs);
+
     quote! {
         #[allow(unused_assignments, unused_variables)]
         impl #impl_generics serde_lite::Serialize for #name #ty_generics #where_clause {
-            fn serialize(&self) -> Result<serde_lite::Intermediate, serde_lite::Error> {
+            fn serialize(&self) -> Result<serde_lite::Intermediate<'_>, serde_lite::Error> {
                 #serialize
             }
+
+            fn serialize_to(
+                &self,
+                __emitter: &mut dyn serde_lite::emit::Emitter,
+            ) -> Result<(), serde_lite::Error> {
+                #serialize_to
+            }
         }
+
+        #serde_compat
     }
 }
 
 /// Expand Serialize for given named struct fields.
-fn expand_struct_named_fields(fields: &FieldsNamed) -> TokenStream {
-    let (deconstructor, serialize) = serialize_named_fields(fields);
+fn expand_struct_named_fields(fields: &FieldsNamed, compact: bool) -> (TokenStream, TokenStream) {
+    let (deconstructor, serialize, serialize_to) = serialize_named_fields(fields, compact);
 
-    quote! {
+    let serialize = quote! {
         let Self { #deconstructor } = self;
 
         #serialize
 
         Ok(serde_lite::Intermediate::Map(__map))
-    }
+    };
+
+    let serialize_to = quote! {
+        let Self { #deconstructor } = self;
+
+        #serialize_to
+    };
+
+    (serialize, serialize_to)
 }
 
 /// Expand Serialize for given unnamed struct fields.
-fn expand_struct_unnamed_fields(fields: &FieldsUnnamed) -> TokenStream {
-    let (deconstructor, serialize) = serialize_unnamed_fields(fields);
+fn expand_struct_unnamed_fields(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+    let (deconstructor, serialize, serialize_to) = serialize_unnamed_fields(fields);
 
-    quote! {
+    let serialize = quote! {
         let Self(#deconstructor) = self;
 
         #serialize
 
         Ok(__val)
-    }
+    };
+
+    let serialize_to = quote! {
+        let Self(#deconstructor) = self;
+
+        #serialize_to
+    };
+
+    (serialize, serialize_to)
+}
+
+/// Enum tagging.
+enum Tagging {
+    External,
+    Internal(String),
+    Adjacent(String, String),
 }
 
 /// Expand Serialize for a given enum.
@@ -88,10 +129,26 @@ fn expand_for_enum(
         panic!("enum with no variants cannot be serialized")
     }
 
+    let tagging = if let Some(tag) = attributes::get_enum_tag(attrs) {
+        if let Some(content) = attributes::get_enum_content(attrs) {
+            Tagging::Adjacent(tag, content)
+        } else {
+            Tagging::Internal(tag)
+        }
+    } else {
+        Tagging::External
+    };
+
+    let compact = attributes::has_flag(attrs, "compact");
+
     let mut serialize_variants = TokenStream::new();
+    let mut serialize_to_variants = TokenStream::new();
 
     for variant in &data.variants {
-        serialize_variants.extend(serialize_enum_variant(variant));
+        let (serialize, serialize_to) = serialize_enum_variant(variant, &tagging, compact);
+
+        serialize_variants.extend(serialize);
+        serialize_to_variants.extend(serialize_to);
     }
 
     let mut serialize = quote! {
@@ -100,22 +157,58 @@ fn expand_for_enum(
         };
     };
 
-    if let Some(tag) = attributes::get_enum_tag(attrs) {
-        if let Some(content) = attributes::get_enum_content(attrs) {
-            serialize.extend(expand_adjacently_tagged_enum(&tag, &content));
-        } else {
-            serialize.extend(expand_internally_tagged_enum(&tag));
+    serialize.extend(match &tagging {
+        Tagging::External => expand_externally_tagged_enum(),
+        Tagging::Internal(tag) => expand_internally_tagged_enum(tag),
+        Tagging::Adjacent(tag, content) => expand_adjacently_tagged_enum(tag, content),
+    });
+
+    let serialize_to = quote! {
+        match self {
+            #serialize_to_variants
         }
-    } else {
-        serialize.extend(expand_externally_tagged_enum());
-    }
+    };
+
+    let serde_compat = expand_serde_compat(&name, &generics, attrs);
 
     quote! {
         #[allow(unused_assignments, unused_variables)]
         impl #impl_generics serde_lite::Serialize for #name #ty_generics #where_clause {
-            fn serialize(&self) -> Result<serde_lite::Intermediate, serde_lite::Error> {
+            fn serialize(&self) -> Result<serde_lite::Intermediate<'_>, serde_lite::Error> {
                 #serialize
             }
+
+            fn serialize_to(
+                &self,
+                __emitter: &mut dyn serde_lite::emit::Emitter,
+            ) -> Result<(), serde_lite::Error> {
+                #serialize_to
+            }
+        }
+
+        #serde_compat
+    }
+}
+
+/// Expand serde::Serialize if the serde_compat attribute is present.
+fn expand_serde_compat(name: &Ident, generics: &Generics, attrs: &[Attribute]) -> TokenStream {
+    if !attributes::has_flag(attrs, "serde_compat") {
+        return TokenStream::new();
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics serde_lite::compat::serde::Serialize for #name #ty_generics #where_clause {
+            fn serialize<__S>(&self, __serializer: __S) -> Result<__S::Ok, __S::Error>
+            where
+                __S: serde_lite::compat::serde::Serializer,
+            {
+                serde_lite::compat::serde::Serialize::serialize(
+                    &serde_lite::compat::SerdeCompat(self),
+                    __serializer,
+                )
+            }
         }
     }
 }
@@ -134,7 +227,7 @@ fn expand_internally_tagged_enum(tag: &str) -> TokenStream {
             );
 
             Ok(serde_lite::Intermediate::Map(__map))
-        } else if let serde_lite::Intermediate::Map(__map) = __content {
+        } else if let Ok(__map) = __content.into_map() {
             let mut __res = serde_lite::Map::with_capacity(__map.len() + 1);
 
             // NOTE: The tag must be inserted before the content because some
@@ -189,63 +282,185 @@ fn expand_externally_tagged_enum() -> TokenStream {
 }
 
 /// Generate code for serializing a given enum variant.
-fn serialize_enum_variant(variant: &Variant) -> TokenStream {
+///
+/// The first returned token stream is a match arm of the serialize method,
+/// the second one is a match arm of the serialize_to method.
+fn serialize_enum_variant(
+    variant: &Variant,
+    tagging: &Tagging,
+    compact: bool,
+) -> (TokenStream, TokenStream) {
     match &variant.fields {
-        Fields::Named(fields) => serialize_struct_enum_variant(variant, fields),
-        Fields::Unnamed(fields) => serialize_tuple_enum_variant(variant, fields),
-        Fields::Unit => serialize_unit_enum_variant(variant),
+        Fields::Named(fields) => serialize_struct_enum_variant(variant, fields, tagging, compact),
+        Fields::Unnamed(fields) => serialize_tuple_enum_variant(variant, fields, tagging),
+        Fields::Unit => serialize_unit_enum_variant(variant, tagging),
     }
 }
 
 /// Generate code for serializing a given struct-like enum variant.
-fn serialize_struct_enum_variant(variant: &Variant, fields: &FieldsNamed) -> TokenStream {
-    let (deconstructor, serialize) = serialize_named_fields(fields);
+fn serialize_struct_enum_variant(
+    variant: &Variant,
+    fields: &FieldsNamed,
+    tagging: &Tagging,
+    compact: bool,
+) -> (TokenStream, TokenStream) {
+    let (deconstructor, serialize, serialize_to) = serialize_named_fields(fields, compact);
 
     let ident = &variant.ident;
     let sname = attributes::get_variant_name(variant);
     let lname = Literal::string(&sname);
 
-    quote! {
+    let serialize_to = emit_tagged_variant(&lname, serialize_to, tagging);
+
+    let serialize = quote! {
         Self::#ident { #deconstructor } => {
             #serialize
 
             (#lname, serde_lite::Intermediate::Map(__map))
         }
-    }
+    };
+
+    let serialize_to = quote! {
+        Self::#ident { #deconstructor } => {
+            #serialize_to
+        }
+    };
+
+    (serialize, serialize_to)
 }
 
 /// Generate code for serializing a given tuple-like enum variant.
-fn serialize_tuple_enum_variant(variant: &Variant, fields: &FieldsUnnamed) -> TokenStream {
-    let (deconstructor, serialize) = serialize_unnamed_fields(fields);
+fn serialize_tuple_enum_variant(
+    variant: &Variant,
+    fields: &FieldsUnnamed,
+    tagging: &Tagging,
+) -> (TokenStream, TokenStream) {
+    let (deconstructor, serialize, serialize_to) = serialize_unnamed_fields(fields);
 
     let ident = &variant.ident;
     let sname = attributes::get_variant_name(variant);
     let lname = Literal::string(&sname);
 
-    quote! {
+    let serialize_to = emit_tagged_variant(&lname, serialize_to, tagging);
+
+    let serialize = quote! {
         Self::#ident ( #deconstructor ) => {
             #serialize
 
             (#lname, __val)
         }
-    }
+    };
+
+    let serialize_to = quote! {
+        Self::#ident ( #deconstructor ) => {
+            #serialize_to
+        }
+    };
+
+    (serialize, serialize_to)
 }
 
 /// Generate code for serializing a given enum variant.
-fn serialize_unit_enum_variant(variant: &Variant) -> TokenStream {
+fn serialize_unit_enum_variant(variant: &Variant, tagging: &Tagging) -> (TokenStream, TokenStream) {
     let ident = &variant.ident;
     let sname = attributes::get_variant_name(variant);
     let lname = Literal::string(&sname);
 
-    quote! {
+    let serialize_to = if let Tagging::External = tagging {
+        quote! {
+            __emitter.scalar(serde_lite::emit::Scalar::String(#lname))
+        }
+    } else {
+        let content = quote! {
+            __emitter.scalar(serde_lite::emit::Scalar::None)
+        };
+
+        emit_tagged_variant(&lname, content, tagging)
+    };
+
+    let serialize = quote! {
         Self::#ident => {
             (#lname, serde_lite::Intermediate::None)
         }
+    };
+
+    let serialize_to = quote! {
+        Self::#ident => {
+            #serialize_to
+        }
+    };
+
+    (serialize, serialize_to)
+}
+
+/// Generate code for emitting a given enum variant content together with
+/// the variant tag.
+fn emit_tagged_variant(lname: &Literal, content: TokenStream, tagging: &Tagging) -> TokenStream {
+    match tagging {
+        Tagging::External => quote! {
+            let mut __tagged = serde_lite::emit::TaggedEmitter::new(__emitter, #lname);
+
+            {
+                let __emitter: &mut dyn serde_lite::emit::Emitter = &mut __tagged;
+
+                #content
+            }?;
+
+            __tagged.finish()
+        },
+        Tagging::Internal(tag) => {
+            let ltag = Literal::string(tag);
+
+            quote! {
+                __emitter.begin_map(None)?;
+                __emitter.key(#ltag)?;
+                __emitter.scalar(serde_lite::emit::Scalar::String(#lname))?;
+
+                {
+                    let __emitter: &mut dyn serde_lite::emit::Emitter =
+                        &mut serde_lite::emit::FlatEmitter::new(
+                            __emitter,
+                            "enum cannot be tagged internally",
+                        );
+
+                    #content
+                }?;
+
+                __emitter.end_map()
+            }
+        }
+        Tagging::Adjacent(tag, cont) => {
+            let ltag = Literal::string(tag);
+            let lcont = Literal::string(cont);
+
+            quote! {
+                __emitter.begin_map(Some(2))?;
+                __emitter.key(#ltag)?;
+                __emitter.scalar(serde_lite::emit::Scalar::String(#lname))?;
+                __emitter.key(#lcont)?;
+
+                {
+                    #content
+                }?;
+
+                __emitter.end_map()
+            }
+        }
     }
 }
 
 /// Generate code for serializing given named field.
-fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
+///
+/// The returned token streams are the fields deconstructor, code for the
+/// serialize method and code for the serialize_to method.
+fn serialize_named_fields(
+    fields: &FieldsNamed,
+    compact: bool,
+) -> (TokenStream, TokenStream, TokenStream) {
+    if compact && !fields.named.is_empty() {
+        return serialize_named_fields_compact(fields);
+    }
+
     let mut deconstructor = TokenStream::new();
 
     let len = Literal::usize_unsuffixed(fields.named.len());
@@ -255,19 +470,39 @@ fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
         let mut __field_errors = serde_lite::ErrorList::new();
     };
 
+    let mut serialize_to = TokenStream::new();
+
+    let mut has_flatten = false;
+
+    // the number of map entries is known in advance only if all fields are
+    // always serialized as exactly one entry
+    let mut exact_len = true;
+
     for field in &fields.named {
         let name = &field.ident;
         let ty = &field.ty;
         let sname = attributes::get_field_name(field);
         let lname = Literal::string(&sname);
-        let serializer = attributes::get_field_serializer(field)
+        let custom_serializer = attributes::get_field_serializer(field)
             .map(|path| TokenStream::from_str(&path))
-            .map(|res| res.expect("invalid path given for the serialize_with attribute"))
-            .unwrap_or_else(|| {
-                quote! {
-                    <#ty as serde_lite::Serialize>::serialize
-                }
-            });
+            .map(|res| res.expect("invalid path given for the serialize_with attribute"));
+        let non_finite_policy = attributes::get_field_non_finite_policy(field);
+        let streaming = custom_serializer.is_none() && non_finite_policy.is_none();
+        let serializer = custom_serializer.unwrap_or_else(|| {
+            quote! {
+                <#ty as serde_lite::Serialize>::serialize
+            }
+        });
+        let serializer = if let Some(policy) = non_finite_policy {
+            let policy = Ident::new(policy, Span::call_site());
+
+            quote! {
+                (|__v| serde_lite::float::NonFinitePolicy::#policy.serialize_with(__v, #serializer))
+            }
+        } else {
+            serializer
+        };
+        let flatten = attributes::has_flag(&field.attrs, "flatten");
         let skip = attributes::has_flag(&field.attrs, "skip")
             || attributes::has_flag(&field.attrs, "skip_serializing");
 
@@ -277,11 +512,11 @@ fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
 
         let serialize_field = if skip {
             continue;
-        } else if attributes::has_flag(&field.attrs, "flatten") {
+        } else if flatten {
             quote! {
-                match #serializer(#name) {
-                    Ok(serde_lite::Intermediate::Map(inner)) => __map.extend(inner),
-                    Ok(_) => {
+                match #serializer(#name).map(serde_lite::Intermediate::into_map) {
+                    Ok(Ok(inner)) => __map.extend(inner),
+                    Ok(Err(_)) => {
                         __field_errors.push(serde_lite::NamedFieldError::new_static(
                             #lname,
                             serde_lite::Error::custom_static("field cannot be flattened"),
@@ -303,6 +538,33 @@ fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
             }
         };
 
+        // emitting a field value cannot be undone, so the field value
+        // needs to be serialized before emitting the key if a custom
+        // serializer is used
+        let emit_field = if streaming {
+            quote! {
+                __emitter.key(#lname)?;
+
+                <#ty as serde_lite::Serialize>::serialize_to(#name, __emitter).map_err(|err| {
+                    serde_lite::Error::from(serde_lite::NamedFieldError::new_static(#lname, err))
+                })?;
+            }
+        } else {
+            quote! {
+                let __v = #serializer(#name).map_err(|err| {
+                    serde_lite::Error::from(serde_lite::NamedFieldError::new_static(#lname, err))
+                })?;
+
+                __emitter.key(#lname)?;
+
+                serde_lite::emit::emit(&__v, __emitter).map_err(|err| {
+                    serde_lite::Error::from(serde_lite::NamedFieldError::new_static(#lname, err))
+                })?;
+            }
+        };
+
+        has_flatten |= flatten;
+
         if let Some(path) = attributes::get_skip_field_serializing_if(field) {
             let path = TokenStream::from_str(&path)
                 .expect("invalid path given for the skip_serializing_if attribute");
@@ -312,8 +574,21 @@ fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
                     #serialize_field
                 }
             });
+
+            serialize_to.extend(quote! {
+                if !#path(#name) {
+                    #emit_field
+                }
+            });
+
+            exact_len = false;
         } else {
             serialize.extend(serialize_field);
+            serialize_to.extend(quote! {
+                {
+                    #emit_field
+                }
+            });
         }
     }
 
@@ -323,21 +598,164 @@ fn serialize_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
         }
     });
 
-    (deconstructor, serialize)
+    let len = if exact_len {
+        let len = Literal::usize_unsuffixed(
+            fields
+                .named
+                .iter()
+                .filter(|field| {
+                    !attributes::has_flag(&field.attrs, "skip")
+                        && !attributes::has_flag(&field.attrs, "skip_serializing")
+                })
+                .count(),
+        );
+
+        quote! { Some(#len) }
+    } else {
+        quote! { None }
+    };
+
+    // flattened fields may collide with other fields, so the map is built
+    // in advance to resolve the collisions in the same way as serialize
+    let serialize_to = if has_flatten {
+        quote! {
+            #serialize
+
+            serde_lite::emit::emit(&serde_lite::Intermediate::Map(__map), __emitter)
+        }
+    } else {
+        quote! {
+            __emitter.begin_map(#len)?;
+
+            #serialize_to
+
+            __emitter.end_map()
+        }
+    };
+
+    (deconstructor, serialize, serialize_to)
+}
+
+/// Generate code for serializing given named fields using the table-driven
+/// runtime.
+///
+/// The returned token streams are the same as for `serialize_named_fields`.
+fn serialize_named_fields_compact(fields: &FieldsNamed) -> (TokenStream, TokenStream, TokenStream) {
+    let mut deconstructor = TokenStream::new();
+    let mut table = TokenStream::new();
+    let mut values = TokenStream::new();
+
+    for field in &fields.named {
+        let name = &field.ident;
+        let ty = &field.ty;
+        let sname = attributes::get_field_name(field);
+        let lname = Literal::string(&sname);
+        let serializer = attributes::get_field_serializer(field)
+            .map(|path| TokenStream::from_str(&path))
+            .map(|res| res.expect("invalid path given for the serialize_with attribute"));
+        let non_finite_policy = attributes::get_field_non_finite_policy(field);
+
+        deconstructor.extend(quote! {
+            #name,
+        });
+
+        if attributes::has_flag(&field.attrs, "skip")
+            || attributes::has_flag(&field.attrs, "skip_serializing")
+        {
+            continue;
+        }
+
+        let flags = if attributes::has_flag(&field.attrs, "flatten") {
+            quote! { serde_lite::compact::Field::FLATTEN }
+        } else {
+            quote! { 0 }
+        };
+
+        table.extend(quote! {
+            serde_lite::compact::Field::new(#lname, #flags),
+        });
+
+        // fields with a custom serializer are serialized in advance
+        let value = if serializer.is_none() && non_finite_policy.is_none() {
+            quote! {
+                serde_lite::compact::FieldValue::Serialize(#name)
+            }
+        } else {
+            let serializer = serializer.unwrap_or_else(|| {
+                quote! {
+                    <#ty as serde_lite::Serialize>::serialize
+                }
+            });
+
+            let serializer = if let Some(policy) = non_finite_policy {
+                let policy = Ident::new(policy, Span::call_site());
+
+                quote! {
+                    serde_lite::float::NonFinitePolicy::#policy.serialize_with(#name, #serializer)
+                }
+            } else {
+                quote! {
+                    #serializer(#name)
+                }
+            };
+
+            quote! {
+                serde_lite::compact::FieldValue::Serialized(#serializer)
+            }
+        };
+
+        if let Some(path) = attributes::get_skip_field_serializing_if(field) {
+            let path = TokenStream::from_str(&path)
+                .expect("invalid path given for the skip_serializing_if attribute");
+
+            values.extend(quote! {
+                if #path(#name) {
+                    serde_lite::compact::FieldValue::Skip
+                } else {
+                    #value
+                },
+            });
+        } else {
+            values.extend(quote! {
+                #value,
+            });
+        }
+    }
+
+    let table = quote! {
+        static __FIELDS: &[serde_lite::compact::Field] = &[#table];
+    };
+
+    let serialize = quote! {
+        #table
+
+        let __map = serde_lite::compact::serialize_fields(__FIELDS, &mut [#values])?;
+    };
+
+    let serialize_to = quote! {
+        #table
+
+        serde_lite::compact::serialize_fields_to(__FIELDS, &mut [#values], __emitter)
+    };
+
+    (deconstructor, serialize, serialize_to)
 }
 
 /// Generate code for serializing given unnamed fields.
-fn serialize_unnamed_fields(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+///
+/// The returned token streams are the fields deconstructor, code for the
+/// serialize method and code for the serialize_to method.
+fn serialize_unnamed_fields(fields: &FieldsUnnamed) -> (TokenStream, TokenStream, TokenStream) {
     match fields.unnamed.len() {
         0 => serialize_unnamed_fields_0(),
-        1 => serialize_unnamed_fields_1(),
+        1 => serialize_unnamed_fields_1(fields),
         _ => serialize_unnamed_fields_n(fields),
     }
 }
 
 /// Generate code for serializing given unnamed fields where the actual number
 /// of fields is zero (e.g. zero-length tuple struct).
-fn serialize_unnamed_fields_0() -> (TokenStream, TokenStream) {
+fn serialize_unnamed_fields_0() -> (TokenStream, TokenStream, TokenStream) {
     let deconstructor = TokenStream::new();
 
     let mut serialize = TokenStream::new();
@@ -346,12 +764,16 @@ fn serialize_unnamed_fields_0() -> (TokenStream, TokenStream) {
         let __val = serde_lite::Intermediate::None;
     });
 
-    (deconstructor, serialize)
+    let serialize_to = quote! {
+        __emitter.scalar(serde_lite::emit::Scalar::None)
+    };
+
+    (deconstructor, serialize, serialize_to)
 }
 
 /// Generate code for serializing given unnamed fields where the actual number
 /// of fields is one (e.g. single-element tuple struct).
-fn serialize_unnamed_fields_1() -> (TokenStream, TokenStream) {
+fn serialize_unnamed_fields_1(fields: &FieldsUnnamed) -> (TokenStream, TokenStream, TokenStream) {
     let mut deconstructor = TokenStream::new();
     let mut serialize = TokenStream::new();
 
@@ -361,18 +783,21 @@ fn serialize_unnamed_fields_1() -> (TokenStream, TokenStream) {
         #name
     });
 
+    let (serialize_field, serialize_to) = serialize_unnamed_field(&fields.unnamed[0], &name);
+
     serialize.extend(quote! {
-        let __val = serde_lite::Serialize::serialize(#name)?;
+        let __val = #serialize_field?;
     });
 
-    (deconstructor, serialize)
+    (deconstructor, serialize, serialize_to)
 }
 
 /// Generate code for serializing given unnamed fields where the actual number
 /// of fields is greater one (e.g. multiple-element tuple struct).
-fn serialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
+fn serialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenStream, TokenStream) {
     let mut deconstructor = TokenStream::new();
     let mut serialize = TokenStream::new();
+    let mut serialize_to = TokenStream::new();
 
     if !fields.unnamed.is_empty() {
         let len = Literal::usize_unsuffixed(fields.unnamed.len());
@@ -383,21 +808,28 @@ fn serialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenStre
         });
     }
 
-    for (index, _) in fields.unnamed.iter().enumerate() {
+    for (index, field) in fields.unnamed.iter().enumerate() {
         let sname = format!("f{}", index);
         let name = Ident::new(&sname, Span::call_site());
         let lindex = Literal::usize_unsuffixed(index);
+        let (serialize_field, emit_field) = serialize_unnamed_field(field, &name);
 
         deconstructor.extend(quote! {
             #name,
         });
 
         serialize.extend(quote! {
-            match serde_lite::Serialize::serialize(#name) {
+            match #serialize_field {
                 Ok(v) => __arr.push(v),
                 Err(err) => __field_errors.push(serde_lite::UnnamedFieldError::new(#lindex, err)),
             }
         });
+
+        serialize_to.extend(quote! {
+            #emit_field.map_err(|err| {
+                serde_lite::Error::from(serde_lite::UnnamedFieldError::new(#lindex, err))
+            })?;
+        });
     }
 
     serialize.extend(quote! {
@@ -408,5 +840,43 @@ fn serialize_unnamed_fields_n(fields: &FieldsUnnamed) -> (TokenStream, TokenStre
         let __val = serde_lite::Intermediate::Array(__arr);
     });
 
-    (deconstructor, serialize)
+    let len = Literal::usize_unsuffixed(fields.unnamed.len());
+
+    let serialize_to = quote! {
+        __emitter.begin_array(Some(#len))?;
+
+        #serialize_to
+
+        __emitter.end_array()
+    };
+
+    (deconstructor, serialize, serialize_to)
+}
+
+/// Generate expressions serializing and emitting a given unnamed field
+/// (bound to a given name).
+fn serialize_unnamed_field(field: &Field, name: &Ident) -> (TokenStream, TokenStream) {
+    if let Some(policy) = attributes::get_field_non_finite_policy(field) {
+        let policy = Ident::new(policy, Span::call_site());
+
+        let serialize = quote! {
+            serde_lite::float::NonFinitePolicy::#policy.serialize(#name)
+        };
+
+        let serialize_to = quote! {
+            #serialize.and_then(|__v| serde_lite::emit::emit(&__v, __emitter))
+        };
+
+        (serialize, serialize_to)
+    } else {
+        let serialize = quote! {
+            serde_lite::Serialize::serialize(#name)
+        };
+
+        let serialize_to = quote! {
+            serde_lite::Serialize::serialize_to(#name, __emitter)
+        };
+
+        (serialize, serialize_to)
+    }
 }
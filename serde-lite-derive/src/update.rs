@@ -7,7 +7,7 @@ use syn::{
     Generics, Ident, Variant,
 };
 
-use crate::attributes;
+use crate::{attributes, validate};
 
 /// Expand derive Update.
 pub fn derive_update(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -27,18 +27,22 @@ fn expand_for_struct(
     name: Ident,
     generics: Generics,
     data: DataStruct,
-    _: &[Attribute],
+    attrs: &[Attribute],
 ) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let compact = attributes::has_flag(attrs, "compact");
+
     let update = match data.fields {
-        Fields::Named(fields) => expand_named_fields(fields),
+        Fields::Named(fields) => expand_named_fields(fields, compact),
         Fields::Unnamed(fields) => expand_unnamed_fields(fields),
         Fields::Unit => quote! {
             Ok(())
         },
     };
 
+    let update = validate::validate_updated(attrs, update);
+
     let expanded = quote! {
         #[allow(unused_assignments, unused_variables)]
         impl #impl_generics serde_lite::Update for #name #ty_generics #where_clause {
@@ -52,8 +56,8 @@ fn expand_for_struct(
 }
 
 /// Expand Update for given named struct fields.
-fn expand_named_fields(fields: FieldsNamed) -> TokenStream {
-    let (deconstructor, update) = update_named_fields(&fields);
+fn expand_named_fields(fields: FieldsNamed, compact: bool) -> TokenStream {
+    let (deconstructor, update) = update_named_fields(&fields, compact);
 
     let mut init = TokenStream::new();
 
@@ -98,20 +102,29 @@ fn expand_for_enum(
 ) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let compact = attributes::has_flag(attrs, "compact");
+
     let update = if let Some(tag) = attributes::get_enum_tag(attrs) {
         let content = attributes::get_enum_content(attrs);
 
-        expand_internally_tagged_enum(&tag, content.as_deref(), data)
+        expand_internally_tagged_enum(&tag, content.as_deref(), data, compact)
     } else {
-        expand_externally_tagged_enum(data)
+        expand_externally_tagged_enum(data, compact)
     };
 
+    let update = validate::validate_updated(
+        attrs,
+        quote! {
+            #update
+            Ok(())
+        },
+    );
+
     quote! {
         #[allow(unused_assignments, unused_variables)]
         impl #impl_generics serde_lite::Update for #name #ty_generics #where_clause {
             fn update(&mut self, __val: &serde_lite::Intermediate) -> Result<(), serde_lite::Error> {
                 #update
-                Ok(())
             }
         }
     }
@@ -123,6 +136,7 @@ fn expand_internally_tagged_enum(
     tag_field: &str,
     content_field: Option<&str>,
     data: DataEnum,
+    compact: bool,
 ) -> TokenStream {
     let mut update = TokenStream::new();
 
@@ -134,11 +148,11 @@ fn expand_internally_tagged_enum(
             // is in a sub-field and we don't know yet if the field exists.
             // Therefore, we have to use the construct_enum_variant function
             // here which will check if the field exists.
-            update_enum_variant(variant, content_field)
+            update_enum_variant(variant, content_field, compact)
         } else {
             // Here the enum content is a part of the currently deserialized
             // object, so we don't need to check anything.
-            update_enum_variant_with_content(variant)
+            update_enum_variant_with_content(variant, compact)
         };
 
         update.extend(quote! {
@@ -185,15 +199,16 @@ fn expand_internally_tagged_enum(
 }
 
 /// Expand Update for a given externally tagged enum.
-fn expand_externally_tagged_enum(data: DataEnum) -> TokenStream {
+fn expand_externally_tagged_enum(data: DataEnum, compact: bool) -> TokenStream {
     let mut plain = TokenStream::new();
     let mut with_content = TokenStream::new();
 
     for (index, variant) in data.variants.into_iter().enumerate() {
         let sname = attributes::get_variant_name(&variant);
         let lname = Literal::string(&sname);
-        let update_variant_with_content = update_enum_variant_with_content(&variant);
-        let update_variant_without_content = update_enum_variant_without_content(&variant, None);
+        let update_variant_with_content = update_enum_variant_with_content(&variant, compact);
+        let update_variant_without_content =
+            update_enum_variant_without_content(&variant, None, compact);
 
         plain.extend(quote! {
             #lname => { #update_variant_without_content }
@@ -261,9 +276,13 @@ fn get_current_enum_variant(data: &DataEnum) -> TokenStream {
 }
 
 /// Generate code for updating a given enum variant.
-fn update_enum_variant(variant: &Variant, content_field: Option<&str>) -> TokenStream {
-    let with_content = update_enum_variant_with_content(variant);
-    let without_content = update_enum_variant_without_content(variant, content_field);
+fn update_enum_variant(
+    variant: &Variant,
+    content_field: Option<&str>,
+    compact: bool,
+) -> TokenStream {
+    let with_content = update_enum_variant_with_content(variant, compact);
+    let without_content = update_enum_variant_without_content(variant, content_field, compact);
 
     quote! {
         if let Some(__content) = __content {
@@ -276,9 +295,9 @@ fn update_enum_variant(variant: &Variant, content_field: Option<&str>) -> TokenS
 
 /// Generate code for updating a given enum variant and use the available
 /// variant content.
-fn update_enum_variant_with_content(variant: &Variant) -> TokenStream {
+fn update_enum_variant_with_content(variant: &Variant, compact: bool) -> TokenStream {
     match &variant.fields {
-        Fields::Named(fields) => update_struct_enum_variant(variant, fields),
+        Fields::Named(fields) => update_struct_enum_variant(variant, fields, compact),
         Fields::Unnamed(fields) => update_tuple_enum_variant(variant, fields),
         Fields::Unit => update_unit_enum_variant(variant),
     }
@@ -288,10 +307,11 @@ fn update_enum_variant_with_content(variant: &Variant) -> TokenStream {
 fn update_enum_variant_without_content(
     variant: &Variant,
     content_field: Option<&str>,
+    compact: bool,
 ) -> TokenStream {
     match &variant.fields {
         Fields::Named(fields) if fields.named.is_empty() => {
-            return update_struct_enum_variant(variant, fields);
+            return update_struct_enum_variant(variant, fields, compact);
         }
         Fields::Unnamed(fields) if fields.unnamed.is_empty() => {
             return update_tuple_enum_variant(variant, fields);
@@ -316,7 +336,11 @@ fn update_enum_variant_without_content(
 }
 
 /// Generate code for updating a given struct-like enum variant.
-fn update_struct_enum_variant(variant: &Variant, fields: &FieldsNamed) -> TokenStream {
+fn update_struct_enum_variant(
+    variant: &Variant,
+    fields: &FieldsNamed,
+    compact: bool,
+) -> TokenStream {
     let mut init = TokenStream::new();
 
     if !fields.named.is_empty() {
@@ -325,7 +349,7 @@ fn update_struct_enum_variant(variant: &Variant, fields: &FieldsNamed) -> TokenS
         });
     }
 
-    let (deconstructor, update) = update_named_fields(fields);
+    let (deconstructor, update) = update_named_fields(fields, compact);
 
     let ident = &variant.ident;
 
@@ -373,7 +397,11 @@ fn update_unit_enum_variant(variant: &Variant) -> TokenStream {
 }
 
 /// Generate code for updating given named field.
-fn update_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
+fn update_named_fields(fields: &FieldsNamed, compact: bool) -> (TokenStream, TokenStream) {
+    if compact && !fields.named.is_empty() {
+        return update_named_fields_compact(fields);
+    }
+
     let mut deconstructor = TokenStream::new();
     let mut update = TokenStream::new();
 
@@ -421,6 +449,18 @@ fn update_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
                     }
                 }
             });
+        } else if let Some(validation) = validate::validate_field(field, &lname) {
+            update.extend(quote! {
+                if let Some(__v) = __obj.get(#lname) {
+                    if let Err(err) = #updater(#name, __v) {
+                        __field_errors.push(serde_lite::NamedFieldError::new_static(#lname, err));
+                    } else {
+                        let __field = &*#name;
+
+                        #validation
+                    }
+                }
+            });
         } else {
             update.extend(quote! {
                 if let Some(__v) = __obj.get(#lname) {
@@ -443,6 +483,101 @@ fn update_named_fields(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
     (deconstructor, update)
 }
 
+/// Generate code for updating given named fields using the table-driven
+/// runtime.
+fn update_named_fields_compact(fields: &FieldsNamed) -> (TokenStream, TokenStream) {
+    let mut deconstructor = TokenStream::new();
+    let mut table = TokenStream::new();
+    let mut targets = TokenStream::new();
+    let mut validate = TokenStream::new();
+
+    for field in &fields.named {
+        let name = field.ident.as_ref().unwrap();
+        let sname = attributes::get_field_name(field);
+        let lname = Literal::string(&sname);
+        let updater = attributes::get_field_updater(field)
+            .map(|path| TokenStream::from_str(&path))
+            .map(|res| res.expect("invalid path given for the update_with attribute"));
+
+        deconstructor.extend(quote! {
+            #name,
+        });
+
+        if attributes::has_flag(&field.attrs, "skip")
+            || attributes::has_flag(&field.attrs, "skip_deserializing")
+        {
+            continue;
+        }
+
+        let flags = if attributes::has_flag(&field.attrs, "flatten") {
+            quote! { serde_lite::compact::Field::FLATTEN }
+        } else {
+            quote! { 0 }
+        };
+
+        table.extend(quote! {
+            serde_lite::compact::Field::new(#lname, #flags),
+        });
+
+        if let Some(validation) = validate::validate_field(field, &lname) {
+            validate.extend(quote! {
+                if __obj.get(#lname).is_some() {
+                    let __field = &*#name;
+
+                    #validation
+                }
+            });
+        }
+
+        if let Some(path) = updater {
+            targets.extend(quote! {
+                &mut serde_lite::compact::UpdateWith::new(#name, |__f, __v| #path(__f, __v))
+                    as &mut dyn serde_lite::compact::UpdateField,
+            });
+        } else {
+            targets.extend(quote! {
+                #name as &mut dyn serde_lite::compact::UpdateField,
+            });
+        }
+    }
+
+    let mut update = quote! {
+        static __FIELDS: &[serde_lite::compact::Field] = &[#table];
+    };
+
+    if validate.is_empty() {
+        update.extend(quote! {
+            serde_lite::compact::update_fields(__FIELDS, &mut [#targets], __val)?;
+        });
+    } else {
+        // validation errors are reported together with the other field
+        // errors
+        update.extend(quote! {
+            let mut __field_errors = match serde_lite::compact::update_fields(
+                __FIELDS,
+                &mut [#targets],
+                __val,
+            ) {
+                Ok(()) => serde_lite::ErrorList::new(),
+                Err(serde_lite::Error::NamedFieldErrors(errors)) => errors,
+                Err(err) => return Err(err),
+            };
+
+            let __obj = __val
+                .as_map()
+                .ok_or_else(|| serde_lite::Error::invalid_value_static("object"))?;
+
+            #validate
+
+            if !__field_errors.is_empty() {
+                return Err(serde_lite::Error::NamedFieldErrors(__field_errors));
+            }
+        });
+    }
+
+    (deconstructor, update)
+}
+
 /// Generate code for updating given unnamed fields.
 fn update_unnamed_fields(fields: &FieldsUnnamed) -> (TokenStream, TokenStream) {
     match fields.unnamed.len() {
@@ -0,0 +1,168 @@
+use std::str::FromStr;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Attribute, Expr, ExprLit, ExprUnary, Field, Lit, UnOp};
+
+use crate::attributes::{self, Validation};
+
+/// Wrap a given Deserialize body with the container validation (if the
+/// container has a validator).
+pub fn validate_deserialized(attrs: &[Attribute], body: TokenStream) -> TokenStream {
+    let Some(path) = container_validator(attrs) else {
+        return body;
+    };
+
+    quote! {
+        let __res = (|| -> Result<Self, serde_lite::Error> { #body })()?;
+
+        #path(&__res)?;
+
+        Ok(__res)
+    }
+}
+
+/// Wrap a given Update body with the container validation (if the container
+/// has a validator).
+///
+/// The object is restored from a snapshot if the update or the validation
+/// fails.
+pub fn validate_updated(attrs: &[Attribute], body: TokenStream) -> TokenStream {
+    let Some(path) = container_validator(attrs) else {
+        return body;
+    };
+
+    quote! {
+        let __snapshot = Clone::clone(&*self);
+
+        let __res = (|| -> Result<(), serde_lite::Error> { #body })()
+            .and_then(|()| #path(&*self));
+
+        if __res.is_err() {
+            *self = __snapshot;
+        }
+
+        __res
+    }
+}
+
+/// Get the container validator path (if present).
+fn container_validator(attrs: &[Attribute]) -> Option<TokenStream> {
+    attributes::get_container_validator(attrs).map(|path| {
+        TokenStream::from_str(&path).expect("invalid path given for the validate attribute")
+    })
+}
+
+/// Generate code validating a given named field (if the field has any
+/// validation rules).
+///
+/// The generated code expects a reference to the field value in `__field`
+/// and it pushes all violations into `__field_errors`.
+pub fn validate_field(field: &Field, lname: &Literal) -> Option<TokenStream> {
+    let validations = attributes::get_field_validations(field);
+
+    if validations.is_empty() {
+        return None;
+    }
+
+    if attributes::has_flag(&field.attrs, "flatten") {
+        panic!("the validate attribute cannot be used for flattened fields");
+    } else if attributes::has_flag(&field.attrs, "skip")
+        || attributes::has_flag(&field.attrs, "skip_deserializing")
+    {
+        panic!("the validate attribute cannot be used for skipped fields");
+    }
+
+    let mut res = TokenStream::new();
+
+    for validation in validations {
+        let check = match validation {
+            Validation::Range { min, max } => {
+                let min = range_bound(min);
+                let max = range_bound(max);
+
+                quote! {
+                    serde_lite::validate::range(__field, #min, #max)
+                }
+            }
+            Validation::Length { min, max } => {
+                let min = bound(min);
+                let max = bound(max);
+
+                quote! {
+                    serde_lite::validate::length(__field, #min, #max)
+                }
+            }
+            Validation::NonEmpty => quote! {
+                serde_lite::validate::non_empty(__field)
+            },
+            Validation::Pattern(pattern) => {
+                let lpattern = Literal::string(&pattern);
+
+                quote! {
+                    {
+                        static __PATTERN: serde_lite::validate::LazyPattern =
+                            serde_lite::validate::LazyPattern::new(#lpattern);
+
+                        serde_lite::validate::pattern(__field, &__PATTERN)
+                    }
+                }
+            }
+            Validation::Custom(path) => {
+                let path = TokenStream::from_str(&path)
+                    .expect("invalid path given for the custom validation");
+
+                quote! {
+                    #path(__field)
+                }
+            }
+        };
+
+        res.extend(quote! {
+            if let Err(err) = #check {
+                __field_errors.push(serde_lite::NamedFieldError::new_static(#lname, err));
+            }
+        });
+    }
+
+    Some(res)
+}
+
+/// Generate an optional validation bound.
+fn bound(bound: Option<Expr>) -> TokenStream {
+    match bound {
+        Some(bound) => quote! { Some(#bound) },
+        None => quote! { None },
+    }
+}
+
+/// Generate an optional range bound.
+///
+/// Unsuffixed integer literals are converted to the type of the bound, so
+/// that they can be used for floating point numbers as well.
+fn range_bound(bound: Option<Expr>) -> TokenStream {
+    match bound {
+        Some(bound) if is_int_literal(&bound) => quote! {
+            Some(<_ as serde_lite::validate::FromIntLiteral>::from_int_literal(#bound))
+        },
+        other => self::bound(other),
+    }
+}
+
+/// Check if a given expression is an unsuffixed (possibly negative) integer
+/// literal.
+fn is_int_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.suffix().is_empty(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => is_int_literal(expr),
+        Expr::Group(group) => is_int_literal(&group.expr),
+        Expr::Paren(paren) => is_int_literal(&paren.expr),
+        _ => false,
+    }
+}
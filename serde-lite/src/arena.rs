@@ -0,0 +1,685 @@
+//! Arena-allocated values.
+//!
+//! Building an `Intermediate` value requires one allocation for every array,
+//! map and string. An `Arena` stores all nodes of a value in a single vector
+//! and all strings in a single buffer instead. Nodes reference each other by
+//! index. An arena can be reused for processing many values without
+//! allocating any additional memory:
+//!
+//! ```rust
+//! use serde_lite::{arena::Arena, intermediate, view::ValueView};
+//!
+//! let mut arena = Arena::new();
+//!
+//! for id in 0..3u32 {
+//!     let val = intermediate!({
+//!         "id": id,
+//!         "name": "foo",
+//!     });
+//!
+//!     arena.load(&val);
+//!
+//!     let root = arena.root();
+//!
+//!     assert_eq!(root.get("name").unwrap().as_str(), Some("foo"));
+//! }
+//! ```
+//!
+//! An arena can be also loaded directly from a serde deserializer without
+//! building an `Intermediate` value first:
+//!
+//! ```rust
+//! use serde_lite::{arena::Arena, view::ValueView};
+//!
+//! let mut arena = Arena::new();
+//!
+//! let mut deserializer = serde_json::Deserializer::from_str(r#"{"name": "foo"}"#);
+//!
+//! arena.load_from(&mut deserializer).unwrap();
+//!
+//! assert_eq!(arena.root().get("name").unwrap().as_str(), Some("foo"));
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Formatter},
+    ops::Range,
+};
+
+use serde::{
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{
+    view::{Kind, ValueView, ViewIter, ViewRef},
+    Intermediate, Number,
+};
+
+/// Arena node.
+#[derive(Debug, Clone)]
+enum Node {
+    None,
+    Bool(bool),
+    Number(Number),
+    String(Range<usize>),
+    Bytes(Range<usize>),
+    Array(Range<usize>),
+    Map(Range<usize>),
+}
+
+/// Arena-allocated value.
+///
+/// Array elements are stored as consecutive nodes. Map entries are stored
+/// as consecutive pairs of nodes where the first node of each pair is the
+/// key.
+#[derive(Debug, Clone)]
+pub struct Arena {
+    nodes: Vec<Node>,
+    strings: String,
+    bytes: Vec<u8>,
+    scratch: Vec<Node>,
+}
+
+impl Arena {
+    /// Create a new arena containing a single `None` value.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::None],
+            strings: String::new(),
+            bytes: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Create a new arena from a given intermediate value.
+    #[inline]
+    pub fn from_intermediate(val: &Intermediate) -> Self {
+        let mut res = Self::new();
+
+        res.load(val);
+        res
+    }
+
+    /// Replace the content of the arena with a given intermediate value.
+    ///
+    /// The already allocated memory is reused.
+    #[inline(never)]
+    pub fn load(&mut self, val: &Intermediate) {
+        self.nodes.clear();
+        self.strings.clear();
+        self.bytes.clear();
+
+        self.nodes.push(Node::None);
+
+        let mut queue = VecDeque::new();
+
+        queue.push_back((val, 0));
+
+        // the nodes are created in the breadth-first order, so that all
+        // children of a given container can be allocated at once
+        while let Some((val, index)) = queue.pop_front() {
+            let node = match val {
+                Intermediate::None => Node::None,
+                Intermediate::Bool(v) => Node::Bool(*v),
+                Intermediate::Number(v) => Node::Number(*v),
+                Intermediate::String(v) => Node::String(self.push_str(v)),
+                Intermediate::Bytes(v) => {
+                    let start = self.bytes.len();
+
+                    self.bytes.extend_from_slice(v);
+
+                    Node::Bytes(start..self.bytes.len())
+                }
+                Intermediate::Array(v) => {
+                    let start = self.nodes.len();
+
+                    self.nodes.resize(start + v.len(), Node::None);
+
+                    for (offset, elem) in v.iter().enumerate() {
+                        queue.push_back((elem, start + offset));
+                    }
+
+                    Node::Array(start..self.nodes.len())
+                }
+                Intermediate::Map(v) => {
+                    let start = self.nodes.len();
+
+                    for (key, value) in v {
+                        let key = self.push_str(key);
+
+                        self.nodes.push(Node::String(key));
+
+                        queue.push_back((value, self.nodes.len()));
+
+                        self.nodes.push(Node::None);
+                    }
+
+                    Node::Map(start..self.nodes.len())
+                }
+            };
+
+            self.nodes[index] = node;
+        }
+    }
+
+    /// Replace the content of the arena with a value read from a given
+    /// deserializer.
+    ///
+    /// The value is stored directly in the arena without building an
+    /// `Intermediate` value first. The already allocated memory is reused.
+    /// If a map contains duplicate keys, the last value is kept (the same as
+    /// in `Intermediate` maps).
+    /// The arena will contain a single `None` value if the deserialization
+    /// fails.
+    #[inline(never)]
+    pub fn load_from<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.nodes.clear();
+        self.strings.clear();
+        self.bytes.clear();
+        self.scratch.clear();
+
+        self.nodes.push(Node::None);
+
+        let seed = NodeSeed { arena: self };
+
+        match seed.deserialize(deserializer) {
+            Ok(root) => {
+                self.nodes[0] = root;
+
+                Ok(())
+            }
+            Err(err) => {
+                self.clear();
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Remove all content from the arena.
+    ///
+    /// The arena will contain a single `None` value.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.load(&Intermediate::None)
+    }
+
+    /// Get the root value.
+    #[inline]
+    pub fn root(&self) -> ArenaValue<'_> {
+        ArenaValue {
+            arena: self,
+            index: 0,
+        }
+    }
+
+    /// Convert the arena into an intermediate value.
+    ///
+    /// Strings and byte strings are borrowed from the arena.
+    #[inline]
+    pub fn to_intermediate(&self) -> Intermediate<'_> {
+        self.root().to_intermediate()
+    }
+
+    /// Append a given string to the string buffer.
+    #[inline]
+    fn push_str(&mut self, s: &str) -> Range<usize> {
+        let start = self.strings.len();
+
+        self.strings.push_str(s);
+
+        start..self.strings.len()
+    }
+
+    /// Find a map key equal to a given key among the entries collected in
+    /// the scratch buffer starting at a given position.
+    #[inline(never)]
+    fn find_scratch_key(&self, start: usize, key: Range<usize>) -> Option<usize> {
+        let key = &self.strings[key];
+
+        (start..self.scratch.len()).step_by(2).find(|&index| {
+            matches!(&self.scratch[index], Node::String(k) if &self.strings[k.clone()] == key)
+        })
+    }
+
+    /// Move all nodes from the scratch buffer starting at a given position
+    /// to the end of the node vector.
+    ///
+    /// The nodes are created in the depth-first order while deserializing,
+    /// so the children of a container are collected in the scratch buffer
+    /// until the container is complete.
+    #[inline]
+    fn flush_scratch(&mut self, start: usize) -> Range<usize> {
+        let first = self.nodes.len();
+
+        self.nodes.extend(self.scratch.drain(start..));
+
+        first..self.nodes.len()
+    }
+}
+
+impl Default for Arena {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&Intermediate<'_>> for Arena {
+    #[inline]
+    fn from(val: &Intermediate) -> Self {
+        Self::from_intermediate(val)
+    }
+}
+
+impl<'de> Deserialize<'de> for Arena {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut res = Self::new();
+
+        res.load_from(deserializer)?;
+
+        Ok(res)
+    }
+}
+
+/// Seed for deserializing arena nodes.
+struct NodeSeed<'a> {
+    arena: &'a mut Arena,
+}
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+    type Value = Node;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+    type Value = Node;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Node::Bool(value))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Node::Number(Number::SignedInt(value)))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Node::Number(Number::UnsignedInt(value)))
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+        Ok(Node::Number(Number::from(value)))
+    }
+
+    #[inline]
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+        Ok(Node::Number(Number::from(value)))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Node::Number(Number::Float(value)))
+    }
+
+    #[inline]
+    fn visit_char<E>(self, value: char) -> Result<Self::Value, E> {
+        Ok(Node::String(
+            self.arena.push_str(value.encode_utf8(&mut [0; 4])),
+        ))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Node::String(self.arena.push_str(value)))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+        let start = self.arena.bytes.len();
+
+        self.arena.bytes.extend_from_slice(value);
+
+        Ok(Node::Bytes(start..self.arena.bytes.len()))
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Node::None)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Node::None)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let start = self.arena.scratch.len();
+
+        while let Some(elem) = seq.next_element_seed(NodeSeed {
+            arena: &mut *self.arena,
+        })? {
+            self.arena.scratch.push(elem);
+        }
+
+        Ok(Node::Array(self.arena.flush_scratch(start)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let start = self.arena.scratch.len();
+
+        while let Some(key) = map.next_key_seed(KeySeed {
+            arena: &mut *self.arena,
+        })? {
+            let value = map.next_value_seed(NodeSeed {
+                arena: &mut *self.arena,
+            })?;
+
+            // NOTE: the last value of a duplicate key wins (the same as in
+            // `Intermediate` maps)
+            if let Some(index) = self.arena.find_scratch_key(start, key.clone()) {
+                self.arena.scratch[index + 1] = value;
+            } else {
+                self.arena.scratch.push(Node::String(key));
+                self.arena.scratch.push(value);
+            }
+        }
+
+        Ok(Node::Map(self.arena.flush_scratch(start)))
+    }
+}
+
+/// Seed for deserializing map keys into the string buffer of an arena.
+struct KeySeed<'a> {
+    arena: &'a mut Arena,
+}
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = Range<usize>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed<'_> {
+    type Value = Range<usize>;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a string")
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(self.arena.push_str(value))
+    }
+}
+
+/// Value stored in an arena.
+#[derive(Copy, Clone)]
+pub struct ArenaValue<'a> {
+    arena: &'a Arena,
+    index: usize,
+}
+
+impl<'a> ArenaValue<'a> {
+    /// Get the underlying node.
+    #[inline]
+    fn node(&self) -> &'a Node {
+        &self.arena.nodes[self.index]
+    }
+
+    /// Create a value referencing a given node in the same arena.
+    #[inline]
+    fn with_index(&self, index: usize) -> Self {
+        Self {
+            arena: self.arena,
+            index,
+        }
+    }
+
+    /// Get kind of the value.
+    #[inline]
+    pub fn kind(&self) -> Kind {
+        match self.node() {
+            Node::None => Kind::None,
+            Node::Bool(_) => Kind::Bool,
+            Node::Number(_) => Kind::Number,
+            Node::String(_) => Kind::String,
+            Node::Bytes(_) => Kind::Bytes,
+            Node::Array(_) => Kind::Array,
+            Node::Map(_) => Kind::Map,
+        }
+    }
+
+    /// Get the value as a bool (if possible).
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Node::Bool(v) = self.node() {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    /// Get the value as a number (if possible).
+    #[inline]
+    pub fn as_number(&self) -> Option<Number> {
+        if let Node::Number(v) = self.node() {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    /// Get the value as a string (if possible).
+    #[inline]
+    pub fn as_str(&self) -> Option<&'a str> {
+        if let Node::String(range) = self.node() {
+            Some(&self.arena.strings[range.clone()])
+        } else {
+            None
+        }
+    }
+
+    /// Get the value as a byte string (if possible).
+    #[inline]
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        if let Node::Bytes(range) = self.node() {
+            Some(&self.arena.bytes[range.clone()])
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of array elements or map entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.node() {
+            Node::Array(range) => range.len(),
+            Node::Map(range) => range.len() >> 1,
+            _ => 0,
+        }
+    }
+
+    /// Check if the value is an empty array, an empty map or not a container
+    /// at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an array element at a given index.
+    #[inline]
+    pub fn element(&self, index: usize) -> Option<ArenaValue<'a>> {
+        if let Node::Array(range) = self.node() {
+            if index < range.len() {
+                return Some(self.with_index(range.start + index));
+            }
+        }
+
+        None
+    }
+
+    /// Get a map entry with a given key.
+    ///
+    /// Map entries are not indexed, so the lookup is a linear scan over all
+    /// entries of the map. Use `iter` instead of looking up every key of a
+    /// large map separately.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<ArenaValue<'a>> {
+        self.iter().find(|(k, _)| *k == Some(key)).map(|(_, v)| v)
+    }
+
+    /// Iterate over array elements or map entries.
+    #[inline]
+    pub fn iter(&self) -> Iter<'a> {
+        let (range, map) = match self.node() {
+            Node::Array(range) => (range.clone(), false),
+            Node::Map(range) => (range.clone(), true),
+            _ => (0..0, false),
+        };
+
+        Iter {
+            arena: self.arena,
+            range,
+            map,
+        }
+    }
+
+    /// Convert the value into an intermediate value.
+    ///
+    /// Strings and byte strings are borrowed from the arena.
+    #[inline]
+    pub fn to_intermediate(&self) -> Intermediate<'a> {
+        ViewRef::from(*self).to_intermediate()
+    }
+}
+
+impl ValueView for ArenaValue<'_> {
+    #[inline]
+    fn kind(&self) -> Kind {
+        ArenaValue::kind(self)
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        ArenaValue::as_bool(self)
+    }
+
+    #[inline]
+    fn as_number(&self) -> Option<Number> {
+        ArenaValue::as_number(self)
+    }
+
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        ArenaValue::as_str(self)
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> Option<&[u8]> {
+        ArenaValue::as_bytes(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        ArenaValue::len(self)
+    }
+
+    #[inline]
+    fn get(&self, key: &str) -> Option<ViewRef<'_>> {
+        ArenaValue::get(self, key).map(ViewRef::from)
+    }
+
+    #[inline]
+    fn element(&self, index: usize) -> Option<ViewRef<'_>> {
+        ArenaValue::element(self, index).map(ViewRef::from)
+    }
+
+    #[inline]
+    fn iter(&self) -> ViewIter<'_> {
+        ViewIter::from(ArenaValue::iter(self))
+    }
+}
+
+/// Iterator over array elements or map entries of an arena value.
+///
+/// Array elements do not have any key.
+pub struct Iter<'a> {
+    arena: &'a Arena,
+    range: Range<usize>,
+    map: bool,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Option<&'a str>, ArenaValue<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+
+        let value = ArenaValue {
+            arena: self.arena,
+            index,
+        };
+
+        if self.map {
+            let index = self.range.next()?;
+
+            let key = value.as_str();
+
+            let value = ArenaValue {
+                arena: self.arena,
+                index,
+            };
+
+            Some((key, value))
+        } else {
+            Some((None, value))
+        }
+    }
+}
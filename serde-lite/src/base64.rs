@@ -0,0 +1,181 @@
+//! Base64 encoding of binary data.
+//!
+//! The submodules can be used with the `with` attribute (or with the
+//! `serialize_with`, `deserialize_with` and `update_with` attributes) for
+//! fields of type `Vec<u8>`, `[u8; N]` or `Box<[u8]>`. The data is serialized
+//! as a base64 string. Deserialization accepts a base64 string or raw bytes.
+//!
+//! ```rust
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Key {
+//!     #[serde(with = "serde_lite::base64::standard")]
+//!     public: Vec<u8>,
+//!     #[serde(with = "serde_lite::base64::url_safe_no_pad")]
+//!     fingerprint: [u8; 8],
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{bytes::FromBytes, Error, Intermediate};
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+macro_rules! base64_module {
+    ( $(#[$doc:meta])* $name:ident, $alphabet:expr, $pad:expr ) => {
+        $(#[$doc])*
+        pub mod $name {
+            use crate::{bytes::FromBytes, Error, Intermediate};
+
+            /// Serialize given bytes as a base64 string.
+            #[inline]
+            pub fn serialize<T>(val: &T) -> Result<Intermediate<'_>, Error>
+            where
+                T: AsRef<[u8]> + ?Sized,
+            {
+                Ok(super::encode(val.as_ref(), $alphabet, $pad))
+            }
+
+            /// Deserialize bytes from a base64 string.
+            #[inline]
+            pub fn deserialize<T>(val: &Intermediate) -> Result<T, Error>
+            where
+                T: FromBytes,
+            {
+                super::decode(val, $alphabet, $pad)
+            }
+
+            /// Replace given bytes with bytes decoded from a base64 string.
+            #[inline]
+            pub fn update<T>(val: &mut T, input: &Intermediate) -> Result<(), Error>
+            where
+                T: FromBytes,
+            {
+                *val = deserialize(input)?;
+
+                Ok(())
+            }
+        }
+    };
+}
+
+base64_module!(
+    /// Standard base64 alphabet with padding (RFC 4648, section 4).
+    standard,
+    super::STANDARD,
+    true
+);
+
+base64_module!(
+    /// URL-safe base64 alphabet with padding (RFC 4648, section 5).
+    url_safe,
+    super::URL_SAFE,
+    true
+);
+
+base64_module!(
+    /// URL-safe base64 alphabet without padding (RFC 4648, section 5).
+    url_safe_no_pad,
+    super::URL_SAFE,
+    false
+);
+
+/// Encode given bytes.
+#[inline(never)]
+fn encode(input: &[u8], alphabet: &[u8; 64], pad: bool) -> Intermediate<'static> {
+    let mut res = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+
+        let n = (b0 << 16) | (b1 << 8) | b2;
+
+        let chars = chunk.len() + 1;
+
+        for i in 0..4 {
+            if i < chars {
+                let index = (n >> (18 - 6 * i)) & 0x3f;
+
+                res.push(alphabet[index as usize] as char);
+            } else if pad {
+                res.push('=');
+            }
+        }
+    }
+
+    Intermediate::String(Cow::Owned(res))
+}
+
+/// Decode bytes from a given intermediate value.
+#[inline(never)]
+fn decode_to_vec(val: &Intermediate, alphabet: &[u8; 64], pad: bool) -> Result<Vec<u8>, Error> {
+    if let Some(bytes) = val.as_bytes() {
+        return Ok(bytes.to_vec());
+    }
+
+    let input = val
+        .as_str()
+        .ok_or_else(|| Error::invalid_value_static("base64 string"))?
+        .as_bytes();
+
+    let data = if pad {
+        if input.len() % 4 != 0 {
+            return Err(Error::invalid_value_static("base64 string"));
+        }
+
+        let padding = input.iter().rev().take_while(|&&c| c == b'=').count();
+
+        if padding > 2 {
+            return Err(Error::invalid_value_static("base64 string"));
+        }
+
+        &input[..input.len() - padding]
+    } else {
+        input
+    };
+
+    if data.len() % 4 == 1 {
+        return Err(Error::invalid_value_static("base64 string"));
+    }
+
+    let mut res = Vec::with_capacity(data.len() / 4 * 3 + 2);
+
+    for chunk in data.chunks(4) {
+        let mut n = 0u32;
+
+        for (i, c) in chunk.iter().enumerate() {
+            let v = alphabet
+                .iter()
+                .position(|a| a == c)
+                .ok_or_else(|| Error::invalid_value_static("base64 string"))?;
+
+            n |= (v as u32) << (18 - 6 * i);
+        }
+
+        let bytes = n.to_be_bytes();
+
+        // the unused bits of the last character must be zero, otherwise
+        // the encoding would not be unique (RFC 4648, section 3.5)
+        if bytes[chunk.len()..].iter().any(|&b| b != 0) {
+            return Err(Error::invalid_value_static("base64 string"));
+        }
+
+        res.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+
+    Ok(res)
+}
+
+/// Decode bytes from a given intermediate value.
+#[inline]
+fn decode<T>(val: &Intermediate, alphabet: &[u8; 64], pad: bool) -> Result<T, Error>
+where
+    T: FromBytes,
+{
+    T::from_bytes(decode_to_vec(val, alphabet, pad)?)
+}
@@ -0,0 +1,245 @@
+//! Binary data support.
+//!
+//! Byte sequences are serialized as `Intermediate::Bytes` instead of arrays
+//! of numbers. This allows binary formats (e.g. CBOR or MessagePack) to
+//! round-trip binary data losslessly. The functions in this module can be
+//! used directly with the `serialize_with`, `deserialize_with` and
+//! `update_with` attributes or via the `bytes` field attribute:
+//!
+//! ```rust
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Attachment {
+//!     name: String,
+//!     #[serde(bytes)]
+//!     data: Vec<u8>,
+//! }
+//! ```
+
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Deserialize, Error, Intermediate, Serialize, Update};
+
+/// Types that can be constructed from a sequence of bytes.
+pub trait FromBytes: Sized {
+    /// Create a new instance from given bytes.
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error>;
+}
+
+impl FromBytes for Vec<u8> {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        Ok(bytes)
+    }
+}
+
+impl FromBytes for Box<[u8]> {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        Ok(bytes.into_boxed_slice())
+    }
+}
+
+impl FromBytes for Cow<'static, [u8]> {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+impl FromBytes for ByteBuf {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        Ok(Self::from(bytes))
+    }
+}
+
+impl<const N: usize> FromBytes for [u8; N] {
+    #[inline]
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        <[u8; N]>::try_from(bytes)
+            .map_err(|_| Error::invalid_value_static("byte array of a given length"))
+    }
+}
+
+/// Serialize given bytes as `Intermediate::Bytes`.
+#[inline]
+pub fn serialize<T>(val: &T) -> Result<Intermediate<'_>, Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+{
+    Ok(Intermediate::Bytes(Cow::Borrowed(val.as_ref())))
+}
+
+/// Deserialize bytes from `Intermediate::Bytes` or from an array of
+/// unsigned 8-bit integers.
+#[inline]
+pub fn deserialize<T>(val: &Intermediate) -> Result<T, Error>
+where
+    T: FromBytes,
+{
+    T::from_bytes(to_byte_vec(val)?)
+}
+
+/// Replace given bytes with the bytes from `Intermediate::Bytes` or from an
+/// array of unsigned 8-bit integers.
+#[inline]
+pub fn update<T>(val: &mut T, input: &Intermediate) -> Result<(), Error>
+where
+    T: FromBytes,
+{
+    *val = deserialize(input)?;
+
+    Ok(())
+}
+
+/// Helper function.
+#[inline(never)]
+fn to_byte_vec(val: &Intermediate) -> Result<Vec<u8>, Error> {
+    if let Some(bytes) = val.as_bytes() {
+        Ok(bytes.to_vec())
+    } else if let Some(arr) = val.as_array() {
+        let mut res = Vec::with_capacity(arr.len());
+
+        for elem in arr {
+            res.push(u8::deserialize(elem)?);
+        }
+
+        Ok(res)
+    } else {
+        Err(Error::invalid_value_static("bytes"))
+    }
+}
+
+/// Borrowed byte slice that is serialized as `Intermediate::Bytes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Bytes<'a> {
+    inner: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    /// Wrap a given byte slice.
+    #[inline]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { inner: bytes }
+    }
+
+    /// Get the underlying byte slice.
+    #[inline]
+    pub const fn as_slice(&self) -> &'a [u8] {
+        self.inner
+    }
+}
+
+impl<'a> From<&'a [u8]> for Bytes<'a> {
+    #[inline]
+    fn from(bytes: &'a [u8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Bytes<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.inner
+    }
+}
+
+impl Deref for Bytes<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.inner
+    }
+}
+
+impl Serialize for Bytes<'_> {
+    #[inline]
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        serialize(self.inner)
+    }
+}
+
+/// Owned byte buffer that is serialized as `Intermediate::Bytes`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ByteBuf {
+    inner: Vec<u8>,
+}
+
+impl ByteBuf {
+    /// Create a new empty buffer.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    /// Take the underlying vector.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.inner
+    }
+}
+
+impl From<Vec<u8>> for ByteBuf {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self { inner: bytes }
+    }
+}
+
+impl From<ByteBuf> for Vec<u8> {
+    #[inline]
+    fn from(buf: ByteBuf) -> Self {
+        buf.inner
+    }
+}
+
+impl AsRef<[u8]> for ByteBuf {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl Deref for ByteBuf {
+    type Target = Vec<u8>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for ByteBuf {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl Serialize for ByteBuf {
+    #[inline]
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        serialize(&self.inner)
+    }
+}
+
+impl Deserialize for ByteBuf {
+    #[inline]
+    fn deserialize(val: &Intermediate) -> Result<Self, Error> {
+        deserialize(val)
+    }
+}
+
+impl Update for ByteBuf {
+    #[inline]
+    fn update(&mut self, val: &Intermediate) -> Result<(), Error> {
+        update(self, val)
+    }
+}
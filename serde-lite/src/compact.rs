@@ -0,0 +1,341 @@
+//! Table-driven serialization, deserialization and update of named fields.
+//!
+//! Types using the `compact` container attribute do not get their own loops
+//! for building maps, looking up keys and collecting field errors. Instead,
+//! the derive macros generate a static table of field descriptors and an
+//! array of type-erased references to the fields. Both are then passed to
+//! one of the functions in this module. The functions are not generic, so
+//! there is only a single copy of them in the resulting binary no matter how
+//! many types use them.
+//!
+//! The mode trades a bit of runtime performance (fields are accessed via
+//! dynamic dispatch) for smaller code. It pays off mainly for large structs.
+//!
+//! ```rust
+//! use serde_lite::{Deserialize, Serialize};
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! #[serde(compact)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//!     #[serde(default)]
+//!     label: Option<String>,
+//! }
+//!
+//! let point = Point {
+//!     x: 1,
+//!     y: 2,
+//!     label: None,
+//! };
+//!
+//! let intermediate = point.serialize().unwrap();
+//!
+//! assert_eq!(Point::deserialize(&intermediate).unwrap(), point);
+//! ```
+//!
+//! The items in this module are used by the generated code and they are not
+//! meant to be used directly.
+
+use std::mem;
+
+use crate::{
+    emit::{self, Emitter},
+    DeserializeBorrowed, Error, ErrorList, Intermediate, Map, NamedFieldError, Serialize, Update,
+};
+
+/// Field descriptor.
+#[derive(Debug, Copy, Clone)]
+pub struct Field {
+    name: &'static str,
+    flags: u8,
+}
+
+impl Field {
+    /// The field is flattened.
+    pub const FLATTEN: u8 = 1;
+
+    /// The default value is used if the field is missing.
+    pub const DEFAULT: u8 = 2;
+
+    /// Create a new field descriptor.
+    #[inline]
+    pub const fn new(name: &'static str, flags: u8) -> Self {
+        Self { name, flags }
+    }
+
+    /// Get the field name.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Check if the field is flattened.
+    #[inline]
+    pub const fn is_flattened(&self) -> bool {
+        (self.flags & Self::FLATTEN) != 0
+    }
+
+    /// Check if the default value should be used if the field is missing.
+    #[inline]
+    pub const fn has_default(&self) -> bool {
+        (self.flags & Self::DEFAULT) != 0
+    }
+}
+
+/// Field value to be serialized.
+pub enum FieldValue<'a> {
+    /// The field is skipped.
+    Skip,
+    /// The field is serialized using its `Serialize` implementation.
+    Serialize(&'a dyn Serialize),
+    /// The field has been already serialized (e.g. using a custom
+    /// serializer).
+    Serialized(Result<Intermediate<'a>, Error>),
+}
+
+/// Serialize given fields into a map.
+///
+/// Errors are collected for all fields.
+#[inline(never)]
+pub fn serialize_fields<'a>(
+    fields: &[Field],
+    values: &mut [FieldValue<'a>],
+) -> Result<Map<'a>, Error> {
+    let mut map = Map::with_capacity(values.len());
+    let mut errors = ErrorList::new();
+
+    for (field, value) in fields.iter().zip(values) {
+        let res = match mem::replace(value, FieldValue::Skip) {
+            FieldValue::Skip => continue,
+            FieldValue::Serialize(v) => v.serialize(),
+            FieldValue::Serialized(res) => res,
+        };
+
+        match res {
+            Ok(v) if field.is_flattened() => match v.into_map() {
+                Ok(inner) => map.extend(inner),
+                Err(_) => errors.push(NamedFieldError::new_static(
+                    field.name,
+                    Error::custom_static("field cannot be flattened"),
+                )),
+            },
+            Ok(v) => map.insert_with_static_key(field.name, v),
+            Err(err) => errors.push(NamedFieldError::new_static(field.name, err)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::NamedFieldErrors(errors));
+    }
+
+    Ok(map)
+}
+
+/// Serialize given fields into a given emitter.
+///
+/// The method stops at the first error.
+#[inline(never)]
+pub fn serialize_fields_to(
+    fields: &[Field],
+    values: &mut [FieldValue<'_>],
+    emitter: &mut dyn Emitter,
+) -> Result<(), Error> {
+    // flattened fields may collide with other fields, so the map is built
+    // in advance to resolve the collisions in the same way as
+    // `serialize_fields`
+    if fields.iter().any(Field::is_flattened) {
+        let map = serialize_fields(fields, values)?;
+
+        return emit::emit(&Intermediate::Map(map), emitter);
+    }
+
+    let len = values
+        .iter()
+        .filter(|v| !matches!(v, FieldValue::Skip))
+        .count();
+
+    emitter.begin_map(Some(len))?;
+
+    for (field, value) in fields.iter().zip(values) {
+        // values serialized using a custom serializer are checked before
+        // emitting the key because emitting cannot be undone
+        let res = match mem::replace(value, FieldValue::Skip) {
+            FieldValue::Skip => continue,
+            FieldValue::Serialize(v) => emitter
+                .key(field.name)
+                .and_then(|_| v.serialize_to(emitter)),
+            FieldValue::Serialized(Ok(v)) => emitter
+                .key(field.name)
+                .and_then(|_| emit::emit(&v, emitter)),
+            FieldValue::Serialized(Err(err)) => Err(err),
+        };
+
+        res.map_err(|err| NamedFieldError::new_static(field.name, err))?;
+    }
+
+    emitter.end_map()
+}
+
+/// Deserialization target of a single field.
+pub trait DeserializeField<'de> {
+    /// Deserialize the field from a given value.
+    fn deserialize_field(&mut self, val: &'de Intermediate<'de>) -> Result<(), Error>;
+}
+
+impl<'de, T> DeserializeField<'de> for Option<T>
+where
+    T: DeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_field(&mut self, val: &'de Intermediate<'de>) -> Result<(), Error> {
+        *self = Some(T::deserialize_borrowed(val)?);
+
+        Ok(())
+    }
+}
+
+/// Deserialization target of a field using a custom deserializer.
+pub struct DeserializeWith<'de, T> {
+    value: Option<T>,
+    deserializer: fn(&'de Intermediate<'de>) -> Result<T, Error>,
+}
+
+impl<'de, T> DeserializeWith<'de, T> {
+    /// Create a new deserialization target using a given deserializer.
+    #[inline]
+    pub const fn new(deserializer: fn(&'de Intermediate<'de>) -> Result<T, Error>) -> Self {
+        Self {
+            value: None,
+            deserializer,
+        }
+    }
+
+    /// Take the deserialized value (if any).
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.value
+    }
+}
+
+impl<'de, T> DeserializeField<'de> for DeserializeWith<'de, T> {
+    #[inline]
+    fn deserialize_field(&mut self, val: &'de Intermediate<'de>) -> Result<(), Error> {
+        self.value = Some((self.deserializer)(val)?);
+
+        Ok(())
+    }
+}
+
+/// Deserialize given fields from a given map.
+///
+/// Errors are collected for all fields. Fields having the `DEFAULT` flag are
+/// left untouched if they are missing.
+#[inline(never)]
+pub fn deserialize_fields<'de>(
+    fields: &[Field],
+    targets: &mut [&mut dyn DeserializeField<'de>],
+    val: &'de Intermediate<'de>,
+) -> Result<(), Error> {
+    let obj = val
+        .as_map()
+        .ok_or_else(|| Error::invalid_value_static("object"))?;
+
+    let mut errors = ErrorList::new();
+
+    for (field, target) in fields.iter().zip(targets) {
+        if field.is_flattened() {
+            match target.deserialize_field(val) {
+                Ok(()) => (),
+                Err(Error::NamedFieldErrors(inner)) => errors.append(inner),
+                Err(err) => return Err(err),
+            }
+        } else if let Some(v) = obj.get(field.name) {
+            if let Err(err) = target.deserialize_field(v) {
+                errors.push(NamedFieldError::new_static(field.name, err));
+            }
+        } else if !field.has_default() {
+            errors.push(NamedFieldError::new_static(field.name, Error::MissingField));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::NamedFieldErrors(errors));
+    }
+
+    Ok(())
+}
+
+/// Update target of a single field.
+pub trait UpdateField {
+    /// Update the field from a given value.
+    fn update_field(&mut self, val: &Intermediate) -> Result<(), Error>;
+}
+
+impl<T> UpdateField for T
+where
+    T: Update,
+{
+    #[inline]
+    fn update_field(&mut self, val: &Intermediate) -> Result<(), Error> {
+        self.update(val)
+    }
+}
+
+/// Update target of a field using a custom updater.
+pub struct UpdateWith<'a, T> {
+    value: &'a mut T,
+    updater: fn(&mut T, &Intermediate) -> Result<(), Error>,
+}
+
+impl<'a, T> UpdateWith<'a, T> {
+    /// Create a new update target using a given updater.
+    #[inline]
+    pub fn new(value: &'a mut T, updater: fn(&mut T, &Intermediate) -> Result<(), Error>) -> Self {
+        Self { value, updater }
+    }
+}
+
+impl<T> UpdateField for UpdateWith<'_, T> {
+    #[inline]
+    fn update_field(&mut self, val: &Intermediate) -> Result<(), Error> {
+        (self.updater)(self.value, val)
+    }
+}
+
+/// Update given fields from a given map.
+///
+/// Missing fields are left untouched. Errors are collected for all fields.
+#[inline(never)]
+pub fn update_fields(
+    fields: &[Field],
+    targets: &mut [&mut dyn UpdateField],
+    val: &Intermediate,
+) -> Result<(), Error> {
+    let obj = val
+        .as_map()
+        .ok_or_else(|| Error::invalid_value_static("object"))?;
+
+    let mut errors = ErrorList::new();
+
+    for (field, target) in fields.iter().zip(targets) {
+        if field.is_flattened() {
+            match target.update_field(val) {
+                Ok(()) => (),
+                Err(Error::NamedFieldErrors(inner)) => errors.append(inner),
+                Err(err) => return Err(err),
+            }
+        } else if let Some(v) = obj.get(field.name) {
+            if let Err(err) = target.update_field(v) {
+                errors.push(NamedFieldError::new_static(field.name, err));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::NamedFieldErrors(errors));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,813 @@
+//! Compatibility layer for types implementing only the serde traits.
+//!
+//! The module contains `serde::Serializer` producing `Intermediate` values
+//! and an implementation of `serde::Deserializer` for `&Intermediate`. The
+//! `serialize`, `deserialize` and `update` functions can be used with the
+//! `with` attribute in order to use serde types as fields of serde-lite
+//! types:
+//!
+//! ```rust
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Foreign {
+//!     value: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Local {
+//!     #[serde(with = "serde_lite::compat")]
+//!     foreign: Foreign,
+//! }
+//! ```
+//!
+//! Enums are represented as externally tagged enums, i.e. unit variants are
+//! represented as strings and all other variants as single-entry maps.
+//!
+//! The other direction is covered by the `SerdeCompat` wrapper. It implements
+//! `serde::Serialize` and `serde::Deserialize` for any serde-lite type, so
+//! serde-lite types can be passed to libraries requiring serde traits. The
+//! same implementations can be generated directly for a derived type using
+//! the `serde_compat` container attribute:
+//!
+//! ```rust
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(serde_compat)]
+//! struct Local {
+//!     value: u32,
+//! }
+//!
+//! let json = serde_json::to_string(&Local { value: 1 }).unwrap();
+//! let local: Local = serde_json::from_str(&json).unwrap();
+//! ```
+
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{
+        self, value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+        Visitor,
+    },
+    forward_to_deserialize_any, ser,
+};
+
+use crate::{Error, Intermediate, Map, Number};
+
+#[doc(hidden)]
+pub use serde;
+
+/// Serialize a given serde value into the intermediate representation.
+#[inline]
+pub fn serialize<T>(val: &T) -> Result<Intermediate<'_>, Error>
+where
+    T: ser::Serialize + ?Sized,
+{
+    val.serialize(Serializer)
+}
+
+/// Deserialize a serde value from the intermediate representation.
+#[inline]
+pub fn deserialize<T>(val: &Intermediate) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(val)
+}
+
+/// Replace a given serde value with a value deserialized from the
+/// intermediate representation.
+#[inline]
+pub fn update<T>(val: &mut T, input: &Intermediate) -> Result<(), Error>
+where
+    T: DeserializeOwned,
+{
+    *val = deserialize(input)?;
+
+    Ok(())
+}
+
+/// Wrapper implementing the serde traits for serde-lite types.
+///
+/// The wrapped value is serialized into/deserialized from the intermediate
+/// representation, which is then serialized/deserialized using serde.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SerdeCompat<T>(pub T);
+
+impl<T> SerdeCompat<T> {
+    /// Take the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for SerdeCompat<T> {
+    #[inline]
+    fn from(val: T) -> Self {
+        Self(val)
+    }
+}
+
+impl<T> Deref for SerdeCompat<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SerdeCompat<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> ser::Serialize for SerdeCompat<T>
+where
+    T: crate::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let val = self
+            .0
+            .serialize()
+            .map_err(<S::Error as ser::Error>::custom)?;
+
+        ser::Serialize::serialize(&val, serializer)
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for SerdeCompat<T>
+where
+    T: crate::Deserialize,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let val = <Intermediate as de::Deserialize>::deserialize(deserializer)?;
+
+        T::deserialize(&val)
+            .map(Self)
+            .map_err(<D::Error as de::Error>::custom)
+    }
+}
+
+impl ser::Error for Error {
+    #[inline]
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self::Custom(Cow::Owned(msg.to_string()))
+    }
+}
+
+impl de::Error for Error {
+    #[inline]
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self::Custom(Cow::Owned(msg.to_string()))
+    }
+}
+
+/// Serde serializer producing `Intermediate` values.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Bool(v))
+    }
+
+    #[inline]
+    fn serialize_i8(self, v: i8) -> Result<Intermediate<'static>, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_i16(self, v: i16) -> Result<Intermediate<'static>, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_i32(self, v: i32) -> Result<Intermediate<'static>, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    #[inline]
+    fn serialize_i64(self, v: i64) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Number(Number::SignedInt(v)))
+    }
+
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Number(Number::from(v)))
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<Intermediate<'static>, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<Intermediate<'static>, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<Intermediate<'static>, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    #[inline]
+    fn serialize_u64(self, v: u64) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Number(Number::UnsignedInt(v)))
+    }
+
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Number(Number::from(v)))
+    }
+
+    #[inline]
+    fn serialize_f32(self, v: f32) -> Result<Intermediate<'static>, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    #[inline]
+    fn serialize_f64(self, v: f64) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Number(Number::Float(v)))
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::String(Cow::Owned(v.to_string())))
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::String(Cow::Owned(String::from(v))))
+    }
+
+    #[inline]
+    fn serialize_bytes(self, v: &[u8]) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Bytes(Cow::Owned(v.to_vec())))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::None)
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<Intermediate<'static>, Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::None)
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::None)
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::String(Cow::Borrowed(variant)))
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Intermediate<'static>, Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Intermediate<'static>, Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        let mut map = Map::with_capacity(1);
+
+        map.insert_with_static_key(variant, value.serialize(self)?);
+
+        Ok(Intermediate::Map(map))
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec {
+            inner: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, Error> {
+        Ok(SerializeTupleVariant {
+            variant,
+            inner: Vec::with_capacity(len),
+        })
+    }
+
+    #[inline]
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            inner: Map::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    #[inline]
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant, Error> {
+        Ok(SerializeStructVariant {
+            variant,
+            inner: Map::with_capacity(len),
+        })
+    }
+}
+
+/// Serializer for sequences, tuples and tuple structs.
+pub struct SerializeVec {
+    inner: Vec<Intermediate<'static>>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.inner.push(value.serialize(Serializer)?);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Array(self.inner))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializer for tuple variants.
+pub struct SerializeTupleVariant {
+    variant: &'static str,
+    inner: Vec<Intermediate<'static>>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.inner.push(value.serialize(Serializer)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        let mut map = Map::with_capacity(1);
+
+        map.insert_with_static_key(self.variant, Intermediate::Array(self.inner));
+
+        Ok(Intermediate::Map(map))
+    }
+}
+
+/// Serializer for maps and structs.
+pub struct SerializeMap {
+    inner: Map<'static>,
+    key: Option<Cow<'static, str>>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        let key = match key.serialize(Serializer)?.into_string() {
+            Ok(s) => s,
+            Err(Intermediate::Number(Number::SignedInt(v))) => Cow::Owned(v.to_string()),
+            Err(Intermediate::Number(Number::UnsignedInt(v))) => Cow::Owned(v.to_string()),
+            Err(Intermediate::Number(Number::SignedInt128(v))) => Cow::Owned(v.to_string()),
+            Err(Intermediate::Number(Number::UnsignedInt128(v))) => Cow::Owned(v.to_string()),
+            Err(Intermediate::Bool(v)) => Cow::Owned(v.to_string()),
+            _ => return Err(Error::custom_static("map key must be a string")),
+        };
+
+        self.key = Some(key);
+
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::custom_static("map value serialized before its key"))?;
+
+        self.inner.insert(key, value.serialize(Serializer)?);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Map(self.inner))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.inner
+            .insert_with_static_key(key, value.serialize(Serializer)?);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::Map(self.inner))
+    }
+}
+
+/// Serializer for struct variants.
+pub struct SerializeStructVariant {
+    variant: &'static str,
+    inner: Map<'static>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Intermediate<'static>;
+    type Error = Error;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.inner
+            .insert_with_static_key(key, value.serialize(Serializer)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Intermediate<'static>, Error> {
+        let mut map = Map::with_capacity(1);
+
+        map.insert_with_static_key(self.variant, Intermediate::Map(self.inner));
+
+        Ok(Intermediate::Map(map))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &'de Intermediate<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Intermediate::None => visitor.visit_unit(),
+            Intermediate::Bool(v) => visitor.visit_bool(*v),
+            Intermediate::Number(Number::Float(v)) => visitor.visit_f64(*v),
+            Intermediate::Number(Number::SignedInt(v)) => visitor.visit_i64(*v),
+            Intermediate::Number(Number::UnsignedInt(v)) => visitor.visit_u64(*v),
+            Intermediate::Number(Number::SignedInt128(v)) => visitor.visit_i128(*v),
+            Intermediate::Number(Number::UnsignedInt128(v)) => visitor.visit_u128(*v),
+            Intermediate::String(v) => visitor.visit_borrowed_str(v),
+            Intermediate::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Intermediate::Array(v) => visitor.visit_seq(SeqDeserializer { iter: v.iter() }),
+            Intermediate::Map(v) => visitor.visit_map(MapDeserializer {
+                iter: v.iter(),
+                value: None,
+            }),
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_none() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match self {
+            Intermediate::String(variant) => (&**variant, None),
+            Intermediate::Map(map) if map.len() == 1 => map
+                .iter()
+                .next()
+                .map(|(variant, value)| (&**variant, Some(value)))
+                .ok_or(Error::UnknownEnumVariant)?,
+            _ => return Err(Error::invalid_value_static("enum variant")),
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier
+    }
+}
+
+/// Deserializer for arrays.
+struct SeqDeserializer<'de> {
+    iter: std::slice::Iter<'de, Intermediate<'de>>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = Error;
+
+    #[inline]
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.iter.next().map(|v| seed.deserialize(v)).transpose()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializer for maps.
+struct MapDeserializer<'de> {
+    iter: <&'de Map<'de> as IntoIterator>::IntoIter,
+    value: Option<&'de Intermediate<'de>>,
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if let Some((k, v)) = self.iter.next() {
+            self.value = Some(v);
+
+            seed.deserialize(BorrowedStrDeserializer::<Error>::new(k))
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::custom_static("map value requested before its key"))?;
+
+        seed.deserialize(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializer for externally tagged enums.
+struct EnumDeserializer<'de> {
+    variant: &'de str,
+    value: Option<&'de Intermediate<'de>>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = VariantDeserializer<'de>;
+
+    #[inline]
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+/// Deserializer for enum variant content.
+struct VariantDeserializer<'de> {
+    value: Option<&'de Intermediate<'de>>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer<'de> {
+    type Error = Error;
+
+    #[inline]
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            None | Some(Intermediate::None) => Ok(()),
+            Some(_) => Err(Error::invalid_value_static("unit variant")),
+        }
+    }
+
+    #[inline]
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let value = self.value.ok_or(Error::MissingEnumVariantContent)?;
+
+        seed.deserialize(value)
+    }
+
+    #[inline]
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let value = self.value.ok_or(Error::MissingEnumVariantContent)?;
+
+        de::Deserializer::deserialize_seq(value, visitor)
+    }
+
+    #[inline]
+    fn struct_variant<V>(self, _: &'static [&'static str], visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let value = self.value.ok_or(Error::MissingEnumVariantContent)?;
+
+        de::Deserializer::deserialize_map(value, visitor)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Intermediate<'de> {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
@@ -0,0 +1,780 @@
+//! CSV (RFC 4180).
+//!
+//! The first record of the input is used as a header. Column names must be
+//! unique. Every other record is converted into an `Intermediate::Map` keyed
+//! by the column names. All values are read as strings. The target type
+//! must implement `Describe` and its description determines which columns
+//! are converted into numbers or bools (empty values of such columns are
+//! converted into `Intermediate::None`):
+//!
+//! ```rust
+//! use serde_lite::csv::CsvReader;
+//! use serde_lite_derive::{Deserialize, Describe};
+//!
+//! #[derive(Deserialize, Describe)]
+//! struct Person {
+//!     name: String,
+//!     age: u32,
+//!     active: bool,
+//! }
+//!
+//! let input = "name;age;active\n\"Doe; John\";42;true\n";
+//!
+//! let people = CsvReader::<_, Person>::new(input.as_bytes())
+//!     .with_delimiter(b';')
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//!
+//! assert_eq!(people[0].name, "Doe; John");
+//! assert_eq!(people[0].age, 42);
+//! assert!(people[0].active);
+//! ```
+//!
+//! The writer accepts values serialized as flat maps. The header is taken
+//! from the first value. Nested values are rejected.
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    io::{BufRead, Read, Write},
+    marker::PhantomData,
+};
+
+use crate::{
+    describe::{Fields, Shape},
+    emit::{Emitter, Scalar},
+    float, Describe, Deserialize, Error, Intermediate, LineError, Map, NamedFieldError, Number,
+    Serialize,
+};
+
+/// Serialize all items of a given iterator as CSV into a given writer.
+#[inline]
+pub fn to_writer<W, I>(writer: W, items: I) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Serialize,
+{
+    let mut writer = CsvWriter::new(writer);
+
+    writer.write_all(items)?;
+    writer.flush()
+}
+
+/// Conversion applied to all values of a column.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Coercion {
+    None,
+    Number,
+    Bool,
+}
+
+impl Coercion {
+    /// Get the conversion for values of a given shape.
+    fn from_shape(shape: &Shape) -> Self {
+        match shape {
+            Shape::Bool => Self::Bool,
+            Shape::Integer { .. } | Shape::Float => Self::Number,
+            Shape::Option(inner) => Self::from_shape(&inner()),
+            _ => Self::None,
+        }
+    }
+}
+
+/// Get the conversions for all columns of records described by a given
+/// shape.
+#[inline(never)]
+fn column_plan(shape: &Shape, header: &[String]) -> Vec<Coercion> {
+    let mut res = vec![Coercion::None; header.len()];
+
+    match shape {
+        Shape::Map(value) => res.fill(Coercion::from_shape(&value())),
+        Shape::Struct(s) => plan_fields(s.fields(), header, &mut res),
+        _ => (),
+    }
+
+    res
+}
+
+/// Set the conversions for columns corresponding to given fields.
+fn plan_fields(fields: &Fields, header: &[String], plan: &mut [Coercion]) {
+    let Fields::Named(fields) = fields else {
+        return;
+    };
+
+    for field in fields {
+        if field.is_flattened() {
+            if let Shape::Struct(s) = field.shape() {
+                plan_fields(s.fields(), header, plan);
+            }
+        } else if let Some(column) = header.iter().position(|h| h == field.name()) {
+            plan[column] = Coercion::from_shape(&field.shape());
+        }
+    }
+}
+
+/// CSV reader.
+///
+/// The reader yields one deserialized value per record. Empty lines are
+/// ignored. Errors are reported as `Error::Line` containing the line number
+/// where the corresponding record starts. The reader stops after the first
+/// syntax error.
+///
+/// The length of a record is not limited by default. Use
+/// `with_max_record_length` when reading untrusted input.
+pub struct CsvReader<R, T> {
+    reader: R,
+    delimiter: u8,
+    max_record_length: usize,
+    header: Vec<String>,
+    has_header: bool,
+    plan: Option<Vec<Coercion>>,
+    buffer: Vec<u8>,
+    record: Vec<String>,
+    line: usize,
+    done: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<R, T> CsvReader<R, T> {
+    /// Create a new CSV reader.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            delimiter: b',',
+            max_record_length: usize::MAX,
+            header: Vec::new(),
+            has_header: false,
+            plan: None,
+            buffer: Vec::new(),
+            record: Vec::new(),
+            line: 0,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Use a given field delimiter (the default is `,`).
+    #[inline]
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the maximum length of a record in bytes (including line breaks in
+    /// quoted fields but not the line break terminating the record).
+    ///
+    /// The header is a record as well.
+    #[inline]
+    pub fn with_max_record_length(mut self, max_record_length: usize) -> Self {
+        self.max_record_length = max_record_length;
+        self
+    }
+
+    /// Get the number of lines read so far.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Take the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, T> CsvReader<R, T>
+where
+    R: BufRead,
+{
+    /// Get the column names.
+    ///
+    /// The header is read from the input if it has not been read yet.
+    /// Duplicate column names are rejected.
+    #[inline]
+    pub fn header(&mut self) -> Result<&[String], Error> {
+        if !self.has_header {
+            self.read_header()?;
+        }
+
+        Ok(&self.header)
+    }
+
+    /// Read the header.
+    #[inline(never)]
+    fn read_header(&mut self) -> Result<(), Error> {
+        let line = self.line + 1;
+
+        let header = if self
+            .read_record()
+            .map_err(|err| LineError::new(line, err))?
+        {
+            std::mem::take(&mut self.record)
+        } else {
+            Vec::new()
+        };
+
+        // skip the empty lines preceding the header
+        let line = self.line + 1 - record_lines(&header);
+
+        let mut names = HashSet::with_capacity(header.len());
+
+        for name in &header {
+            if !names.insert(name.as_str()) {
+                let err = Error::custom(format_args!("duplicate column name \"{}\"", name));
+
+                return Err(LineError::new(line, err).into());
+            }
+        }
+
+        self.header = header;
+        self.has_header = true;
+
+        Ok(())
+    }
+
+    /// Read the next record as a map keyed by the column names.
+    ///
+    /// All values are strings.
+    #[inline]
+    pub fn read_row(&mut self) -> Result<Option<Intermediate<'static>>, Error> {
+        self.next_row().map(|row| row.map(|(_, row)| row))
+    }
+
+    /// Read the next record together with its line number.
+    #[inline(never)]
+    fn next_row(&mut self) -> Result<Option<(usize, Intermediate<'static>)>, Error> {
+        self.header()?;
+
+        let line = self.line + 1;
+
+        if !self
+            .read_record()
+            .map_err(|err| LineError::new(line, err))?
+        {
+            return Ok(None);
+        }
+
+        // skip the empty lines preceding the record
+        let line = self.line + 1 - record_lines(&self.record);
+
+        let header = &self.header;
+
+        if self.record.len() != header.len() {
+            let err = Error::custom(format_args!(
+                "expected {} fields, found {}",
+                header.len(),
+                self.record.len()
+            ));
+
+            return Err(LineError::new(line, err).into());
+        }
+
+        let mut row = Map::with_capacity(header.len());
+
+        for (name, value) in header.iter().zip(self.record.drain(..)) {
+            row.insert_with_owned_key(name.clone(), Intermediate::String(Cow::Owned(value)));
+        }
+
+        Ok(Some((line, Intermediate::Map(row))))
+    }
+
+    /// Read the next record. The method returns `false` if there are no
+    /// more records.
+    #[inline(never)]
+    fn read_record(&mut self) -> Result<bool, Error> {
+        self.record.clear();
+
+        let mut field = Vec::new();
+
+        // the current field is quoted and the closing quote has not been
+        // reached yet
+        let mut quoted = false;
+
+        // the closing quote of the current field has been reached
+        let mut closed = false;
+
+        // number of bytes of the current record read so far
+        let mut total = 0;
+
+        loop {
+            if total > self.max_record_length {
+                return Err(Error::custom_static("maximum record length exceeded"));
+            }
+
+            self.buffer.clear();
+
+            // read at most one byte more than allowed, so that the line break
+            // following a record of the maximum length fits in as well
+            let limit = ((self.max_record_length - total) as u64).saturating_add(1);
+
+            let len = (&mut self.reader)
+                .take(limit)
+                .read_until(b'\n', &mut self.buffer)
+                .map_err(Error::custom)?;
+
+            total += len;
+
+            if total > self.max_record_length && !self.buffer.ends_with(b"\n") {
+                return Err(Error::custom_static("maximum record length exceeded"));
+            }
+
+            if len == 0 {
+                if quoted {
+                    return Err(Error::custom_static("unterminated quoted field"));
+                } else if self.record.is_empty() && field.is_empty() && !closed {
+                    return Ok(false);
+                }
+
+                // the last record is not followed by a line break
+                self.record.push(into_string(field)?);
+
+                return Ok(true);
+            }
+
+            self.line += 1;
+
+            let empty = matches!(&self.buffer[..], b"\n" | b"\r\n");
+
+            if empty && !quoted && self.record.is_empty() && field.is_empty() && !closed {
+                total = 0;
+
+                continue;
+            }
+
+            let mut bytes = self.buffer.iter().copied().peekable();
+
+            while let Some(b) = bytes.next() {
+                if quoted {
+                    if b != b'"' {
+                        field.push(b);
+                    } else if bytes.peek() == Some(&b'"') {
+                        field.push(b'"');
+
+                        bytes.next();
+                    } else {
+                        quoted = false;
+                        closed = true;
+                    }
+                } else if b == self.delimiter {
+                    self.record.push(into_string(std::mem::take(&mut field))?);
+
+                    closed = false;
+                } else if b == b'\n' || (b == b'\r' && bytes.peek() == Some(&b'\n')) {
+                    if b == b'\n' {
+                        self.record.push(into_string(field)?);
+
+                        return Ok(true);
+                    }
+                } else if closed {
+                    return Err(Error::custom_static(
+                        "unexpected character after a quoted field",
+                    ));
+                } else if b == b'"' && field.is_empty() {
+                    quoted = true;
+                } else if b == b'"' {
+                    return Err(Error::custom_static(
+                        "unexpected quote in an unquoted field",
+                    ));
+                } else {
+                    field.push(b);
+                }
+            }
+        }
+    }
+}
+
+impl<R, T> CsvReader<R, T>
+where
+    R: BufRead,
+    T: Deserialize + Describe,
+{
+    /// Deserialize a given row.
+    ///
+    /// String values are converted into numbers or bools if the target type
+    /// needs it.
+    #[inline]
+    fn deserialize_row(&mut self, mut row: Intermediate<'static>) -> Result<T, Error> {
+        let plan = self
+            .plan
+            .get_or_insert_with(|| column_plan(&T::describe(), &self.header));
+
+        apply_plan(&mut row, &self.header, plan);
+
+        T::deserialize(&row)
+    }
+}
+
+impl<R, T> Iterator for CsvReader<R, T>
+where
+    R: BufRead,
+    T: Deserialize + Describe,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_row() {
+            Ok(Some((line, row))) => {
+                let res = self.deserialize_row(row);
+
+                Some(res.map_err(|err| LineError::new(line, err).into()))
+            }
+            Ok(None) => {
+                self.done = true;
+
+                None
+            }
+            Err(err) => {
+                self.done = true;
+
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Convert values of a given row according to a given plan.
+#[inline(never)]
+fn apply_plan(row: &mut Intermediate, header: &[String], plan: &[Coercion]) {
+    if let Intermediate::Map(map) = row {
+        for (name, coercion) in header.iter().zip(plan) {
+            if let Some(val) = map.get_mut(name.as_str()) {
+                coerce(val, *coercion);
+            }
+        }
+    }
+}
+
+/// Convert a given string value.
+///
+/// Empty strings are converted into `Intermediate::None`. Values that cannot
+/// be converted are left untouched.
+#[inline(never)]
+fn coerce(val: &mut Intermediate, coercion: Coercion) {
+    let s = match (val.as_str(), coercion) {
+        (_, Coercion::None) | (None, _) => return,
+        (Some(s), _) => s.trim(),
+    };
+
+    let res = if s.is_empty() {
+        Some(Intermediate::None)
+    } else if coercion == Coercion::Number {
+        parse_number(s).map(Intermediate::Number)
+    } else if s.eq_ignore_ascii_case("true") {
+        Some(Intermediate::Bool(true))
+    } else if s.eq_ignore_ascii_case("false") {
+        Some(Intermediate::Bool(false))
+    } else {
+        None
+    };
+
+    if let Some(res) = res {
+        *val = res;
+    }
+}
+
+/// Parse a given number.
+#[inline]
+fn parse_number(s: &str) -> Option<Number> {
+    if let Ok(v) = s.parse() {
+        Some(Number::UnsignedInt(v))
+    } else if let Ok(v) = s.parse() {
+        Some(Number::SignedInt(v))
+    } else if let Ok(v) = s.parse::<u128>() {
+        Some(Number::from(v))
+    } else if let Ok(v) = s.parse::<i128>() {
+        Some(Number::from(v))
+    } else {
+        s.parse().ok().map(Number::Float)
+    }
+}
+
+/// Get the number of lines occupied by a given record.
+#[inline]
+fn record_lines(record: &[String]) -> usize {
+    1 + record
+        .iter()
+        .map(|field| field.bytes().filter(|&b| b == b'\n').count())
+        .sum::<usize>()
+}
+
+/// Convert given field bytes into a string.
+#[inline]
+fn into_string(field: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(field).map_err(|_| Error::custom_static("invalid UTF-8"))
+}
+
+/// CSV writer.
+///
+/// The header is written together with the first value. All values must be
+/// serialized as flat maps. Missing columns are left empty. Errors are
+/// reported as `Error::Line` containing the line number where the
+/// corresponding record would start.
+pub struct CsvWriter<W> {
+    writer: W,
+    delimiter: u8,
+    header: Option<Vec<String>>,
+    cells: Vec<(String, String)>,
+    buffer: Vec<u8>,
+    line: usize,
+}
+
+impl<W> CsvWriter<W> {
+    /// Create a new CSV writer.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            delimiter: b',',
+            header: None,
+            cells: Vec::new(),
+            buffer: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// Use a given field delimiter (the default is `,`).
+    #[inline]
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Get the number of lines written so far.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Take the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> CsvWriter<W>
+where
+    W: Write,
+{
+    /// Write a given value as a single record.
+    #[inline]
+    pub fn write<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.cells.clear();
+
+        let mut emitter = RowEmitter {
+            cells: &mut self.cells,
+            depth: 0,
+            key: None,
+            failed: false,
+        };
+
+        let res = value.serialize_to(&mut emitter).map_err(|err| {
+            // add the field path unless the error already has one
+            match emitter.key.take() {
+                Some(key) if emitter.failed && !matches!(err, Error::NamedFieldErrors(_)) => {
+                    NamedFieldError::new(key, err).into()
+                }
+                _ => err,
+            }
+        });
+
+        res.and_then(|_| self.write_record())
+            .map_err(|err| LineError::new(self.line + 1, err).into())
+    }
+
+    /// Write all items of a given iterator.
+    pub fn write_all<I>(&mut self, items: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        for item in items {
+            self.write(&item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(Error::custom)
+    }
+
+    /// Write the current cells (and the header if it has not been written
+    /// yet).
+    #[inline(never)]
+    fn write_record(&mut self) -> Result<(), Error> {
+        self.buffer.clear();
+
+        let header = if let Some(header) = self.header.as_ref() {
+            header
+        } else {
+            let header = self
+                .cells
+                .iter()
+                .map(|(k, _)| k.clone())
+                .collect::<Vec<_>>();
+
+            write_record(&mut self.buffer, self.delimiter, header.iter());
+
+            self.header.insert(header)
+        };
+
+        let mut record = vec![""; header.len()];
+
+        for (index, (key, value)) in self.cells.iter().enumerate() {
+            let column = if header.get(index) == Some(key) {
+                Some(index)
+            } else {
+                header.iter().position(|h| h == key)
+            };
+
+            if let Some(column) = column {
+                record[column] = value;
+            } else {
+                let err = Error::custom_static("unknown column");
+
+                return Err(NamedFieldError::new(key, err).into());
+            }
+        }
+
+        write_record(&mut self.buffer, self.delimiter, record.into_iter());
+
+        self.writer.write_all(&self.buffer).map_err(Error::custom)?;
+
+        self.line += self.buffer.iter().filter(|&&b| b == b'\n').count();
+
+        Ok(())
+    }
+}
+
+/// Append a given record to a given buffer.
+#[inline(never)]
+fn write_record<I, T>(buffer: &mut Vec<u8>, delimiter: u8, fields: I)
+where
+    I: Iterator<Item = T>,
+    T: AsRef<str>,
+{
+    for (index, field) in fields.enumerate() {
+        let field = field.as_ref();
+
+        if index > 0 {
+            buffer.push(delimiter);
+        }
+
+        let quote = field
+            .bytes()
+            .any(|b| b == delimiter || b == b'"' || b == b'\r' || b == b'\n');
+
+        if quote {
+            buffer.push(b'"');
+
+            for b in field.bytes() {
+                if b == b'"' {
+                    buffer.push(b'"');
+                }
+
+                buffer.push(b);
+            }
+
+            buffer.push(b'"');
+        } else {
+            buffer.extend_from_slice(field.as_bytes());
+        }
+    }
+
+    buffer.extend_from_slice(b"\r\n");
+}
+
+/// Emitter collecting cells of a single record.
+struct RowEmitter<'a> {
+    cells: &'a mut Vec<(String, String)>,
+    depth: usize,
+    key: Option<String>,
+    failed: bool,
+}
+
+impl RowEmitter<'_> {
+    /// Create an error for the current field.
+    #[inline(never)]
+    fn error(&mut self, msg: &'static str) -> Error {
+        self.failed = true;
+
+        Error::custom_static(msg)
+    }
+}
+
+impl Emitter for RowEmitter<'_> {
+    fn scalar(&mut self, value: Scalar<'_>) -> Result<(), Error> {
+        let key = match (self.depth, self.key.take()) {
+            (1, Some(key)) => key,
+            _ => return Err(self.error("CSV record must be a map")),
+        };
+
+        let value = match value {
+            Scalar::None => String::new(),
+            Scalar::Bool(v) => v.to_string(),
+            Scalar::Number(Number::Float(v)) if v.is_finite() => format!("{:?}", v),
+            Scalar::Number(Number::Float(v)) => String::from(float::to_token(v)),
+            Scalar::Number(Number::SignedInt(v)) => v.to_string(),
+            Scalar::Number(Number::UnsignedInt(v)) => v.to_string(),
+            Scalar::Number(Number::SignedInt128(v)) => v.to_string(),
+            Scalar::Number(Number::UnsignedInt128(v)) => v.to_string(),
+            Scalar::String(v) => String::from(v),
+            Scalar::Bytes(_) => {
+                self.key = Some(key);
+
+                return Err(self.error("byte strings are not supported"));
+            }
+        };
+
+        self.cells.push((key, value));
+
+        Ok(())
+    }
+
+    fn begin_array(&mut self, _: Option<usize>) -> Result<(), Error> {
+        if self.depth == 0 {
+            Err(self.error("CSV record must be a map"))
+        } else {
+            Err(self.error("nested values are not supported"))
+        }
+    }
+
+    fn end_array(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn begin_map(&mut self, _: Option<usize>) -> Result<(), Error> {
+        if self.depth == 0 {
+            self.depth = 1;
+
+            Ok(())
+        } else {
+            Err(self.error("nested values are not supported"))
+        }
+    }
+
+    fn key(&mut self, key: &str) -> Result<(), Error> {
+        self.key = Some(String::from(key));
+
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<(), Error> {
+        self.depth = 0;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,556 @@
+//! Runtime description of the serialized form of types.
+//!
+//! Types implementing the `Describe` trait can provide a description of
+//! their wire shape (i.e. the shape of their intermediate representation).
+//! The description can be used for introspection at runtime, e.g. for
+//! generating documentation or building user interfaces. The trait can be
+//! derived using the `Describe` derive macro which honors the same
+//! attributes as the other derive macros:
+//!
+//! ```rust
+//! use serde_lite::{
+//!     describe::{Fields, Shape},
+//!     Describe,
+//! };
+//! use serde_lite_derive::Describe;
+//!
+//! #[derive(Describe)]
+//! struct User {
+//!     #[serde(rename = "userName")]
+//!     name: String,
+//!     #[serde(default)]
+//!     email: Option<String>,
+//! }
+//!
+//! let Shape::Struct(user) = User::describe() else {
+//!     panic!("struct expected");
+//! };
+//!
+//! let Fields::Named(fields) = user.fields() else {
+//!     panic!("named fields expected");
+//! };
+//!
+//! assert_eq!(fields[0].name(), "userName");
+//! assert!(matches!(fields[0].shape(), Shape::String));
+//! assert!(fields[1].has_default());
+//! assert!(matches!(fields[1].shape(), Shape::Option(_)));
+//! ```
+//!
+//! Nested shapes are referenced using functions returning the shape (see
+//! `ShapeFn`), so the description of recursive types is finite.
+//!
+//! Fields using a custom serializer (except for the `bytes` attribute) are
+//! described as `Shape::Any` unless the `describe_with` attribute is used.
+//! The attribute takes a path to a function returning the field shape.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    bytes::{ByteBuf, Bytes},
+    Intermediate, Number,
+};
+
+/// Describe trait.
+///
+/// The trait can be implemented by objects that can describe their
+/// serialized form.
+pub trait Describe {
+    /// Get the shape of the serialized value.
+    fn describe() -> Shape;
+}
+
+/// Function returning a shape.
+pub type ShapeFn = fn() -> Shape;
+
+/// Shape of a serialized value.
+#[derive(Debug, Clone)]
+pub enum Shape {
+    /// Any value.
+    Any,
+    /// Null (e.g. the unit type).
+    Null,
+    /// Boolean.
+    Bool,
+    /// Integer within a given range.
+    Integer { min: i128, max: u128 },
+    /// Floating point number (or any number).
+    Float,
+    /// String containing a single character.
+    Char,
+    /// String.
+    String,
+    /// Byte sequence.
+    Bytes,
+    /// Optional value (i.e. the value or null).
+    Option(ShapeFn),
+    /// Sequence of values of the same shape.
+    Seq(ShapeFn),
+    /// Fixed-size array of values of the same shape.
+    Array(ShapeFn, usize),
+    /// Tuple (i.e. a fixed-size array of values of given shapes).
+    Tuple(Vec<ShapeFn>),
+    /// Map with string keys and values of the same shape.
+    Map(ShapeFn),
+    /// Struct.
+    Struct(Struct),
+    /// Enum.
+    Enum(Enum),
+}
+
+impl Shape {
+    /// Get the name of the corresponding Rust type without the path and
+    /// generic arguments (if available).
+    ///
+    /// The name is available only for structs and enums.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Struct(s) => Some(s.name),
+            Self::Enum(e) => Some(e.name),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the corresponding Rust type (if available).
+    ///
+    /// The name is available only for structs and enums. It is the full name
+    /// as returned by `std::any::type_name`, so it can be used to identify
+    /// the type.
+    pub fn type_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Struct(s) => Some(s.type_name),
+            Self::Enum(e) => Some(e.type_name),
+            _ => None,
+        }
+    }
+}
+
+/// Struct description.
+#[derive(Debug, Clone)]
+pub struct Struct {
+    name: &'static str,
+    type_name: &'static str,
+    fields: Fields,
+}
+
+impl Struct {
+    /// Create a new struct description.
+    #[inline]
+    pub const fn new(name: &'static str, type_name: &'static str, fields: Fields) -> Self {
+        Self {
+            name,
+            type_name,
+            fields,
+        }
+    }
+
+    /// Get the struct name.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the full name of the Rust type.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Get the struct fields.
+    #[inline]
+    pub fn fields(&self) -> &Fields {
+        &self.fields
+    }
+}
+
+/// Struct or enum variant fields.
+///
+/// Unit structs and structs with no unnamed fields are serialized as null,
+/// structs with a single unnamed field are serialized as the field itself,
+/// structs with more unnamed fields are serialized as arrays and structs
+/// with named fields are serialized as maps.
+#[derive(Debug, Clone)]
+pub enum Fields {
+    /// No fields.
+    Unit,
+    /// Unnamed fields.
+    Unnamed(Vec<ShapeFn>),
+    /// Named fields.
+    Named(Vec<Field>),
+}
+
+/// Named field description.
+#[derive(Debug, Copy, Clone)]
+pub struct Field {
+    name: &'static str,
+    shape: ShapeFn,
+    flags: u8,
+}
+
+impl Field {
+    /// The default value is used if the field is missing.
+    pub const DEFAULT: u8 = 1;
+
+    /// The field is never serialized.
+    pub const SKIP_SERIALIZING: u8 = 2;
+
+    /// The field is never deserialized.
+    pub const SKIP_DESERIALIZING: u8 = 4;
+
+    /// The field is serialized only if a given condition is not met.
+    pub const SKIP_SERIALIZING_IF: u8 = 8;
+
+    /// The field is flattened.
+    pub const FLATTEN: u8 = 16;
+
+    /// Create a new field description.
+    #[inline]
+    pub const fn new(name: &'static str, shape: ShapeFn) -> Self {
+        Self {
+            name,
+            shape,
+            flags: 0,
+        }
+    }
+
+    /// Set the field flags.
+    #[inline]
+    pub const fn with_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Get the field name (after rename).
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the field shape.
+    #[inline]
+    pub fn shape(&self) -> Shape {
+        (self.shape)()
+    }
+
+    /// Get the function returning the field shape.
+    #[inline]
+    pub fn shape_fn(&self) -> ShapeFn {
+        self.shape
+    }
+
+    /// Check if the field may be missing in the input (i.e. the default
+    /// value is used or the field is not deserialized at all).
+    #[inline]
+    pub fn is_optional(&self) -> bool {
+        (self.flags & (Self::DEFAULT | Self::SKIP_DESERIALIZING)) != 0
+    }
+
+    /// Check if the default value is used if the field is missing.
+    #[inline]
+    pub fn has_default(&self) -> bool {
+        (self.flags & Self::DEFAULT) != 0
+    }
+
+    /// Check if the field is skipped completely.
+    #[inline]
+    pub fn is_skipped(&self) -> bool {
+        self.skip_serializing() && self.skip_deserializing()
+    }
+
+    /// Check if the field is never serialized.
+    #[inline]
+    pub fn skip_serializing(&self) -> bool {
+        (self.flags & Self::SKIP_SERIALIZING) != 0
+    }
+
+    /// Check if the field is never deserialized.
+    #[inline]
+    pub fn skip_deserializing(&self) -> bool {
+        (self.flags & Self::SKIP_DESERIALIZING) != 0
+    }
+
+    /// Check if the field is serialized only if a given condition is not met
+    /// (i.e. it may be missing in the output).
+    #[inline]
+    pub fn skip_serializing_if(&self) -> bool {
+        (self.flags & Self::SKIP_SERIALIZING_IF) != 0
+    }
+
+    /// Check if the field is flattened.
+    #[inline]
+    pub fn is_flattened(&self) -> bool {
+        (self.flags & Self::FLATTEN) != 0
+    }
+}
+
+/// Enum description.
+#[derive(Debug, Clone)]
+pub struct Enum {
+    name: &'static str,
+    type_name: &'static str,
+    repr: EnumRepr,
+    variants: Vec<Variant>,
+}
+
+impl Enum {
+    /// Create a new enum description.
+    #[inline]
+    pub const fn new(
+        name: &'static str,
+        type_name: &'static str,
+        repr: EnumRepr,
+        variants: Vec<Variant>,
+    ) -> Self {
+        Self {
+            name,
+            type_name,
+            repr,
+            variants,
+        }
+    }
+
+    /// Get the enum name.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the full name of the Rust type.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Get the enum representation.
+    #[inline]
+    pub fn repr(&self) -> EnumRepr {
+        self.repr
+    }
+
+    /// Get the enum variants.
+    #[inline]
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+}
+
+/// Enum representation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EnumRepr {
+    /// Unit variants are serialized as their names, other variants are
+    /// serialized as single-entry maps `{ variant: content }`.
+    External,
+    /// Variants are serialized as maps containing the variant name under a
+    /// given key together with the variant fields.
+    Internal { tag: &'static str },
+    /// Variants are serialized as maps containing the variant name under a
+    /// given key and the variant content under another key.
+    Adjacent {
+        tag: &'static str,
+        content: &'static str,
+    },
+}
+
+/// Enum variant description.
+#[derive(Debug, Clone)]
+pub struct Variant {
+    name: &'static str,
+    fields: Fields,
+}
+
+impl Variant {
+    /// Create a new variant description.
+    #[inline]
+    pub const fn new(name: &'static str, fields: Fields) -> Self {
+        Self { name, fields }
+    }
+
+    /// Get the variant name (after rename).
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the variant fields.
+    #[inline]
+    pub fn fields(&self) -> &Fields {
+        &self.fields
+    }
+}
+
+macro_rules! describe_as {
+    ( $shape:expr => $($x:ty),* ) => {
+        $(
+            impl Describe for $x {
+                #[inline]
+                fn describe() -> Shape {
+                    $shape
+                }
+            }
+        )*
+    };
+}
+
+describe_as!(Shape::Null => ());
+describe_as!(Shape::Bool => bool);
+describe_as!(Shape::Float => f32, f64, Number);
+describe_as!(Shape::Char => char);
+describe_as!(Shape::String => String, str, Cow<'_, str>);
+describe_as!(Shape::Bytes => Bytes<'_>, ByteBuf);
+describe_as!(Shape::Any => Intermediate<'_>);
+
+macro_rules! describe_int {
+    ( $($x:ty),* ) => {
+        $(
+            impl Describe for $x {
+                #[inline]
+                fn describe() -> Shape {
+                    Shape::Integer {
+                        min: <$x>::MIN as i128,
+                        max: <$x>::MAX as u128,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+describe_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T> Describe for Option<T>
+where
+    T: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Option(T::describe)
+    }
+}
+
+impl<T> Describe for [T]
+where
+    T: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Seq(T::describe)
+    }
+}
+
+impl<T> Describe for Vec<T>
+where
+    T: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Seq(T::describe)
+    }
+}
+
+impl<T, const N: usize> Describe for [T; N]
+where
+    T: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Array(T::describe, N)
+    }
+}
+
+macro_rules! describe_tuple {
+    ( $($t:ident)+ ) => {
+        impl<$($t),+> Describe for ($($t,)+)
+        where
+            $($t: Describe),+
+        {
+            #[inline]
+            fn describe() -> Shape {
+                Shape::Tuple(vec![$($t::describe),+])
+            }
+        }
+    };
+}
+
+describe_tuple!(T0);
+describe_tuple!(T0 T1);
+describe_tuple!(T0 T1 T2);
+describe_tuple!(T0 T1 T2 T3);
+describe_tuple!(T0 T1 T2 T3 T4);
+describe_tuple!(T0 T1 T2 T3 T4 T5);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+impl<K, V, S> Describe for HashMap<K, V, S>
+where
+    V: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Map(V::describe)
+    }
+}
+
+#[cfg(feature = "preserve-order")]
+impl<K, V, S> Describe for indexmap::IndexMap<K, V, S>
+where
+    V: Describe,
+{
+    #[inline]
+    fn describe() -> Shape {
+        Shape::Map(V::describe)
+    }
+}
+
+impl<T> Describe for &T
+where
+    T: Describe + ?Sized,
+{
+    #[inline]
+    fn describe() -> Shape {
+        T::describe()
+    }
+}
+
+impl<T> Describe for &mut T
+where
+    T: Describe + ?Sized,
+{
+    #[inline]
+    fn describe() -> Shape {
+        T::describe()
+    }
+}
+
+macro_rules! describe_wrapper {
+    ( $x:ident ) => {
+        impl<T> Describe for $x<T>
+        where
+            T: Describe + ?Sized,
+        {
+            #[inline]
+            fn describe() -> Shape {
+                T::describe()
+            }
+        }
+    };
+}
+
+describe_wrapper!(Box);
+describe_wrapper!(Rc);
+describe_wrapper!(Arc);
+describe_wrapper!(Cell);
+describe_wrapper!(RefCell);
+describe_wrapper!(Mutex);
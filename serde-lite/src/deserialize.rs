@@ -8,7 +8,9 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use crate::{Error, Intermediate};
+use crate::{
+    bytes::Bytes, float, map, Error, ErrorList, Intermediate, NamedFieldError, UnnamedFieldError,
+};
 
 /// Deserialize trait.
 ///
@@ -21,6 +23,71 @@ pub trait Deserialize {
         Self: Sized;
 }
 
+/// Deserialize trait for objects borrowing data from the intermediate
+/// representation.
+///
+/// The trait is implemented for all types implementing `Deserialize`, for
+/// `&str`, `Cow<str>` and `Bytes` and for their optional variants. It is
+/// also implemented by the `Deserialize` derive macro for types with lifetime
+/// parameters.
+pub trait DeserializeBorrowed<'de>: Sized {
+    /// Deserialize an object instance borrowing data from a given value.
+    fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error>;
+}
+
+impl<'de, T> DeserializeBorrowed<'de> for T
+where
+    T: Deserialize,
+{
+    #[inline]
+    fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error> {
+        T::deserialize(val)
+    }
+}
+
+impl<'de> DeserializeBorrowed<'de> for &'de str {
+    #[inline]
+    fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error> {
+        val.as_str()
+            .ok_or_else(|| Error::invalid_value_static("string"))
+    }
+}
+
+impl<'de> DeserializeBorrowed<'de> for Cow<'de, str> {
+    #[inline]
+    fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error> {
+        <&str>::deserialize_borrowed(val).map(Cow::Borrowed)
+    }
+}
+
+impl<'de> DeserializeBorrowed<'de> for Bytes<'de> {
+    #[inline]
+    fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error> {
+        val.as_bytes()
+            .map(Bytes::new)
+            .ok_or_else(|| Error::invalid_value_static("bytes"))
+    }
+}
+
+macro_rules! deserialize_borrowed_option {
+    ( $ty:ty ) => {
+        impl<'de> DeserializeBorrowed<'de> for Option<$ty> {
+            #[inline]
+            fn deserialize_borrowed(val: &'de Intermediate<'de>) -> Result<Self, Error> {
+                if val.is_none() {
+                    Ok(None)
+                } else {
+                    <$ty>::deserialize_borrowed(val).map(Some)
+                }
+            }
+        }
+    };
+}
+
+deserialize_borrowed_option!(&'de str);
+deserialize_borrowed_option!(Cow<'de, str>);
+deserialize_borrowed_option!(Bytes<'de>);
+
 impl Deserialize for bool {
     #[inline]
     fn deserialize(val: &Intermediate) -> Result<Self, Error> {
@@ -59,28 +126,16 @@ deserialize_for_signed_int!(i8);
 deserialize_for_signed_int!(i16);
 deserialize_for_signed_int!(i32);
 deserialize_for_signed_int!(i64);
+deserialize_for_signed_int!(i128);
 deserialize_for_signed_int!(isize);
 
 deserialize_for_unsigned_int!(u8);
 deserialize_for_unsigned_int!(u16);
 deserialize_for_unsigned_int!(u32);
 deserialize_for_unsigned_int!(u64);
+deserialize_for_unsigned_int!(u128);
 deserialize_for_unsigned_int!(usize);
 
-impl Deserialize for i128 {
-    #[inline]
-    fn deserialize(val: &Intermediate) -> Result<Self, Error> {
-        i64::deserialize(val).map(|v| v.into())
-    }
-}
-
-impl Deserialize for u128 {
-    #[inline]
-    fn deserialize(val: &Intermediate) -> Result<Self, Error> {
-        u64::deserialize(val).map(|v| v.into())
-    }
-}
-
 impl Deserialize for f32 {
     #[inline]
     fn deserialize(val: &Intermediate) -> Result<Self, Error> {
@@ -93,6 +148,7 @@ impl Deserialize for f64 {
     fn deserialize(val: &Intermediate) -> Result<Self, Error> {
         val.as_number()
             .map(|n| n.into())
+            .or_else(|| val.as_str().and_then(float::from_token))
             .ok_or_else(|| Error::invalid_value_static("number"))
     }
 }
@@ -141,18 +197,51 @@ where
     {
         if let Some(val) = val.as_array() {
             let mut res = Vec::with_capacity(val.len());
+            let mut errors = ErrorList::new();
 
-            for elem in val {
-                res.push(T::deserialize(elem)?);
+            for (index, elem) in val.iter().enumerate() {
+                if let Some(elem) = deserialize_element(elem, index, &mut errors) {
+                    res.push(elem);
+                }
             }
 
-            Ok(res)
+            if errors.is_empty() {
+                Ok(res)
+            } else {
+                Err(Error::UnnamedFieldErrors(errors))
+            }
+        } else if let Some(val) = val.as_bytes() {
+            let mut res = Vec::with_capacity(val.len());
+            let mut errors = ErrorList::new();
+
+            for (index, b) in val.iter().enumerate() {
+                if let Some(elem) = deserialize_element(&Intermediate::from(*b), index, &mut errors)
+                {
+                    res.push(elem);
+                }
+            }
+
+            if errors.is_empty() {
+                Ok(res)
+            } else {
+                Err(Error::UnnamedFieldErrors(errors))
+            }
         } else {
             Err(Error::invalid_value_static("array"))
         }
     }
 }
 
+impl<T> Deserialize for Box<[T]>
+where
+    T: Deserialize,
+{
+    #[inline]
+    fn deserialize(val: &Intermediate) -> Result<Self, Error> {
+        Vec::deserialize(val).map(Vec::into_boxed_slice)
+    }
+}
+
 impl<T> Deserialize for [T; 0] {
     #[inline]
     fn deserialize(_: &Intermediate) -> Result<Self, Error>
@@ -164,62 +253,27 @@ impl<T> Deserialize for [T; 0] {
 }
 
 macro_rules! deserialize_array {
-    ( $len:expr => ($($n:tt)+) ) => {
-        impl<T> Deserialize for [T; $len]
-        where
-            T: Deserialize,
-        {
-            fn deserialize(val: &Intermediate) -> Result<Self, Error> {
-                if let Some(val) = val.as_array() {
-                    if val.len() < $len {
-                        return Err(Error::invalid_value_static(concat!("an array of length ", $len)));
-                    }
-
-                    Ok([
-                        $(
-                            T::deserialize(&val[$n])?
-                        ),+
-                    ])
-                } else {
-                    Err(Error::invalid_value_static(concat!("an array of length ", $len)))
+    ( $($len:expr),+ ) => {
+        $(
+            impl<T> Deserialize for [T; $len]
+            where
+                T: Deserialize,
+            {
+                #[inline]
+                fn deserialize(val: &Intermediate) -> Result<Self, Error> {
+                    deserialize_elements(val, $len)?
+                        .try_into()
+                        .map_err(|_| Error::invalid_value_static(concat!("an array of length ", $len)))
                 }
             }
-        }
+        )+
     };
 }
 
-deserialize_array!(1 => (0));
-deserialize_array!(2 => (0 1));
-deserialize_array!(3 => (0 1 2));
-deserialize_array!(4 => (0 1 2 3));
-deserialize_array!(5 => (0 1 2 3 4));
-deserialize_array!(6 => (0 1 2 3 4 5));
-deserialize_array!(7 => (0 1 2 3 4 5 6));
-deserialize_array!(8 => (0 1 2 3 4 5 6 7));
-deserialize_array!(9 => (0 1 2 3 4 5 6 7 8));
-deserialize_array!(10 => (0 1 2 3 4 5 6 7 8 9));
-deserialize_array!(11 => (0 1 2 3 4 5 6 7 8 9 10));
-deserialize_array!(12 => (0 1 2 3 4 5 6 7 8 9 10 11));
-deserialize_array!(13 => (0 1 2 3 4 5 6 7 8 9 10 11 12));
-deserialize_array!(14 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13));
-deserialize_array!(15 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14));
-deserialize_array!(16 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15));
-deserialize_array!(17 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16));
-deserialize_array!(18 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17));
-deserialize_array!(19 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18));
-deserialize_array!(20 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19));
-deserialize_array!(21 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20));
-deserialize_array!(22 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21));
-deserialize_array!(23 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22));
-deserialize_array!(24 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23));
-deserialize_array!(25 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24));
-deserialize_array!(26 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25));
-deserialize_array!(27 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26));
-deserialize_array!(28 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27));
-deserialize_array!(29 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28));
-deserialize_array!(30 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29));
-deserialize_array!(31 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30));
-deserialize_array!(32 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31));
+deserialize_array!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32
+);
 
 impl Deserialize for () {
     #[inline]
@@ -234,17 +288,25 @@ macro_rules! deserialize_tuple {
         where
             $($ty: Deserialize,)+
         {
+            #[allow(non_snake_case)]
             fn deserialize(val: &Intermediate) -> Result<Self, Error> {
                 if let Some(val) = val.as_array() {
                     if val.len() < $len {
                         return Err(Error::invalid_value_static(concat!("an array of length ", $len)));
                     }
 
-                    Ok((
+                    let mut errors = ErrorList::new();
+
+                    let res = (
                         $(
-                            $ty::deserialize(&val[$n])?,
+                            deserialize_element::<$ty>(&val[$n], $n, &mut errors),
                         )+
-                    ))
+                    );
+
+                    match res {
+                        ($(Some($ty),)+) => Ok(($($ty,)+)),
+                        _ => Err(Error::UnnamedFieldErrors(errors)),
+                    }
                 } else {
                     Err(Error::invalid_value_static(concat!("an array of length ", $len)))
                 }
@@ -285,14 +347,22 @@ where
 
         let mut res = HashMap::with_capacity(val.len());
 
-        for (name, value) in val {
-            let k = K::from(name.clone());
-            let v = V::deserialize(value)?;
+        let mut errors = ErrorList::new();
 
-            res.insert(k, v);
+        for (name, value) in val {
+            match V::deserialize(value) {
+                Ok(v) => {
+                    res.insert(K::from(map::to_owned_key(name)), v);
+                }
+                Err(err) => errors.push(NamedFieldError::from_key(map::to_owned_key(name), err)),
+            }
         }
 
-        Ok(res)
+        if errors.is_empty() {
+            Ok(res)
+        } else {
+            Err(Error::NamedFieldErrors(errors))
+        }
     }
 }
 
@@ -312,14 +382,22 @@ where
 
         let mut res = indexmap::IndexMap::with_capacity(val.len());
 
-        for (name, value) in val {
-            let k = K::from(name.clone());
-            let v = V::deserialize(value)?;
+        let mut errors = ErrorList::new();
 
-            res.insert(k, v);
+        for (name, value) in val {
+            match V::deserialize(value) {
+                Ok(v) => {
+                    res.insert(K::from(map::to_owned_key(name)), v);
+                }
+                Err(err) => errors.push(NamedFieldError::from_key(map::to_owned_key(name), err)),
+            }
         }
 
-        Ok(res)
+        if errors.is_empty() {
+            Ok(res)
+        } else {
+            Err(Error::NamedFieldErrors(errors))
+        }
     }
 }
 
@@ -345,3 +423,50 @@ deserialize_wrapper!(Arc);
 deserialize_wrapper!(Cell);
 deserialize_wrapper!(RefCell);
 deserialize_wrapper!(Mutex);
+
+/// Deserialize the first `len` elements of a given array or byte string.
+fn deserialize_elements<T>(val: &Intermediate, len: usize) -> Result<Vec<T>, Error>
+where
+    T: Deserialize,
+{
+    let mut res = Vec::with_capacity(len);
+
+    let mut errors = ErrorList::new();
+
+    if let Some(val) = val.as_array().filter(|val| val.len() >= len) {
+        for (index, elem) in val[..len].iter().enumerate() {
+            if let Some(elem) = deserialize_element(elem, index, &mut errors) {
+                res.push(elem);
+            }
+        }
+    } else if let Some(val) = val.as_bytes().filter(|val| val.len() >= len) {
+        for (index, b) in val[..len].iter().enumerate() {
+            if let Some(elem) = deserialize_element(&Intermediate::from(*b), index, &mut errors) {
+                res.push(elem);
+            }
+        }
+    } else {
+        return Err(Error::invalid_value(format!("an array of length {}", len)));
+    }
+
+    if errors.is_empty() {
+        Ok(res)
+    } else {
+        Err(Error::UnnamedFieldErrors(errors))
+    }
+}
+
+/// Helper function.
+#[inline]
+fn deserialize_element<T>(
+    val: &Intermediate,
+    index: usize,
+    errors: &mut ErrorList<UnnamedFieldError>,
+) -> Option<T>
+where
+    T: Deserialize,
+{
+    T::deserialize(val)
+        .map_err(|err| errors.push(UnnamedFieldError::new(index, err)))
+        .ok()
+}
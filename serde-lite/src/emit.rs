@@ -0,0 +1,323 @@
+//! Streaming serialization.
+//!
+//! `Serialize::serialize_to` passes a value to an `Emitter` as a sequence of
+//! events instead of building the whole intermediate representation at
+//! once. The default implementation of the method falls back to building the
+//! intermediate representation, so only types implementing the method
+//! directly (e.g. collections and types using the derive macro) can avoid
+//! the memory overhead.
+//!
+//! ```rust
+//! use serde_lite::{
+//!     emit::{Emitter, Scalar},
+//!     Error, Serialize,
+//! };
+//!
+//! /// Emitter counting all scalar values.
+//! #[derive(Default)]
+//! struct Counter {
+//!     scalars: usize,
+//! }
+//!
+//! impl Emitter for Counter {
+//!     fn scalar(&mut self, _: Scalar<'_>) -> Result<(), Error> {
+//!         self.scalars += 1;
+//!         Ok(())
+//!     }
+//!
+//!     fn begin_array(&mut self, _: Option<usize>) -> Result<(), Error> {
+//!         Ok(())
+//!     }
+//!
+//!     fn end_array(&mut self) -> Result<(), Error> {
+//!         Ok(())
+//!     }
+//!
+//!     fn begin_map(&mut self, _: Option<usize>) -> Result<(), Error> {
+//!         Ok(())
+//!     }
+//!
+//!     fn key(&mut self, _: &str) -> Result<(), Error> {
+//!         Ok(())
+//!     }
+//!
+//!     fn end_map(&mut self) -> Result<(), Error> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut counter = Counter::default();
+//!
+//! vec![vec![1, 2], vec![3]].serialize_to(&mut counter).unwrap();
+//!
+//! assert_eq!(counter.scalars, 3);
+//! ```
+
+use std::slice;
+
+use crate::{map, Error, Intermediate, Number};
+
+/// Scalar value.
+#[derive(Debug, Copy, Clone)]
+pub enum Scalar<'a> {
+    None,
+    Bool(bool),
+    Number(Number),
+    String(&'a str),
+    Bytes(&'a [u8]),
+}
+
+/// Receiver of serialization events.
+///
+/// A value is either a scalar, an array or a map. Arrays are emitted as
+/// `begin_array`, the array elements and `end_array`. Maps are emitted as
+/// `begin_map`, pairs of `key` and the corresponding value and `end_map`.
+/// The length hints are `None` if the number of elements or entries is not
+/// known in advance.
+pub trait Emitter {
+    /// Emit a scalar value.
+    fn scalar(&mut self, value: Scalar<'_>) -> Result<(), Error>;
+
+    /// Begin an array.
+    fn begin_array(&mut self, len: Option<usize>) -> Result<(), Error>;
+
+    /// End the current array.
+    fn end_array(&mut self) -> Result<(), Error>;
+
+    /// Begin a map.
+    fn begin_map(&mut self, len: Option<usize>) -> Result<(), Error>;
+
+    /// Emit key of the next map entry.
+    fn key(&mut self, key: &str) -> Result<(), Error>;
+
+    /// End the current map.
+    fn end_map(&mut self) -> Result<(), Error>;
+}
+
+/// Pass a given intermediate value to a given emitter.
+#[inline(never)]
+pub fn emit(val: &Intermediate, emitter: &mut dyn Emitter) -> Result<(), Error> {
+    /// Partially emitted array or map.
+    enum Frame<'a, 'b> {
+        Array(slice::Iter<'a, Intermediate<'b>>),
+        Map(map::Iter<'a, 'b>),
+    }
+
+    let mut stack = Vec::new();
+
+    let mut current = Some(val);
+
+    loop {
+        if let Some(val) = current.take() {
+            match val {
+                Intermediate::None => emitter.scalar(Scalar::None)?,
+                Intermediate::Bool(v) => emitter.scalar(Scalar::Bool(*v))?,
+                Intermediate::Number(v) => emitter.scalar(Scalar::Number(*v))?,
+                Intermediate::String(v) => emitter.scalar(Scalar::String(v))?,
+                Intermediate::Bytes(v) => emitter.scalar(Scalar::Bytes(v))?,
+                Intermediate::Array(v) => {
+                    emitter.begin_array(Some(v.len()))?;
+                    stack.push(Frame::Array(v.iter()));
+                }
+                Intermediate::Map(v) => {
+                    emitter.begin_map(Some(v.len()))?;
+                    stack.push(Frame::Map(v.iter()));
+                }
+            }
+        }
+
+        match stack.last_mut() {
+            None => return Ok(()),
+            Some(Frame::Array(elements)) => {
+                if let Some(elem) = elements.next() {
+                    current = Some(elem);
+                } else {
+                    stack.pop();
+                    emitter.end_array()?;
+                }
+            }
+            Some(Frame::Map(entries)) => {
+                if let Some((k, v)) = entries.next() {
+                    emitter.key(k)?;
+                    current = Some(v);
+                } else {
+                    stack.pop();
+                    emitter.end_map()?;
+                }
+            }
+        }
+    }
+}
+
+/// Emitter passing only entries of a map to another emitter.
+///
+/// It is used for internally tagged enums. A `None` value is treated as an
+/// empty map. All other values result in an error.
+pub struct FlatEmitter<'a> {
+    inner: &'a mut dyn Emitter,
+    depth: usize,
+    error: &'static str,
+}
+
+impl<'a> FlatEmitter<'a> {
+    /// Create a new flat emitter with a given error message used for values
+    /// that are not maps.
+    #[inline]
+    pub fn new(inner: &'a mut dyn Emitter, error: &'static str) -> Self {
+        Self {
+            inner,
+            depth: 0,
+            error,
+        }
+    }
+}
+
+impl Emitter for FlatEmitter<'_> {
+    #[inline]
+    fn scalar(&mut self, value: Scalar<'_>) -> Result<(), Error> {
+        match (self.depth, value) {
+            (0, Scalar::None) => Ok(()),
+            (0, _) => Err(Error::custom_static(self.error)),
+            _ => self.inner.scalar(value),
+        }
+    }
+
+    #[inline]
+    fn begin_array(&mut self, len: Option<usize>) -> Result<(), Error> {
+        if self.depth == 0 {
+            return Err(Error::custom_static(self.error));
+        }
+
+        self.depth += 1;
+        self.inner.begin_array(len)
+    }
+
+    #[inline]
+    fn end_array(&mut self) -> Result<(), Error> {
+        self.depth -= 1;
+        self.inner.end_array()
+    }
+
+    #[inline]
+    fn begin_map(&mut self, len: Option<usize>) -> Result<(), Error> {
+        self.depth += 1;
+
+        if self.depth > 1 {
+            self.inner.begin_map(len)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn key(&mut self, key: &str) -> Result<(), Error> {
+        self.inner.key(key)
+    }
+
+    #[inline]
+    fn end_map(&mut self) -> Result<(), Error> {
+        self.depth -= 1;
+
+        if self.depth > 0 {
+            self.inner.end_map()
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Emitter wrapping a value into a single-entry map with a given key.
+///
+/// It is used for externally tagged enums. A `None` value is replaced with
+/// the key itself. `TaggedEmitter::finish` must be called after emitting the
+/// value.
+pub struct TaggedEmitter<'a> {
+    inner: &'a mut dyn Emitter,
+    tag: &'a str,
+    state: TagState,
+}
+
+/// State of a tagged emitter.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum TagState {
+    Pending,
+    Open,
+    Done,
+}
+
+impl<'a> TaggedEmitter<'a> {
+    /// Create a new tagged emitter.
+    #[inline]
+    pub fn new(inner: &'a mut dyn Emitter, tag: &'a str) -> Self {
+        Self {
+            inner,
+            tag,
+            state: TagState::Pending,
+        }
+    }
+
+    /// Finish the tagged value.
+    #[inline]
+    pub fn finish(self) -> Result<(), Error> {
+        match self.state {
+            TagState::Pending => self.inner.scalar(Scalar::String(self.tag)),
+            TagState::Open => self.inner.end_map(),
+            TagState::Done => Ok(()),
+        }
+    }
+
+    /// Open the wrapping map if it has not been opened yet.
+    #[inline]
+    fn open(&mut self) -> Result<(), Error> {
+        if self.state == TagState::Pending {
+            self.state = TagState::Open;
+            self.inner.begin_map(Some(1))?;
+            self.inner.key(self.tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Emitter for TaggedEmitter<'_> {
+    #[inline]
+    fn scalar(&mut self, value: Scalar<'_>) -> Result<(), Error> {
+        if self.state == TagState::Pending {
+            if let Scalar::None = value {
+                self.state = TagState::Done;
+
+                return self.inner.scalar(Scalar::String(self.tag));
+            }
+        }
+
+        self.open()?;
+        self.inner.scalar(value)
+    }
+
+    #[inline]
+    fn begin_array(&mut self, len: Option<usize>) -> Result<(), Error> {
+        self.open()?;
+        self.inner.begin_array(len)
+    }
+
+    #[inline]
+    fn end_array(&mut self) -> Result<(), Error> {
+        self.inner.end_array()
+    }
+
+    #[inline]
+    fn begin_map(&mut self, len: Option<usize>) -> Result<(), Error> {
+        self.open()?;
+        self.inner.begin_map(len)
+    }
+
+    #[inline]
+    fn key(&mut self, key: &str) -> Result<(), Error> {
+        self.inner.key(key)
+    }
+
+    #[inline]
+    fn end_map(&mut self) -> Result<(), Error> {
+        self.inner.end_map()
+    }
+}
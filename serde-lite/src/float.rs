@@ -0,0 +1,155 @@
+//! Handling of non-finite floating point numbers.
+//!
+//! Many formats (e.g. JSON) cannot represent NaN or infinities. A
+//! `NonFinitePolicy` can be applied to a serialized value in order to replace
+//! these numbers or to reject them. It can be also applied to individual
+//! fields using the `non_finite` attribute:
+//!
+//! ```rust
+//! use serde_lite_derive::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Measurement {
+//!     #[serde(non_finite = "null")]
+//!     value: f64,
+//!     #[serde(non_finite = "string")]
+//!     ratio: f64,
+//! }
+//! ```
+//!
+//! The string tokens (`"NaN"`, `"Infinity"` and `"-Infinity"`) are accepted
+//! when deserializing `f32` and `f64` values.
+
+use std::borrow::Cow;
+
+use crate::{Error, Intermediate, Number, Serialize};
+
+/// Policy for non-finite floating point numbers.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Keep non-finite numbers as they are.
+    #[default]
+    Allow,
+    /// Reject non-finite numbers.
+    Error,
+    /// Replace non-finite numbers with `Intermediate::None`.
+    Null,
+    /// Replace non-finite numbers with `"NaN"`, `"Infinity"` or `"-Infinity"`.
+    String,
+}
+
+impl NonFinitePolicy {
+    /// Apply the policy to a given value and all its nested values.
+    #[inline(never)]
+    pub fn apply(self, val: &mut Intermediate) -> Result<(), Error> {
+        if self == Self::Allow {
+            return Ok(());
+        }
+
+        let mut stack = vec![val];
+
+        while let Some(val) = stack.pop() {
+            match val {
+                Intermediate::Number(Number::Float(v)) if !v.is_finite() => {
+                    *val = match self {
+                        Self::Allow => panic!("the allow policy cannot be applied"),
+                        Self::Error => {
+                            return Err(Error::invalid_value_static("finite number"));
+                        }
+                        Self::Null => Intermediate::None,
+                        Self::String => Intermediate::String(Cow::Borrowed(to_token(*v))),
+                    };
+                }
+                Intermediate::Array(arr) => stack.extend(arr.iter_mut()),
+                Intermediate::Map(map) => stack.extend(map.values_mut()),
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serialize a given value and apply the policy.
+    #[inline]
+    pub fn serialize<T>(self, val: &T) -> Result<Intermediate<'_>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_with(val, T::serialize)
+    }
+
+    /// Serialize a given value using a given serializer and apply the
+    /// policy.
+    #[inline]
+    pub fn serialize_with<T, F>(self, val: &T, serializer: F) -> Result<Intermediate<'_>, Error>
+    where
+        T: ?Sized,
+        F: FnOnce(&T) -> Result<Intermediate<'_>, Error>,
+    {
+        let mut res = serializer(val)?;
+
+        self.apply(&mut res)?;
+
+        Ok(res)
+    }
+}
+
+/// Get the string token for a given non-finite number.
+#[inline]
+pub(crate) fn to_token(v: f64) -> &'static str {
+    if v.is_nan() {
+        "NaN"
+    } else if v.is_sign_negative() {
+        "-Infinity"
+    } else {
+        "Infinity"
+    }
+}
+
+/// Parse a given non-finite number token.
+#[inline]
+pub(crate) fn from_token(token: &str) -> Option<f64> {
+    match token {
+        "NaN" => Some(f64::NAN),
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+/// Strict `f32` handling.
+///
+/// Deserialization fails with `Error::OutOfBounds` if a finite number does
+/// not fit into `f32` (i.e. it would be converted to infinity). The module can
+/// be used with the `with` attribute.
+pub mod strict_f32 {
+    use crate::{Deserialize, Error, Intermediate, Serialize};
+
+    /// Serialize a given `f32` value.
+    #[inline]
+    pub fn serialize(val: &f32) -> Result<Intermediate<'_>, Error> {
+        val.serialize()
+    }
+
+    /// Deserialize an `f32` value.
+    #[inline]
+    pub fn deserialize(val: &Intermediate) -> Result<f32, Error> {
+        let v = f64::deserialize(val)?;
+
+        let res = v as f32;
+
+        if v.is_finite() && !res.is_finite() {
+            Err(Error::OutOfBounds)
+        } else {
+            Ok(res)
+        }
+    }
+
+    /// Update a given `f32` value.
+    #[inline]
+    pub fn update(val: &mut f32, input: &Intermediate) -> Result<(), Error> {
+        *val = deserialize(input)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Hexadecimal encoding of binary data.
+//!
+//! The submodules can be used with the `with` attribute (or with the
+//! `serialize_with`, `deserialize_with` and `update_with` attributes) for
+//! fields of type `Vec<u8>`, `[u8; N]` or `Box<[u8]>`. The data is serialized
+//! as a hex string. Deserialization accepts both lowercase and uppercase hex
+//! strings or raw bytes.
+//!
+//! ```rust
+//! use serde_lite_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Object {
+//!     #[serde(with = "serde_lite::hex::lower")]
+//!     sha256: [u8; 32],
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{bytes::FromBytes, Error, Intermediate};
+
+const LOWER: &[u8; 16] = b"0123456789abcdef";
+const UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+macro_rules! hex_module {
+    ( $(#[$doc:meta])* $name:ident, $alphabet:expr ) => {
+        $(#[$doc])*
+        pub mod $name {
+            use crate::{bytes::FromBytes, Error, Intermediate};
+
+            /// Serialize given bytes as a hex string.
+            #[inline]
+            pub fn serialize<T>(val: &T) -> Result<Intermediate<'_>, Error>
+            where
+                T: AsRef<[u8]> + ?Sized,
+            {
+                Ok(super::encode(val.as_ref(), $alphabet))
+            }
+
+            /// Deserialize bytes from a hex string.
+            #[inline]
+            pub fn deserialize<T>(val: &Intermediate) -> Result<T, Error>
+            where
+                T: FromBytes,
+            {
+                super::decode(val)
+            }
+
+            /// Replace given bytes with bytes decoded from a hex string.
+            #[inline]
+            pub fn update<T>(val: &mut T, input: &Intermediate) -> Result<(), Error>
+            where
+                T: FromBytes,
+            {
+                *val = deserialize(input)?;
+
+                Ok(())
+            }
+        }
+    };
+}
+
+hex_module!(
+    /// Lowercase hex encoding.
+    lower,
+    super::LOWER
+);
+
+hex_module!(
+    /// Uppercase hex encoding.
+    upper,
+    super::UPPER
+);
+
+/// Encode given bytes.
+#[inline(never)]
+fn encode(input: &[u8], alphabet: &[u8; 16]) -> Intermediate<'static> {
+    let mut res = String::with_capacity(input.len() * 2);
+
+    for b in input {
+        res.push(alphabet[(b >> 4) as usize] as char);
+        res.push(alphabet[(b & 0x0f) as usize] as char);
+    }
+
+    Intermediate::String(Cow::Owned(res))
+}
+
+/// Decode bytes from a given intermediate value.
+#[inline(never)]
+fn decode_to_vec(val: &Intermediate) -> Result<Vec<u8>, Error> {
+    if let Some(bytes) = val.as_bytes() {
+        return Ok(bytes.to_vec());
+    }
+
+    let input = val
+        .as_str()
+        .ok_or_else(|| Error::invalid_value_static("hex string"))?
+        .as_bytes();
+
+    if input.len() % 2 != 0 {
+        return Err(Error::invalid_value_static("hex string"));
+    }
+
+    let mut res = Vec::with_capacity(input.len() / 2);
+
+    for pair in input.chunks(2) {
+        let hi = decode_digit(pair[0])?;
+        let lo = decode_digit(pair[1])?;
+
+        res.push((hi << 4) | lo);
+    }
+
+    Ok(res)
+}
+
+/// Decode a single hex digit.
+#[inline]
+fn decode_digit(c: u8) -> Result<u8, Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::invalid_value_static("hex string")),
+    }
+}
+
+/// Decode bytes from a given intermediate value.
+#[inline]
+fn decode<T>(val: &Intermediate) -> Result<T, Error>
+where
+    T: FromBytes,
+{
+    T::from_bytes(decode_to_vec(val)?)
+}
@@ -3,22 +3,59 @@ use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt::{self, Formatter},
+    marker::PhantomData,
+    mem,
 };
 
 use serde::{
-    de::{MapAccess, SeqAccess, Visitor},
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
     ser::{SerializeMap, SerializeSeq},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use crate::{Error, Map};
+use crate::{
+    emit::{self, Emitter},
+    map::{self, Map},
+    Error,
+};
 
 /// Number.
+///
+/// Integers that do not fit into 64 bits are represented using the 128-bit
+/// variants. Note that serde_json (without the `arbitrary_precision` feature)
+/// parses such integers as `f64`, so they do not round-trip through
+/// `serde_json::from_str`. The `json` module of this crate parses them
+/// without loss of precision.
 #[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
 pub enum Number {
     Float(f64),
     SignedInt(i64),
     UnsignedInt(u64),
+    SignedInt128(i128),
+    UnsignedInt128(u128),
+}
+
+impl From<i128> for Number {
+    #[inline]
+    fn from(v: i128) -> Self {
+        if let Ok(v) = i64::try_from(v) {
+            Self::SignedInt(v)
+        } else {
+            Self::SignedInt128(v)
+        }
+    }
+}
+
+impl From<u128> for Number {
+    #[inline]
+    fn from(v: u128) -> Self {
+        if let Ok(v) = u64::try_from(v) {
+            Self::UnsignedInt(v)
+        } else {
+            Self::UnsignedInt128(v)
+        }
+    }
 }
 
 impl From<Number> for f64 {
@@ -28,6 +65,8 @@ impl From<Number> for f64 {
             Number::Float(v) => v,
             Number::SignedInt(v) => v as _,
             Number::UnsignedInt(v) => v as _,
+            Number::SignedInt128(v) => v as _,
+            Number::UnsignedInt128(v) => v as _,
         }
     }
 }
@@ -43,6 +82,8 @@ macro_rules! try_int_from_number {
                     Number::Float(_) => return Err(Error::UnsupportedConversion),
                     Number::SignedInt(v) => v.try_into(),
                     Number::UnsignedInt(v) => v.try_into(),
+                    Number::SignedInt128(v) => v.try_into(),
+                    Number::UnsignedInt128(v) => v.try_into(),
                 };
 
                 res.map_err(|_| Error::OutOfBounds)
@@ -65,6 +106,8 @@ impl TryFrom<Number> for i64 {
             Number::Float(_) => Err(Error::UnsupportedConversion),
             Number::SignedInt(v) => Ok(v),
             Number::UnsignedInt(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+            Number::SignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+            Number::UnsignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
         }
     }
 }
@@ -83,6 +126,38 @@ impl TryFrom<Number> for u64 {
             Number::Float(_) => Err(Error::UnsupportedConversion),
             Number::SignedInt(v) => v.try_into().map_err(|_| Error::OutOfBounds),
             Number::UnsignedInt(v) => Ok(v),
+            Number::SignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+            Number::UnsignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+        }
+    }
+}
+
+impl TryFrom<Number> for i128 {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(n: Number) -> Result<Self, Self::Error> {
+        match n {
+            Number::Float(_) => Err(Error::UnsupportedConversion),
+            Number::SignedInt(v) => Ok(v.into()),
+            Number::UnsignedInt(v) => Ok(v.into()),
+            Number::SignedInt128(v) => Ok(v),
+            Number::UnsignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+        }
+    }
+}
+
+impl TryFrom<Number> for u128 {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(n: Number) -> Result<Self, Self::Error> {
+        match n {
+            Number::Float(_) => Err(Error::UnsupportedConversion),
+            Number::SignedInt(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+            Number::UnsignedInt(v) => Ok(v.into()),
+            Number::SignedInt128(v) => v.try_into().map_err(|_| Error::OutOfBounds),
+            Number::UnsignedInt128(v) => Ok(v),
         }
     }
 }
@@ -97,6 +172,8 @@ impl Serialize for Number {
             Self::Float(v) => serializer.serialize_f64(v),
             Self::SignedInt(v) => serializer.serialize_i64(v),
             Self::UnsignedInt(v) => serializer.serialize_u64(v),
+            Self::SignedInt128(v) => serializer.serialize_i128(v),
+            Self::UnsignedInt128(v) => serializer.serialize_u128(v),
         }
     }
 }
@@ -127,6 +204,16 @@ impl<'de> Deserialize<'de> for Number {
                 Ok(Number::UnsignedInt(value))
             }
 
+            #[inline]
+            fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+                Ok(Number::from(value))
+            }
+
+            #[inline]
+            fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+                Ok(Number::from(value))
+            }
+
             #[inline]
             fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
                 Ok(Number::Float(value))
@@ -151,7 +238,8 @@ impl<'de> Deserialize<'de> for Number {
 ///     "field5": [1, 2, 3, 4, 5],
 ///     "field6": {
 ///         "inner_field": "Hello!",
-///     }
+///     },
+///     "field7": 340282366920938463463374607431768211455u128,
 /// });
 /// ```
 #[macro_export]
@@ -189,17 +277,24 @@ macro_rules! intermediate {
 ///
 /// The format is similar to JSON. It can be serialized/deserialized using
 /// serde.
-#[derive(Debug, Clone)]
-pub enum Intermediate {
+///
+/// Dropping and cloning is implemented without recursion, so even extremely
+/// deeply nested values cannot overflow the stack.
+///
+/// Strings and byte strings can be borrowed from the input. Use `into_owned`
+/// to get a value that does not borrow anything.
+#[derive(Debug)]
+pub enum Intermediate<'a> {
     None,
     Bool(bool),
     Number(Number),
-    String(Cow<'static, str>),
-    Array(Vec<Intermediate>),
-    Map(Map),
+    String(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+    Array(Vec<Intermediate<'a>>),
+    Map(Map<'a>),
 }
 
-impl Intermediate {
+impl<'a> Intermediate<'a> {
     /// Check if the value is None.
     #[inline]
     pub fn is_none(&self) -> bool {
@@ -255,9 +350,19 @@ impl Intermediate {
         }
     }
 
+    /// Get the value as a byte string (if possible).
+    #[inline]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let Self::Bytes(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// Get the value as an array (if possible).
     #[inline]
-    pub fn as_array(&self) -> Option<&[Intermediate]> {
+    pub fn as_array(&self) -> Option<&[Intermediate<'a>]> {
         if let Self::Array(v) = self {
             Some(v)
         } else {
@@ -267,58 +372,384 @@ impl Intermediate {
 
     /// Get the value as a map (if possible).
     #[inline]
-    pub fn as_map(&self) -> Option<&Map> {
+    pub fn as_map(&self) -> Option<&Map<'a>> {
         if let Self::Map(v) = self {
             Some(v)
         } else {
             None
         }
     }
+
+    /// Take the string (if possible).
+    ///
+    /// The original value is returned back if it is not a string.
+    #[inline]
+    pub fn into_string(mut self) -> Result<Cow<'a, str>, Self> {
+        if let Self::String(v) = &mut self {
+            Ok(mem::take(v))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Take the array (if possible).
+    ///
+    /// The original value is returned back if it is not an array.
+    #[inline]
+    pub fn into_array(mut self) -> Result<Vec<Intermediate<'a>>, Self> {
+        if let Self::Array(v) = &mut self {
+            Ok(mem::take(v))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Take the map (if possible).
+    ///
+    /// The original value is returned back if it is not a map.
+    #[inline]
+    pub fn into_map(mut self) -> Result<Map<'a>, Self> {
+        if let Self::Map(v) = &mut self {
+            Ok(mem::take(v))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Check if the value is a non-empty array or map.
+    #[inline]
+    pub(crate) fn has_children(&self) -> bool {
+        match self {
+            Self::Array(v) => !v.is_empty(),
+            Self::Map(v) => !v.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Move all nested arrays and maps to a given stack.
+    #[inline]
+    fn take_children(&mut self, stack: &mut Vec<Intermediate<'a>>) {
+        match self {
+            Self::Array(v) => {
+                stack.extend(mem::take(v).into_iter().filter(Self::has_children));
+            }
+            Self::Map(v) => v.take_children(stack),
+            _ => (),
+        }
+    }
+
+    /// Convert the value into a value that does not borrow anything.
+    ///
+    /// Owned strings, byte strings, arrays and maps are moved into the
+    /// resulting value. Only the borrowed data is copied.
+    #[inline(never)]
+    pub fn into_owned(self) -> Intermediate<'static> {
+        /// Partially converted array or map.
+        struct Frame<'a> {
+            value: Intermediate<'static>,
+            elements: std::vec::IntoIter<Intermediate<'a>>,
+            entries: Option<<Map<'a> as IntoIterator>::IntoIter>,
+            key: Option<Cow<'static, str>>,
+        }
+
+        impl<'a> Frame<'a> {
+            /// Create a new frame for a given original value.
+            fn new(mut original: Intermediate<'a>) -> Self {
+                let value = original.take_owned_shallow();
+
+                let (elements, entries) = match &mut original {
+                    Intermediate::Array(v) => (mem::take(v).into_iter(), None),
+                    Intermediate::Map(v) => {
+                        (Vec::new().into_iter(), Some(mem::take(v).into_iter()))
+                    }
+                    _ => (Vec::new().into_iter(), None),
+                };
+
+                Self {
+                    value,
+                    elements,
+                    entries,
+                    key: None,
+                }
+            }
+
+            /// Add a given converted child.
+            fn push(&mut self, child: Intermediate<'static>) {
+                match &mut self.value {
+                    Intermediate::Array(v) => v.push(child),
+                    Intermediate::Map(v) => {
+                        if let Some(key) = self.key.take() {
+                            v.insert(key, child);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            /// Get the next child to be converted.
+            fn next(&mut self) -> Option<Intermediate<'a>> {
+                if let Some(entries) = self.entries.as_mut() {
+                    let (k, child) = entries.next()?;
+
+                    self.key = Some(Cow::Owned(k.into_owned()));
+
+                    Some(child)
+                } else {
+                    self.elements.next()
+                }
+            }
+        }
+
+        let mut frame = Frame::new(self);
+        let mut stack = Vec::new();
+
+        loop {
+            if let Some(mut child) = frame.next() {
+                if child.has_children() {
+                    stack.push(mem::replace(&mut frame, Frame::new(child)));
+                } else {
+                    frame.push(child.take_owned_shallow());
+                }
+            } else if let Some(mut parent) = stack.pop() {
+                parent.push(frame.value);
+
+                frame = parent;
+            } else {
+                return frame.value;
+            }
+        }
+    }
+
+    /// Clone the value into a value that does not borrow anything.
+    #[inline(never)]
+    pub(crate) fn to_owned_value(&self) -> Intermediate<'static> {
+        self.rebuild(Self::to_owned_shallow, |key| map::to_owned_key(key))
+    }
+
+    /// Clone the value without cloning any nested values.
+    #[inline]
+    fn clone_shallow(&self) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::Bool(v) => Self::Bool(*v),
+            Self::Number(v) => Self::Number(*v),
+            Self::String(v) => Self::String(v.clone()),
+            Self::Bytes(v) => Self::Bytes(v.clone()),
+            Self::Array(v) => Self::Array(Vec::with_capacity(v.len())),
+            Self::Map(v) => Self::Map(Map::with_capacity(v.len())),
+        }
+    }
+
+    /// Clone the value without cloning any nested values into a value that
+    /// does not borrow anything.
+    #[inline]
+    fn to_owned_shallow(&self) -> Intermediate<'static> {
+        match self {
+            Self::None => Intermediate::None,
+            Self::Bool(v) => Intermediate::Bool(*v),
+            Self::Number(v) => Intermediate::Number(*v),
+            Self::String(v) => Intermediate::String(Cow::Owned(String::from(&**v))),
+            Self::Bytes(v) => Intermediate::Bytes(Cow::Owned(v.to_vec())),
+            Self::Array(v) => Intermediate::Array(Vec::with_capacity(v.len())),
+            Self::Map(v) => Intermediate::Map(Map::with_capacity(v.len())),
+        }
+    }
+
+    /// Move the value without its nested values into a value that does not
+    /// borrow anything.
+    ///
+    /// Owned strings and byte strings are moved, borrowed ones are copied.
+    /// Nested values are left in place.
+    #[inline]
+    fn take_owned_shallow(&mut self) -> Intermediate<'static> {
+        match self {
+            Self::None => Intermediate::None,
+            Self::Bool(v) => Intermediate::Bool(*v),
+            Self::Number(v) => Intermediate::Number(*v),
+            Self::String(v) => Intermediate::String(Cow::Owned(mem::take(v).into_owned())),
+            Self::Bytes(v) => Intermediate::Bytes(Cow::Owned(mem::take(v).into_owned())),
+            Self::Array(v) => Intermediate::Array(Vec::with_capacity(v.len())),
+            Self::Map(v) => Intermediate::Map(Map::with_capacity(v.len())),
+        }
+    }
+
+    /// Rebuild the value using given functions for cloning individual
+    /// values (without their nested values) and map keys.
+    ///
+    /// The value is traversed without recursion.
+    fn rebuild<'b>(
+        &self,
+        value: fn(&Self) -> Intermediate<'b>,
+        key: fn(&Cow<'a, str>) -> Cow<'b, str>,
+    ) -> Intermediate<'b> {
+        /// Partially rebuilt array or map.
+        struct Frame<'a, 'b, 'c> {
+            value: Intermediate<'b>,
+            elements: std::slice::Iter<'c, Intermediate<'a>>,
+            entries: Option<crate::map::Iter<'c, 'a>>,
+            key: Option<Cow<'b, str>>,
+        }
+
+        impl<'a, 'b, 'c> Frame<'a, 'b, 'c> {
+            /// Create a new frame for a given original value.
+            fn new(original: &'c Intermediate<'a>, value: Intermediate<'b>) -> Self {
+                let (elements, entries) = match original {
+                    Intermediate::Array(v) => (v.iter(), None),
+                    Intermediate::Map(v) => ([].iter(), Some(v.iter())),
+                    _ => ([].iter(), None),
+                };
+
+                Self {
+                    value,
+                    elements,
+                    entries,
+                    key: None,
+                }
+            }
+
+            /// Add a given rebuilt child.
+            fn push(&mut self, child: Intermediate<'b>) {
+                match &mut self.value {
+                    Intermediate::Array(v) => v.push(child),
+                    Intermediate::Map(v) => {
+                        if let Some(key) = self.key.take() {
+                            v.insert(key, child);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            /// Get the next child to be rebuilt.
+            fn next(
+                &mut self,
+                key: fn(&Cow<'a, str>) -> Cow<'b, str>,
+            ) -> Option<&'c Intermediate<'a>> {
+                if let Some(entries) = self.entries.as_mut() {
+                    let (k, child) = entries.next()?;
+
+                    self.key = Some(key(k));
+
+                    Some(child)
+                } else {
+                    self.elements.next()
+                }
+            }
+        }
+
+        if !self.has_children() {
+            return value(self);
+        }
+
+        let mut stack = Vec::new();
+
+        let mut current = Frame::new(self, value(self));
+
+        loop {
+            if let Some(child) = current.next(key) {
+                if child.has_children() {
+                    let frame = Frame::new(child, value(child));
+
+                    stack.push(mem::replace(&mut current, frame));
+                } else {
+                    current.push(value(child));
+                }
+            } else if let Some(parent) = stack.pop() {
+                let done = mem::replace(&mut current, parent);
+
+                current.push(done.value);
+            } else {
+                return current.value;
+            }
+        }
+    }
+}
+
+impl Clone for Intermediate<'_> {
+    #[inline(never)]
+    fn clone(&self) -> Self {
+        self.rebuild(Self::clone_shallow, Cow::clone)
+    }
 }
 
-impl From<()> for Intermediate {
+impl Drop for Intermediate<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.has_children() {
+            let mut stack = Vec::new();
+
+            self.take_children(&mut stack);
+
+            drop_all(stack);
+        }
+    }
+}
+
+/// Drop all values on a given stack without recursion.
+#[inline(never)]
+pub(crate) fn drop_all(mut stack: Vec<Intermediate<'_>>) {
+    while let Some(mut val) = stack.pop() {
+        val.take_children(&mut stack);
+    }
+}
+
+impl From<()> for Intermediate<'_> {
     #[inline]
     fn from(_: ()) -> Self {
         Self::None
     }
 }
 
-impl From<bool> for Intermediate {
+impl From<bool> for Intermediate<'_> {
     #[inline]
     fn from(v: bool) -> Self {
         Self::Bool(v)
     }
 }
 
-impl From<Number> for Intermediate {
+impl From<Number> for Intermediate<'_> {
     #[inline]
     fn from(v: Number) -> Self {
         Self::Number(v)
     }
 }
 
-impl From<i64> for Intermediate {
+impl From<i64> for Intermediate<'_> {
     #[inline]
     fn from(v: i64) -> Self {
         Self::from(Number::SignedInt(v))
     }
 }
 
-impl From<u64> for Intermediate {
+impl From<u64> for Intermediate<'_> {
     #[inline]
     fn from(v: u64) -> Self {
         Self::from(Number::UnsignedInt(v))
     }
 }
 
-impl From<f32> for Intermediate {
+impl From<i128> for Intermediate<'_> {
+    #[inline]
+    fn from(v: i128) -> Self {
+        Self::from(Number::from(v))
+    }
+}
+
+impl From<u128> for Intermediate<'_> {
+    #[inline]
+    fn from(v: u128) -> Self {
+        Self::from(Number::from(v))
+    }
+}
+
+impl From<f32> for Intermediate<'_> {
     #[inline]
     fn from(v: f32) -> Self {
         Self::from(Number::Float(v as _))
     }
 }
 
-impl From<f64> for Intermediate {
+impl From<f64> for Intermediate<'_> {
     #[inline]
     fn from(v: f64) -> Self {
         Self::from(Number::Float(v))
@@ -327,7 +758,7 @@ impl From<f64> for Intermediate {
 
 macro_rules! intermediate_from_signed_int {
     ( $ty:ty ) => {
-        impl From<$ty> for Intermediate {
+        impl From<$ty> for Intermediate<'_> {
             #[inline]
             fn from(v: $ty) -> Self {
                 Self::from(Number::SignedInt(v.into()))
@@ -342,7 +773,7 @@ intermediate_from_signed_int!(i32);
 
 macro_rules! intermediate_from_unsigned_int {
     ( $ty:ty ) => {
-        impl From<$ty> for Intermediate {
+        impl From<$ty> for Intermediate<'_> {
             #[inline]
             fn from(v: $ty) -> Self {
                 Self::from(Number::UnsignedInt(v.into()))
@@ -355,23 +786,30 @@ intermediate_from_unsigned_int!(u8);
 intermediate_from_unsigned_int!(u16);
 intermediate_from_unsigned_int!(u32);
 
-impl From<String> for Intermediate {
+impl From<String> for Intermediate<'_> {
     #[inline]
     fn from(v: String) -> Self {
         Self::String(Cow::Owned(v))
     }
 }
 
-impl From<&str> for Intermediate {
+impl<'a> From<&'a str> for Intermediate<'a> {
+    #[inline]
+    fn from(v: &'a str) -> Self {
+        Self::String(Cow::Borrowed(v))
+    }
+}
+
+impl<'a> From<&'a [u8]> for Intermediate<'a> {
     #[inline]
-    fn from(v: &str) -> Self {
-        Self::String(Cow::Owned(String::from(v)))
+    fn from(v: &'a [u8]) -> Self {
+        Self::Bytes(Cow::Borrowed(v))
     }
 }
 
-impl<T> From<Vec<T>> for Intermediate
+impl<'a, T> From<Vec<T>> for Intermediate<'a>
 where
-    Intermediate: From<T>,
+    Intermediate<'a>: From<T>,
 {
     fn from(v: Vec<T>) -> Self {
         let mut res = Vec::with_capacity(v.len());
@@ -384,10 +822,10 @@ where
     }
 }
 
-impl<K, V> From<HashMap<K, V>> for Intermediate
+impl<'a, K, V> From<HashMap<K, V>> for Intermediate<'a>
 where
-    K: Into<Cow<'static, str>>,
-    V: Into<Intermediate>,
+    K: Into<Cow<'a, str>>,
+    V: Into<Intermediate<'a>>,
 {
     fn from(map: HashMap<K, V>) -> Self {
         let mut res = Map::with_capacity(map.len());
@@ -401,10 +839,10 @@ where
 }
 
 #[cfg(feature = "preserve-order")]
-impl<K, V> From<indexmap::IndexMap<K, V>> for Intermediate
+impl<'a, K, V> From<indexmap::IndexMap<K, V>> for Intermediate<'a>
 where
-    K: Into<Cow<'static, str>>,
-    V: Into<Intermediate>,
+    K: Into<Cow<'a, str>>,
+    V: Into<Intermediate<'a>>,
 {
     fn from(map: indexmap::IndexMap<K, V>) -> Self {
         let mut res = Map::with_capacity(map.len());
@@ -417,46 +855,51 @@ where
     }
 }
 
-impl crate::Serialize for Intermediate {
+impl crate::Serialize for Intermediate<'_> {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(self.clone())
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        emit::emit(self, emitter)
+    }
 }
 
-impl crate::Deserialize for Intermediate {
+impl crate::Deserialize for Intermediate<'static> {
     #[inline]
-    fn deserialize(input: &Intermediate) -> Result<Self, Error> {
-        Ok(input.clone())
+    fn deserialize(input: &Intermediate<'_>) -> Result<Self, Error> {
+        Ok(input.to_owned_value())
     }
 }
 
-impl crate::Update for Intermediate {
+impl crate::Update for Intermediate<'static> {
     #[inline]
-    fn update(&mut self, other: &Intermediate) -> Result<(), Error> {
+    fn update(&mut self, other: &Intermediate<'_>) -> Result<(), Error> {
         match self {
             Self::Array(arr) => {
-                if let Self::Array(_) = other {
+                if let Intermediate::Array(_) = other {
                     arr.update(other)?;
                 } else {
-                    *self = other.clone();
+                    *self = other.to_owned_value();
                 }
             }
             Self::Map(map) => {
-                if let Self::Map(_) = other {
+                if let Intermediate::Map(_) = other {
                     map.update(other)?;
                 } else {
-                    *self = other.clone();
+                    *self = other.to_owned_value();
                 }
             }
-            _ => *self = other.clone(),
+            _ => *self = other.to_owned_value(),
         }
 
         Ok(())
     }
 }
 
-impl Serialize for Intermediate {
+impl Serialize for Intermediate<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -466,6 +909,7 @@ impl Serialize for Intermediate {
             Self::Bool(v) => serializer.serialize_bool(*v),
             Self::Number(v) => v.serialize(serializer),
             Self::String(v) => serializer.serialize_str(v),
+            Self::Bytes(v) => serializer.serialize_bytes(v),
             Self::Array(v) => {
                 let mut seq = serializer.serialize_seq(Some(v.len()))?;
                 for e in v {
@@ -484,120 +928,281 @@ impl Serialize for Intermediate {
     }
 }
 
-impl<'de> Deserialize<'de> for Intermediate {
+impl<'de> Deserialize<'de> for Intermediate<'static> {
     #[inline]
-    fn deserialize<D>(deserializer: D) -> Result<Intermediate, D::Error>
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        struct ValueVisitor;
+        ValueSeed::<Owned>::new().deserialize(deserializer)
+    }
+}
 
-        impl<'a> Visitor<'a> for ValueVisitor {
-            type Value = Intermediate;
+impl<'de> Intermediate<'de> {
+    /// Deserialize a value from a given serde deserializer borrowing strings
+    /// and byte strings from the input if possible.
+    ///
+    /// The `Deserialize` implementation always produces owned values.
+    #[inline]
+    pub fn from_deserializer<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ValueSeed::<Borrowed>::new().deserialize(deserializer)
+    }
+}
 
-            #[inline]
-            fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-                f.write_str("a value")
-            }
+/// Conversion of strings and byte strings borrowed from the input.
+trait Borrowing<'de, 'a> {
+    /// Convert a given string.
+    fn string(value: &'de str) -> Cow<'a, str>;
 
-            #[inline]
-            fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
-                Ok(Intermediate::Bool(value))
-            }
+    /// Convert a given byte string.
+    fn bytes(value: &'de [u8]) -> Cow<'a, [u8]>;
+}
 
-            #[inline]
-            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
-                Ok(Intermediate::Number(Number::SignedInt(value)))
-            }
+/// Strategy copying everything from the input.
+struct Owned;
 
-            #[inline]
-            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
-                Ok(Intermediate::Number(Number::UnsignedInt(value)))
-            }
+impl<'de> Borrowing<'de, 'static> for Owned {
+    #[inline]
+    fn string(value: &'de str) -> Cow<'static, str> {
+        Cow::Owned(String::from(value))
+    }
 
-            #[inline]
-            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
-                Ok(Intermediate::Number(Number::Float(value)))
-            }
+    #[inline]
+    fn bytes(value: &'de [u8]) -> Cow<'static, [u8]> {
+        Cow::Owned(value.to_vec())
+    }
+}
 
-            #[inline]
-            fn visit_char<E>(self, value: char) -> Result<Self::Value, E> {
-                Ok(Intermediate::String(Cow::Owned(value.to_string())))
-            }
+/// Strategy borrowing from the input.
+struct Borrowed;
 
-            #[inline]
-            fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
-                Ok(Intermediate::String(Cow::Owned(value)))
-            }
+impl<'de> Borrowing<'de, 'de> for Borrowed {
+    #[inline]
+    fn string(value: &'de str) -> Cow<'de, str> {
+        Cow::Borrowed(value)
+    }
 
-            #[inline]
-            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
-                Ok(Intermediate::String(Cow::Owned(String::from(value))))
-            }
+    #[inline]
+    fn bytes(value: &'de [u8]) -> Cow<'de, [u8]> {
+        Cow::Borrowed(value)
+    }
+}
 
-            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
-                let mut res = Vec::with_capacity(value.len());
+/// Seed for deserializing intermediate values.
+struct ValueSeed<'a, B> {
+    phantom: PhantomData<(Cow<'a, str>, B)>,
+}
 
-                for b in value {
-                    res.push(Intermediate::Number(Number::UnsignedInt(*b as _)));
-                }
+impl<B> ValueSeed<'_, B> {
+    /// Create a new seed.
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
 
-                Ok(Intermediate::Array(res))
-            }
+impl<'de, 'a, B> DeserializeSeed<'de> for ValueSeed<'a, B>
+where
+    B: Borrowing<'de, 'a>,
+{
+    type Value = Intermediate<'a>;
 
-            #[inline]
-            fn visit_none<E>(self) -> Result<Self::Value, E> {
-                Ok(Intermediate::None)
-            }
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
 
-            #[inline]
-            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-            where
-                D: Deserializer<'a>,
-            {
-                Intermediate::deserialize(deserializer)
-            }
+impl<'de, 'a, B> Visitor<'de> for ValueSeed<'a, B>
+where
+    B: Borrowing<'de, 'a>,
+{
+    type Value = Intermediate<'a>;
 
-            #[inline]
-            fn visit_unit<E>(self) -> Result<Self::Value, E> {
-                Ok(Intermediate::None)
-            }
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a value")
+    }
 
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: SeqAccess<'a>,
-            {
-                let mut res = Vec::new();
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Intermediate::Bool(value))
+    }
 
-                if let Some(size) = seq.size_hint() {
-                    res.reserve(size);
-                }
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::SignedInt(value)))
+    }
 
-                while let Some(elem) = seq.next_element()? {
-                    res.push(elem);
-                }
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::UnsignedInt(value)))
+    }
 
-                Ok(Intermediate::Array(res))
-            }
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::from(value)))
+    }
 
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-            where
-                A: MapAccess<'a>,
-            {
-                let mut res = Map::new();
+    #[inline]
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::from(value)))
+    }
 
-                if let Some(size) = map.size_hint() {
-                    res.reserve(size);
-                }
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::Float(value)))
+    }
 
-                while let Some((k, v)) = map.next_entry()? {
-                    res.insert(Cow::Owned(k), v);
-                }
+    #[inline]
+    fn visit_char<E>(self, value: char) -> Result<Self::Value, E> {
+        Ok(Intermediate::String(Cow::Owned(value.to_string())))
+    }
 
-                Ok(Intermediate::Map(res))
-            }
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(Intermediate::String(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Intermediate::String(Cow::Owned(String::from(value))))
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(Intermediate::String(B::string(value)))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(Intermediate::Bytes(Cow::Owned(value.to_vec())))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Intermediate::Bytes(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Intermediate::Bytes(B::bytes(value)))
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Intermediate::None)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Intermediate::None)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut res = Vec::new();
+
+        if let Some(size) = seq.size_hint() {
+            res.reserve(size);
+        }
+
+        while let Some(elem) = seq.next_element_seed(ValueSeed::<B>::new())? {
+            res.push(elem);
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        Ok(Intermediate::Array(res))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut res = Map::new();
+
+        if let Some(size) = map.size_hint() {
+            res.reserve(size);
+        }
+
+        while let Some(k) = map.next_key_seed(KeySeed::<B>::new())? {
+            res.insert(k, map.next_value_seed(ValueSeed::<B>::new())?);
+        }
+
+        Ok(Intermediate::Map(res))
+    }
+}
+
+/// Seed for deserializing map keys.
+struct KeySeed<'a, B> {
+    phantom: PhantomData<(Cow<'a, str>, B)>,
+}
+
+impl<B> KeySeed<'_, B> {
+    /// Create a new seed.
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, 'a, B> DeserializeSeed<'de> for KeySeed<'a, B>
+where
+    B: Borrowing<'de, 'a>,
+{
+    type Value = Cow<'a, str>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'a, B> Visitor<'de> for KeySeed<'a, B>
+where
+    B: Borrowing<'de, 'a>,
+{
+    type Value = Cow<'a, str>;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a string")
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(B::string(value))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(String::from(value)))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(value))
     }
 }
@@ -0,0 +1,1086 @@
+//! JSON input and output.
+//!
+//! The JSON emitter writes serialization events directly into an
+//! `io::Write`, so types implementing `Serialize::serialize_to` can be
+//! serialized without building the whole intermediate representation:
+//!
+//! ```rust
+//! use serde_lite::json;
+//!
+//! let data = vec![(1, "foo"), (2, "bar")];
+//!
+//! let res = json::to_string(&data).unwrap();
+//!
+//! assert_eq!(res, r#"[[1,"foo"],[2,"bar"]]"#);
+//! ```
+//!
+//! Non-finite floating point numbers are written as `null` and byte strings
+//! are written as arrays of numbers.
+//!
+//! The JSON reader builds intermediate values from an `io::Read`. Huge
+//! arrays can be processed one element at a time using `ArrayIter`. Only the
+//! current element is kept in memory:
+//!
+//! ```rust
+//! use serde_lite::{json, Error};
+//!
+//! let input = r#"{"meta": {"count": 3}, "records": [1, 2, "three"]}"#;
+//!
+//! let res = json::iter_array_at::<_, u32>(input.as_bytes(), "/records")
+//!     .map(|res| res.map_err(|err| err.to_string()))
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(res[..2], [Ok(1), Ok(2)]);
+//! assert!(matches!(&res[2], Err(msg) if msg.starts_with("[2]: ")));
+//! ```
+
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    mem,
+};
+
+use crate::{
+    emit::{Emitter, Scalar},
+    limits::{LimitTracker, Limits},
+    Deserialize, Error, Intermediate, Map, Number, Serialize, UnnamedFieldError,
+};
+
+/// Serialize a given value as JSON into a given writer.
+#[inline]
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    let mut emitter = JsonEmitter::new(writer);
+
+    value.serialize_to(&mut emitter)?;
+
+    emitter.flush()
+}
+
+/// Serialize a given value as a JSON byte vector.
+#[inline]
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut res = Vec::new();
+
+    to_writer(&mut res, value)?;
+
+    Ok(res)
+}
+
+/// Serialize a given value as a JSON string.
+#[inline]
+pub fn to_string<T>(value: &T) -> Result<String, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let res = to_vec(value)?;
+
+    Ok(String::from_utf8(res).expect("the JSON emitter produces only valid UTF-8"))
+}
+
+/// Container state.
+#[derive(Copy, Clone)]
+enum State {
+    /// Array where the next element is the first one.
+    FirstElement,
+    /// Array where the next element is not the first one.
+    Element,
+    /// Map where the next key is the first one.
+    FirstKey,
+    /// Map where the next key is not the first one.
+    Key,
+}
+
+/// Emitter writing JSON into an `io::Write`.
+pub struct JsonEmitter<W> {
+    writer: W,
+    stack: Vec<State>,
+}
+
+impl<W> JsonEmitter<W> {
+    /// Create a new JSON emitter.
+    #[inline]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Take the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> JsonEmitter<W>
+where
+    W: Write,
+{
+    /// Flush the underlying writer.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(io_error)
+    }
+
+    /// Write a given string.
+    #[inline]
+    fn write(&mut self, s: &str) -> Result<(), Error> {
+        self.writer.write_all(s.as_bytes()).map_err(io_error)
+    }
+
+    /// Write a separator if the next value is not the first array element.
+    #[inline]
+    fn begin_value(&mut self) -> Result<(), Error> {
+        match self.stack.last_mut() {
+            Some(state @ State::FirstElement) => *state = State::Element,
+            Some(State::Element) => return self.write(","),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Write a given number.
+    #[inline(never)]
+    fn write_number(&mut self, n: Number) -> Result<(), Error> {
+        let res = match n {
+            Number::Float(v) if v.is_finite() => write!(self.writer, "{:?}", v),
+            Number::Float(_) => write!(self.writer, "null"),
+            Number::SignedInt(v) => write!(self.writer, "{}", v),
+            Number::UnsignedInt(v) => write!(self.writer, "{}", v),
+            Number::SignedInt128(v) => write!(self.writer, "{}", v),
+            Number::UnsignedInt128(v) => write!(self.writer, "{}", v),
+        };
+
+        res.map_err(io_error)
+    }
+
+    /// Write a given string as a JSON string.
+    #[inline(never)]
+    fn write_string(&mut self, s: &str) -> Result<(), Error> {
+        self.write("\"")?;
+
+        let mut start = 0;
+
+        for (index, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{8}' => "\\b",
+                '\u{c}' => "\\f",
+                c if (c as u32) < 0x20 => "",
+                _ => continue,
+            };
+
+            self.write(&s[start..index])?;
+
+            if escaped.is_empty() {
+                write!(self.writer, "\\u{:04x}", c as u32).map_err(io_error)?;
+            } else {
+                self.write(escaped)?;
+            }
+
+            start = index + c.len_utf8();
+        }
+
+        self.write(&s[start..])?;
+        self.write("\"")
+    }
+
+    /// Write given bytes as an array of numbers.
+    #[inline(never)]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.write("[")?;
+
+        for (index, b) in bytes.iter().enumerate() {
+            if index > 0 {
+                self.write(",")?;
+            }
+
+            write!(self.writer, "{}", b).map_err(io_error)?;
+        }
+
+        self.write("]")
+    }
+}
+
+impl<W> Emitter for JsonEmitter<W>
+where
+    W: Write,
+{
+    fn scalar(&mut self, value: Scalar<'_>) -> Result<(), Error> {
+        self.begin_value()?;
+
+        match value {
+            Scalar::None => self.write("null"),
+            Scalar::Bool(true) => self.write("true"),
+            Scalar::Bool(false) => self.write("false"),
+            Scalar::Number(n) => self.write_number(n),
+            Scalar::String(s) => self.write_string(s),
+            Scalar::Bytes(b) => self.write_bytes(b),
+        }
+    }
+
+    fn begin_array(&mut self, _: Option<usize>) -> Result<(), Error> {
+        self.begin_value()?;
+        self.stack.push(State::FirstElement);
+        self.write("[")
+    }
+
+    fn end_array(&mut self) -> Result<(), Error> {
+        self.stack.pop();
+        self.write("]")
+    }
+
+    fn begin_map(&mut self, _: Option<usize>) -> Result<(), Error> {
+        self.begin_value()?;
+        self.stack.push(State::FirstKey);
+        self.write("{")
+    }
+
+    fn key(&mut self, key: &str) -> Result<(), Error> {
+        let separator = match self.stack.last_mut() {
+            Some(state @ State::FirstKey) => {
+                *state = State::Key;
+                false
+            }
+            Some(State::Key) => true,
+            _ => return Err(Error::custom_static("unexpected map key")),
+        };
+
+        if separator {
+            self.write(",")?;
+        }
+
+        self.write_string(key)?;
+        self.write(":")
+    }
+
+    fn end_map(&mut self) -> Result<(), Error> {
+        self.stack.pop();
+        self.write("}")
+    }
+}
+
+/// Helper function.
+#[inline(never)]
+fn io_error(err: io::Error) -> Error {
+    Error::custom(err)
+}
+
+/// Deserialize a given type from a JSON reader.
+#[inline]
+pub fn from_reader<R, T>(reader: R) -> Result<T, Error>
+where
+    R: Read,
+    T: Deserialize,
+{
+    let mut reader = JsonReader::new(reader);
+
+    let val = reader.read_value()?;
+
+    reader.end()?;
+
+    T::deserialize(&val)
+}
+
+/// Deserialize a given type from JSON bytes.
+#[inline]
+pub fn from_slice<T>(input: &[u8]) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    from_reader(input)
+}
+
+/// Deserialize a given type from a JSON string.
+#[inline]
+pub fn from_str<T>(input: &str) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    from_slice(input.as_bytes())
+}
+
+/// Iterate over elements of a top-level JSON array.
+#[inline]
+pub fn iter_array<R, T>(reader: R) -> ArrayIter<R, T>
+where
+    R: Read,
+    T: Deserialize,
+{
+    JsonReader::new(reader).into_array_iter("")
+}
+
+/// Iterate over elements of a JSON array referenced by a given JSON pointer
+/// (e.g. `/data/records`).
+#[inline]
+pub fn iter_array_at<R, T>(reader: R, pointer: &str) -> ArrayIter<R, T>
+where
+    R: Read,
+    T: Deserialize,
+{
+    JsonReader::new(reader).into_array_iter(pointer)
+}
+
+/// Size of the JSON reader buffer.
+const BUFFER_SIZE: usize = 8192;
+
+/// Maximum length of a number.
+///
+/// This is enough for any exactly written `f64` value.
+const MAX_NUMBER_LENGTH: usize = 1024;
+
+/// JSON reader.
+///
+/// The reader builds intermediate values from an `io::Read`. The input is
+/// buffered internally, so there is no need to wrap the reader into a
+/// `BufReader`.
+pub struct JsonReader<R> {
+    reader: R,
+    buffer: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    offset: usize,
+    limits: Limits,
+}
+
+impl<R> JsonReader<R> {
+    /// Create a new JSON reader.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            offset: 0,
+            limits: Limits::new(),
+        }
+    }
+
+    /// Use given limits for all values read from now on.
+    #[inline]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Get the number of bytes consumed so far.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Create a syntax error at the current position.
+    #[inline(never)]
+    fn syntax_error(&self, msg: &str) -> Error {
+        Error::custom(format_args!("{} at byte {}", msg, self.offset))
+    }
+
+    /// Consume the current byte.
+    #[inline]
+    fn bump(&mut self) {
+        self.pos += 1;
+        self.offset += 1;
+    }
+}
+
+impl<R> JsonReader<R>
+where
+    R: Read,
+{
+    /// Read a single JSON value.
+    ///
+    /// Whitespace after the value is not consumed.
+    #[inline]
+    pub fn read_value(&mut self) -> Result<Intermediate<'static>, Error> {
+        let mut tracker = LimitTracker::new(self.limits);
+
+        self.parse(&mut tracker, true)
+    }
+
+    /// Check that there is nothing but whitespace left in the input.
+    #[inline]
+    pub fn end(&mut self) -> Result<(), Error> {
+        if self.peek_non_ws()?.is_some() {
+            Err(self.syntax_error("trailing characters"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Create an iterator over elements of a JSON array referenced by a
+    /// given JSON pointer.
+    ///
+    /// An empty pointer references the whole input. The limits are applied
+    /// to each element separately.
+    #[inline]
+    pub fn into_array_iter<T>(self, pointer: &str) -> ArrayIter<R, T>
+    where
+        T: Deserialize,
+    {
+        ArrayIter {
+            reader: self,
+            pointer: String::from(pointer),
+            state: IterState::Start,
+            index: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Fill the buffer. The method returns `false` if there is no more
+    /// input.
+    #[inline(never)]
+    fn fill(&mut self) -> Result<bool, Error> {
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(len) => {
+                    self.pos = 0;
+                    self.len = len;
+
+                    return Ok(len > 0);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(io_error(err)),
+            }
+        }
+    }
+
+    /// Peek at the next byte.
+    #[inline]
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        if self.pos == self.len && !self.fill()? {
+            Ok(None)
+        } else {
+            Ok(Some(self.buffer[self.pos]))
+        }
+    }
+
+    /// Consume and return the next byte.
+    #[inline]
+    fn next_byte(&mut self) -> Result<Option<u8>, Error> {
+        let res = self.peek()?;
+
+        if res.is_some() {
+            self.bump();
+        }
+
+        Ok(res)
+    }
+
+    /// Skip whitespace and peek at the next byte.
+    #[inline(never)]
+    fn peek_non_ws(&mut self) -> Result<Option<u8>, Error> {
+        while let Some(b) = self.peek()? {
+            if !matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+                return Ok(Some(b));
+            }
+
+            self.bump();
+        }
+
+        Ok(None)
+    }
+
+    /// Skip whitespace and consume the next byte if it is one of given
+    /// bytes.
+    #[inline(never)]
+    fn next_of(&mut self, expected: &[u8]) -> Result<Option<u8>, Error> {
+        match self.peek_non_ws()? {
+            Some(b) if expected.contains(&b) => {
+                self.bump();
+
+                Ok(Some(b))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Consume a given literal (e.g. `true`).
+    #[inline(never)]
+    fn expect_literal(&mut self, literal: &[u8]) -> Result<(), Error> {
+        for b in literal {
+            if self.next_byte()? != Some(*b) {
+                return Err(self.syntax_error("invalid literal"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a single value.
+    ///
+    /// Arrays and maps are not built if `keep` is `false`. The value is
+    /// only validated in such case.
+    #[inline(never)]
+    fn parse(
+        &mut self,
+        tracker: &mut LimitTracker,
+        keep: bool,
+    ) -> Result<Intermediate<'static>, Error> {
+        /// Partially parsed array or map.
+        enum Frame {
+            Array(Vec<Intermediate<'static>>, usize),
+            Map(Map<'static>, String, usize),
+        }
+
+        let mut stack = Vec::new();
+
+        loop {
+            let b = match self.peek_non_ws()? {
+                Some(b) => b,
+                None => return Err(self.syntax_error("unexpected end of input")),
+            };
+
+            tracker.node()?;
+
+            let mut value = match b {
+                b'[' => {
+                    self.bump();
+
+                    tracker.enter()?;
+
+                    if self.peek_non_ws()? == Some(b']') {
+                        self.bump();
+
+                        tracker.leave();
+
+                        Intermediate::Array(Vec::new())
+                    } else {
+                        stack.push(Frame::Array(Vec::new(), 0));
+
+                        continue;
+                    }
+                }
+                b'{' => {
+                    self.bump();
+
+                    tracker.enter()?;
+
+                    if self.peek_non_ws()? == Some(b'}') {
+                        self.bump();
+
+                        tracker.leave();
+
+                        Intermediate::Map(Map::new())
+                    } else {
+                        let key = self.read_key(tracker, keep)?;
+
+                        stack.push(Frame::Map(Map::new(), key, 0));
+
+                        continue;
+                    }
+                }
+                _ => self.read_scalar(b, tracker, keep)?,
+            };
+
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(Frame::Array(arr, len)) => {
+                        *len += 1;
+
+                        tracker.check_array_length(*len)?;
+
+                        if keep {
+                            arr.push(value);
+                        }
+
+                        match self.next_of(b",]")? {
+                            Some(b',') => break,
+                            Some(_) => (),
+                            None => return Err(self.syntax_error("expected `,` or `]`")),
+                        }
+                    }
+                    Some(Frame::Map(map, key, len)) => {
+                        *len += 1;
+
+                        tracker.check_map_size(*len)?;
+
+                        if keep {
+                            map.insert_with_owned_key(mem::take(key), value);
+                        }
+
+                        match self.next_of(b",}")? {
+                            Some(b',') => {
+                                *key = self.read_key(tracker, keep)?;
+
+                                break;
+                            }
+                            Some(_) => (),
+                            None => return Err(self.syntax_error("expected `,` or `}`")),
+                        }
+                    }
+                }
+
+                // the container is complete now
+                tracker.leave();
+
+                value = match stack.pop() {
+                    Some(Frame::Array(arr, _)) => Intermediate::Array(arr),
+                    Some(Frame::Map(map, _, _)) => Intermediate::Map(map),
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    /// Skip a single value.
+    ///
+    /// The skipped value is not kept but it is still subject to the limits.
+    #[inline]
+    fn skip_value(&mut self) -> Result<(), Error> {
+        let mut tracker = LimitTracker::new(self.limits);
+
+        self.parse(&mut tracker, false).map(|_| ())
+    }
+
+    /// Read a map key including the following colon.
+    #[inline(never)]
+    fn read_key(&mut self, tracker: &LimitTracker, keep: bool) -> Result<String, Error> {
+        if self.next_of(b"\"")?.is_none() {
+            return Err(self.syntax_error("expected map key"));
+        }
+
+        let key = self.read_string(tracker, keep)?;
+
+        if self.next_of(b":")?.is_none() {
+            return Err(self.syntax_error("expected `:`"));
+        }
+
+        Ok(key)
+    }
+
+    /// Read a scalar value starting with a given byte.
+    #[inline(never)]
+    fn read_scalar(
+        &mut self,
+        first: u8,
+        tracker: &LimitTracker,
+        keep: bool,
+    ) -> Result<Intermediate<'static>, Error> {
+        let res = match first {
+            b'"' => {
+                self.bump();
+
+                let s = self.read_string(tracker, keep)?;
+
+                Intermediate::String(Cow::Owned(s))
+            }
+            b't' => {
+                self.expect_literal(b"true")?;
+
+                Intermediate::Bool(true)
+            }
+            b'f' => {
+                self.expect_literal(b"false")?;
+
+                Intermediate::Bool(false)
+            }
+            b'n' => {
+                self.expect_literal(b"null")?;
+
+                Intermediate::None
+            }
+            b'-' | b'0'..=b'9' => Intermediate::Number(self.read_number()?),
+            _ => return Err(self.syntax_error("expected value")),
+        };
+
+        Ok(res)
+    }
+
+    /// Read a number.
+    #[inline(never)]
+    fn read_number(&mut self) -> Result<Number, Error> {
+        let mut buf = String::new();
+
+        while let Some(b) = self.peek()? {
+            if !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                break;
+            }
+
+            if buf.len() >= MAX_NUMBER_LENGTH {
+                return Err(self.syntax_error("number too long"));
+            }
+
+            buf.push(b as char);
+
+            self.bump();
+        }
+
+        parse_number(&buf).ok_or_else(|| self.syntax_error("invalid number"))
+    }
+
+    /// Read the rest of a string (the opening quote must be already
+    /// consumed).
+    ///
+    /// An empty string is returned if `keep` is `false`.
+    #[inline(never)]
+    fn read_string(&mut self, tracker: &LimitTracker, keep: bool) -> Result<String, Error> {
+        let mut res = Vec::new();
+        let mut len = 0;
+
+        loop {
+            if self.pos == self.len && !self.fill()? {
+                return Err(self.syntax_error("unexpected end of input"));
+            }
+
+            let chunk = &self.buffer[self.pos..self.len];
+
+            let n = chunk
+                .iter()
+                .position(|&b| b == b'"' || b == b'\\' || b < 0x20)
+                .unwrap_or(chunk.len());
+
+            if keep {
+                res.extend_from_slice(&chunk[..n]);
+            }
+
+            self.pos += n;
+            self.offset += n;
+
+            len += n;
+
+            tracker.check_string_length(len)?;
+
+            if self.pos == self.len {
+                continue;
+            }
+
+            match self.buffer[self.pos] {
+                b'"' => {
+                    self.bump();
+
+                    break;
+                }
+                b'\\' => {
+                    self.bump();
+
+                    let c = self.read_escape()?;
+
+                    let mut buf = [0u8; 4];
+
+                    let c = c.encode_utf8(&mut buf);
+
+                    if keep {
+                        res.extend_from_slice(c.as_bytes());
+                    }
+
+                    len += c.len();
+
+                    tracker.check_string_length(len)?;
+                }
+                _ => return Err(self.syntax_error("control character in string")),
+            }
+        }
+
+        String::from_utf8(res).map_err(|_| self.syntax_error("invalid UTF-8 in string"))
+    }
+
+    /// Read an escape sequence (the backslash must be already consumed).
+    #[inline(never)]
+    fn read_escape(&mut self) -> Result<char, Error> {
+        let c = match self.next_byte()? {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let high = self.read_hex()?;
+
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    if self.next_byte()? != Some(b'\\') || self.next_byte()? != Some(b'u') {
+                        return Err(self.syntax_error("unpaired surrogate"));
+                    }
+
+                    let low = self.read_hex()?;
+
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.syntax_error("unpaired surrogate"));
+                    }
+
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+
+                return char::from_u32(code).ok_or_else(|| self.syntax_error("unpaired surrogate"));
+            }
+            _ => return Err(self.syntax_error("invalid escape sequence")),
+        };
+
+        Ok(c)
+    }
+
+    /// Read four hexadecimal digits.
+    #[inline(never)]
+    fn read_hex(&mut self) -> Result<u32, Error> {
+        let mut res = 0;
+
+        for _ in 0..4 {
+            let digit = self
+                .next_byte()?
+                .and_then(|b| (b as char).to_digit(16))
+                .ok_or_else(|| self.syntax_error("invalid unicode escape"))?;
+
+            res = (res << 4) | digit;
+        }
+
+        Ok(res)
+    }
+
+    /// Move to a value referenced by a given JSON pointer.
+    #[inline(never)]
+    fn seek(&mut self, pointer: &str) -> Result<(), Error> {
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Err(Error::custom(format_args!(
+                "invalid JSON pointer \"{}\"",
+                pointer
+            )));
+        }
+
+        let not_found = || {
+            Error::custom(format_args!(
+                "JSON pointer \"{}\" does not reference any value",
+                pointer
+            ))
+        };
+
+        let tracker = LimitTracker::new(self.limits);
+
+        for token in pointer.split('/').skip(1) {
+            let token = token.replace("~1", "/").replace("~0", "~");
+
+            match self.next_of(b"{[")? {
+                Some(b'{') => {
+                    if self.peek_non_ws()? == Some(b'}') {
+                        return Err(not_found());
+                    }
+
+                    while self.read_key(&tracker, true)? != token {
+                        self.skip_value()?;
+
+                        match self.next_of(b",}")? {
+                            Some(b',') => (),
+                            Some(_) => return Err(not_found()),
+                            None => return Err(self.syntax_error("expected `,` or `}`")),
+                        }
+                    }
+                }
+                Some(b'[') => {
+                    let index = token.parse::<usize>().map_err(|_| not_found())?;
+
+                    if self.peek_non_ws()? == Some(b']') {
+                        return Err(not_found());
+                    }
+
+                    for _ in 0..index {
+                        self.skip_value()?;
+
+                        match self.next_of(b",]")? {
+                            Some(b',') => (),
+                            Some(_) => return Err(not_found()),
+                            None => return Err(self.syntax_error("expected `,` or `]`")),
+                        }
+                    }
+                }
+                _ => return Err(not_found()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// State of an array iterator.
+#[derive(Copy, Clone)]
+enum IterState {
+    Start,
+    Next,
+    Done,
+}
+
+/// Iterator over elements of a JSON array.
+///
+/// Only one element is kept in memory at a time. Errors of individual
+/// elements contain the element index. The iterator stops after the first
+/// syntax error.
+pub struct ArrayIter<R, T> {
+    reader: JsonReader<R>,
+    pointer: String,
+    state: IterState,
+    index: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<R, T> ArrayIter<R, T>
+where
+    R: Read,
+{
+    /// Read the next element.
+    fn next_element(&mut self) -> Result<Option<Intermediate<'static>>, Error> {
+        match self.state {
+            IterState::Start => {
+                self.reader.seek(&self.pointer)?;
+
+                if self.reader.next_of(b"[")?.is_none() {
+                    return Err(Error::custom_static("expected array"));
+                }
+
+                if self.reader.peek_non_ws()? == Some(b']') {
+                    self.reader.bump();
+
+                    return self.finish();
+                }
+            }
+            IterState::Next => match self.reader.next_of(b",]")? {
+                Some(b',') => (),
+                Some(_) => return self.finish(),
+                None => return Err(self.reader.syntax_error("expected `,` or `]`")),
+            },
+            IterState::Done => return Ok(None),
+        }
+
+        if self.index >= self.reader.limits.max_array_length() {
+            return Err(Error::ArrayLengthLimitExceeded);
+        }
+
+        let val = self
+            .reader
+            .read_value()
+            .map_err(|err| UnnamedFieldError::new(self.index, err))?;
+
+        self.state = IterState::Next;
+
+        Ok(Some(val))
+    }
+
+    /// Finish the iteration.
+    fn finish(&mut self) -> Result<Option<Intermediate<'static>>, Error> {
+        self.state = IterState::Done;
+
+        // the rest of the input is not checked if the array is not the
+        // top-level value
+        if self.pointer.is_empty() {
+            self.reader.end()?;
+        }
+
+        Ok(None)
+    }
+}
+
+impl<R, T> Iterator for ArrayIter<R, T>
+where
+    R: Read,
+    T: Deserialize,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_element() {
+            Ok(Some(val)) => {
+                let index = self.index;
+
+                self.index += 1;
+
+                let res = T::deserialize(&val).map_err(|err| UnnamedFieldError::new(index, err));
+
+                Some(res.map_err(Error::from))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.state = IterState::Done;
+
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Parse a given JSON number.
+#[inline(never)]
+fn parse_number(s: &str) -> Option<Number> {
+    let bytes = s.as_bytes();
+
+    let mut pos = 0;
+
+    let digits = |pos: &mut usize| {
+        let start = *pos;
+
+        while bytes.get(*pos).map(u8::is_ascii_digit).unwrap_or(false) {
+            *pos += 1;
+        }
+
+        *pos > start
+    };
+
+    if bytes.first() == Some(&b'-') {
+        pos += 1;
+    }
+
+    match bytes.get(pos) {
+        Some(b'0') => pos += 1,
+        Some(b'1'..=b'9') => {
+            digits(&mut pos);
+        }
+        _ => return None,
+    }
+
+    let integer = pos == bytes.len();
+
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+
+        if !digits(&mut pos) {
+            return None;
+        }
+    }
+
+    if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+
+        if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+            pos += 1;
+        }
+
+        if !digits(&mut pos) {
+            return None;
+        }
+    }
+
+    if pos != bytes.len() {
+        return None;
+    }
+
+    // negative zero cannot be represented as an integer
+    if integer && s != "-0" {
+        if let Ok(v) = s.parse() {
+            return Some(Number::UnsignedInt(v));
+        } else if let Ok(v) = s.parse() {
+            return Some(Number::SignedInt(v));
+        } else if let Ok(v) = s.parse::<u128>() {
+            return Some(Number::from(v));
+        } else if let Ok(v) = s.parse::<i128>() {
+            return Some(Number::from(v));
+        }
+    }
+
+    s.parse().ok().map(Number::Float)
+}
@@ -0,0 +1,478 @@
+//! JSON Lines (newline-delimited JSON).
+//!
+//! Every line of the input or output contains a single JSON document. Lines
+//! are converted from and into `Intermediate` values using a `Codec`. The
+//! native JSON codec is used by default. A codec based on `serde_json` is
+//! available with the `serde-json` feature.
+//!
+//! ```rust
+//! use serde_lite::jsonl::{self, JsonLinesReader, OnError};
+//!
+//! let mut output = Vec::new();
+//!
+//! jsonl::to_writer(&mut output, &[(1, "foo"), (2, "bar")]).unwrap();
+//!
+//! assert_eq!(output, b"[1,\"foo\"]\n[2,\"bar\"]\n");
+//!
+//! output.extend_from_slice(b"invalid\n[3, \"baz\"]\n");
+//!
+//! let res = JsonLinesReader::<_, (u32, String)>::new(&output[..])
+//!     .on_error(OnError::Skip)
+//!     .map(|res| res.unwrap().0)
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(res, [1, 2, 3]);
+//! ```
+
+use std::{
+    io::{BufRead, Read, Write},
+    marker::PhantomData,
+};
+
+use crate::{
+    emit,
+    json::{JsonEmitter, JsonReader},
+    limits::Limits,
+    Deserialize, Error, Intermediate, LineError, Serialize,
+};
+
+/// JSON codec used for individual lines.
+pub trait Codec {
+    /// Decode a given JSON document.
+    fn decode<'a>(&self, input: &'a str) -> Result<Intermediate<'a>, Error>;
+
+    /// Encode a given value as a JSON document not containing any line
+    /// breaks.
+    fn encode(&self, val: &Intermediate, output: &mut Vec<u8>) -> Result<(), Error>;
+
+    /// Serialize and encode a given value as a JSON document not containing
+    /// any line breaks.
+    ///
+    /// The default implementation serializes the value into the
+    /// intermediate representation at first.
+    #[inline]
+    fn encode_value<T>(&self, value: &T, output: &mut Vec<u8>) -> Result<(), Error>
+    where
+        Self: Sized,
+        T: Serialize + ?Sized,
+    {
+        self.encode(&value.serialize()?, output)
+    }
+}
+
+/// Codec using the native JSON reader and writer.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NativeCodec {
+    limits: Limits,
+}
+
+impl NativeCodec {
+    /// Create a new native codec.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            limits: Limits::new(),
+        }
+    }
+
+    /// Use given limits for decoding.
+    #[inline]
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Codec for NativeCodec {
+    fn decode<'a>(&self, input: &'a str) -> Result<Intermediate<'a>, Error> {
+        let mut reader = JsonReader::new(input.as_bytes()).with_limits(self.limits);
+
+        let res = reader.read_value()?;
+
+        reader.end()?;
+
+        Ok(res)
+    }
+
+    fn encode(&self, val: &Intermediate, output: &mut Vec<u8>) -> Result<(), Error> {
+        emit::emit(val, &mut JsonEmitter::new(output))
+    }
+
+    #[inline]
+    fn encode_value<T>(&self, value: &T, output: &mut Vec<u8>) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize_to(&mut JsonEmitter::new(output))
+    }
+}
+
+/// Codec using `serde_json`.
+#[cfg(feature = "serde-json")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SerdeJsonCodec {
+    limits: Limits,
+}
+
+#[cfg(feature = "serde-json")]
+impl SerdeJsonCodec {
+    /// Create a new serde_json codec.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            limits: Limits::new(),
+        }
+    }
+
+    /// Use given limits for decoding.
+    #[inline]
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl Codec for SerdeJsonCodec {
+    fn decode<'a>(&self, input: &'a str) -> Result<Intermediate<'a>, Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(input);
+
+        let res = self.limits.deserialize(&mut deserializer)?;
+
+        deserializer.end().map_err(Error::custom)?;
+
+        Ok(res)
+    }
+
+    fn encode(&self, val: &Intermediate, output: &mut Vec<u8>) -> Result<(), Error> {
+        serde_json::to_writer(output, val).map_err(Error::custom)
+    }
+}
+
+/// Serialize all items of a given iterator as JSON Lines into a given
+/// writer.
+#[inline]
+pub fn to_writer<W, I>(writer: W, items: I) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Serialize,
+{
+    let mut writer = JsonLinesWriter::new(writer);
+
+    writer.write_all(items)?;
+    writer.flush()
+}
+
+/// Policy for lines that cannot be decoded or deserialized.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OnError {
+    /// Return the error and stop reading.
+    Fail,
+    /// Skip the line.
+    Skip,
+}
+
+/// JSON Lines reader.
+///
+/// The reader yields one deserialized value per line. Empty lines are
+/// ignored. Errors are reported as `Error::Line` containing the line number.
+/// I/O errors always stop the reader regardless of the `OnError` policy.
+///
+/// The length of a line is not limited by default. Use
+/// `with_max_line_length` when reading untrusted input. Lines exceeding the
+/// limit are not kept in memory and they are handled like any other bad
+/// line.
+pub struct JsonLinesReader<R, T, C = NativeCodec> {
+    reader: R,
+    codec: C,
+    policy: OnError,
+    max_line_length: usize,
+    buffer: Vec<u8>,
+    too_long: bool,
+    line: usize,
+    skipped: usize,
+    done: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<R, T> JsonLinesReader<R, T> {
+    /// Create a new reader using the native JSON codec.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self::with_codec(reader, NativeCodec::new())
+    }
+}
+
+impl<R, T, C> JsonLinesReader<R, T, C> {
+    /// Create a new reader using a given codec.
+    #[inline]
+    pub fn with_codec(reader: R, codec: C) -> Self {
+        Self {
+            reader,
+            codec,
+            policy: OnError::Fail,
+            max_line_length: usize::MAX,
+            buffer: Vec::new(),
+            too_long: false,
+            line: 0,
+            skipped: 0,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Set the policy for lines that cannot be decoded or deserialized.
+    ///
+    /// The default policy is `OnError::Fail`.
+    #[inline]
+    pub fn on_error(mut self, policy: OnError) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Set the maximum length of a line in bytes (not including the line
+    /// break).
+    #[inline]
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Get the number of lines read so far.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the number of skipped lines.
+    #[inline]
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Take the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, T, C> JsonLinesReader<R, T, C>
+where
+    R: BufRead,
+    T: Deserialize,
+    C: Codec,
+{
+    /// Read the next line. The method returns `false` if there are no more
+    /// lines.
+    #[inline(never)]
+    fn read_line(&mut self) -> Result<bool, Error> {
+        self.buffer.clear();
+
+        // read at most one byte more than allowed, so that the line break
+        // following a line of the maximum length fits in as well
+        let limit = (self.max_line_length as u64).saturating_add(1);
+
+        let len = (&mut self.reader)
+            .take(limit)
+            .read_until(b'\n', &mut self.buffer)
+            .map_err(|err| LineError::new(self.line + 1, Error::custom(err)))?;
+
+        self.too_long = len > self.max_line_length && !self.buffer.ends_with(b"\n");
+
+        if self.too_long {
+            self.buffer.clear();
+            self.skip_line()
+                .map_err(|err| LineError::new(self.line + 1, Error::custom(err)))?;
+        }
+
+        if len > 0 {
+            self.line += 1;
+        }
+
+        Ok(len > 0)
+    }
+
+    /// Skip the rest of the current line including the line break.
+    fn skip_line(&mut self) -> std::io::Result<()> {
+        loop {
+            let available = self.reader.fill_buf()?;
+
+            if available.is_empty() {
+                return Ok(());
+            }
+
+            if let Some(pos) = available.iter().position(|&b| b == b'\n') {
+                self.reader.consume(pos + 1);
+
+                return Ok(());
+            }
+
+            let len = available.len();
+
+            self.reader.consume(len);
+        }
+    }
+
+    /// Decode and deserialize the current line.
+    #[inline(never)]
+    fn parse_line(&self) -> Result<Option<T>, Error> {
+        if self.too_long {
+            return Err(Error::custom_static("maximum line length exceeded"));
+        }
+
+        let line = std::str::from_utf8(&self.buffer)
+            .map_err(|_| Error::custom_static("invalid UTF-8"))?
+            .trim();
+
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        let val = self.codec.decode(line)?;
+
+        T::deserialize(&val).map(Some)
+    }
+}
+
+impl<R, T, C> Iterator for JsonLinesReader<R, T, C>
+where
+    R: BufRead,
+    T: Deserialize,
+    C: Codec,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.read_line() {
+                Ok(true) => (),
+                Ok(false) => {
+                    self.done = true;
+
+                    return None;
+                }
+                Err(err) => {
+                    self.done = true;
+
+                    return Some(Err(err));
+                }
+            }
+
+            match self.parse_line() {
+                Ok(Some(val)) => return Some(Ok(val)),
+                Ok(None) => (),
+                Err(_) if self.policy == OnError::Skip => self.skipped += 1,
+                Err(err) => {
+                    self.done = true;
+
+                    return Some(Err(LineError::new(self.line, err).into()));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// JSON Lines writer.
+///
+/// Every value is written as a single compact JSON document followed by a
+/// line break. Errors are reported as `Error::Line` containing the line
+/// number.
+pub struct JsonLinesWriter<W, C = NativeCodec> {
+    writer: W,
+    codec: C,
+    buffer: Vec<u8>,
+    line: usize,
+}
+
+impl<W> JsonLinesWriter<W> {
+    /// Create a new writer using the native JSON codec.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self::with_codec(writer, NativeCodec::new())
+    }
+}
+
+impl<W, C> JsonLinesWriter<W, C> {
+    /// Create a new writer using a given codec.
+    #[inline]
+    pub fn with_codec(writer: W, codec: C) -> Self {
+        Self {
+            writer,
+            codec,
+            buffer: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// Get the number of lines written so far.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Take the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W, C> JsonLinesWriter<W, C>
+where
+    W: Write,
+    C: Codec,
+{
+    /// Write a given value as a single line.
+    #[inline]
+    pub fn write<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.buffer.clear();
+
+        let res = self.codec.encode_value(value, &mut self.buffer);
+
+        self.write_line(res)
+    }
+
+    /// Write all items of a given iterator.
+    pub fn write_all<I>(&mut self, items: I) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        for item in items {
+            self.write(&item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(Error::custom)
+    }
+
+    /// Write the encoded value in the buffer as a single line.
+    #[inline(never)]
+    fn write_line(&mut self, encoded: Result<(), Error>) -> Result<(), Error> {
+        let res = encoded.and_then(|_| {
+            self.buffer.push(b'\n');
+
+            self.writer.write_all(&self.buffer).map_err(Error::custom)
+        });
+
+        if let Err(err) = res {
+            return Err(LineError::new(self.line + 1, err).into());
+        }
+
+        self.line += 1;
+
+        Ok(())
+    }
+}
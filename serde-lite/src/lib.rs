@@ -96,6 +96,10 @@
 //! This feature can be especially handy if you're constructing a REST API and
 //! you'd like to allow partial updates of your data.
 //!
+//! An update collects errors of all fields, so it may leave the object modified
+//! partially if it fails. Use `Update::try_update_atomic` if the object must stay
+//! unchanged in such case (the object must implement `Clone`).
+//!
 //! ## Supported attributes
 //!
 //! The library does not support all Serde attributes at this moment. Patches are
@@ -104,9 +108,14 @@
 //! * Container attributes:
 //!     * `tag`
 //!     * `content`
+//!     * `serde_compat`
+//!     * `compact`
+//!     * `validate` (see the `validate` module)
 //! * Field attributes:
+//!     * `bytes`
 //!     * `default`
 //!     * `flatten`
+//!     * `non_finite`
 //!     * `rename`
 //!     * `skip`
 //!     * `skip_serializing`
@@ -115,6 +124,9 @@
 //!     * `serialize_with`
 //!     * `deserialize_with`
 //!     * `update_with`
+//!     * `describe_with` (`Describe` only)
+//!     * `validate` (see the `validate` module)
+//!     * `with`
 //! * Enum variant attributes:
 //!     * `rename`
 //!
@@ -129,10 +141,31 @@
 //!
 //! If the only thing that matters to you is the runtime performance, you probably
 //! don't want to use this library. It also isn't very useful for
-//! serializing/de-serializing huge amount of data because it needs to be
-//! transformed into the intermediate representation at first. And, finally, this
+//! de-serializing huge amount of data because it needs to be transformed into
+//! the intermediate representation at first (serialization can be streamed
+//! using `Serialize::serialize_to` and huge JSON arrays can be processed one
+//! element at a time using `json::ArrayIter` though). And, finally, this
 //! library can only be used with self-describing formats like JSON.
 
+pub mod arena;
+pub mod base64;
+pub mod bytes;
+pub mod compact;
+pub mod compat;
+pub mod csv;
+pub mod describe;
+pub mod emit;
+pub mod float;
+pub mod hex;
+pub mod json;
+pub mod jsonl;
+pub mod limits;
+pub mod pattern;
+pub mod schema;
+pub mod typescript;
+pub mod validate;
+pub mod view;
+
 mod deserialize;
 mod intermediate;
 mod map;
@@ -146,14 +179,18 @@ use std::{
 };
 
 #[cfg(feature = "derive")]
-pub use serde_lite_derive::{Deserialize, Serialize, Update};
+pub use serde_lite_derive::{Describe, Deserialize, DeserializeView, Serialize, Update};
 
 pub use crate::{
-    deserialize::Deserialize,
+    bytes::{ByteBuf, Bytes},
+    compat::SerdeCompat,
+    describe::Describe,
+    deserialize::{Deserialize, DeserializeBorrowed},
     intermediate::{Intermediate, Number},
     map::{Map, MapImpl},
     serialize::Serialize,
     update::Update,
+    view::{DeserializeView, ValueView},
 };
 
 /// Error.
@@ -167,7 +204,16 @@ pub enum Error {
     InvalidValue(Cow<'static, str>),
     NamedFieldErrors(ErrorList<NamedFieldError>),
     UnnamedFieldErrors(ErrorList<UnnamedFieldError>),
+    DepthLimitExceeded,
+    NodeLimitExceeded,
+    StringLengthLimitExceeded,
+    ArrayLengthLimitExceeded,
+    MapSizeLimitExceeded,
+    Line(Box<LineError>),
+    Validation(Cow<'static, str>),
     Custom(Cow<'static, str>),
+    /// Several errors at the same location.
+    Multiple(Vec<Error>),
 }
 
 impl Error {
@@ -186,6 +232,21 @@ impl Error {
         Self::InvalidValue(Cow::Borrowed(expected))
     }
 
+    /// Create a validation error with a given error message.
+    #[inline]
+    pub fn validation<T>(msg: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::Validation(Cow::Owned(msg.to_string()))
+    }
+
+    /// Create a validation error with a given error message.
+    #[inline]
+    pub const fn validation_static(msg: &'static str) -> Self {
+        Self::Validation(Cow::Borrowed(msg))
+    }
+
     /// Create a custom error with a given error message.
     #[inline]
     pub fn custom<T>(msg: T) -> Self
@@ -200,10 +261,31 @@ impl Error {
     pub const fn custom_static(msg: &'static str) -> Self {
         Self::Custom(Cow::Borrowed(msg))
     }
+
+    /// Get all leaf errors (i.e. errors that are not field errors) together
+    /// with their paths.
+    ///
+    /// The path of a leaf error is empty if this is not a field error.
+    #[inline]
+    pub fn leaves(&self) -> Vec<(ErrorPath<'_>, &Error)> {
+        collect_leaves(Vec::new(), self)
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::NamedFieldErrors(_) | Self::UnnamedFieldErrors(_) | Self::Multiple(_) => {
+                fmt_leaves(f, &self.leaves())
+            }
+            _ => self.fmt_leaf(f),
+        }
+    }
+}
+
+impl Error {
+    /// Format a leaf error.
+    fn fmt_leaf(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::OutOfBounds => f.write_str("value is out of bounds"),
             Self::UnsupportedConversion => f.write_str("conversion not supported"),
@@ -211,13 +293,16 @@ impl Display for Error {
             Self::UnknownEnumVariant => f.write_str("unknown enum variant"),
             Self::MissingEnumVariantContent => f.write_str("missing enum variant content"),
             Self::InvalidValue(expected) => write!(f, "invalid value ({} expected)", expected),
-            Self::NamedFieldErrors(errors) => {
-                write!(f, "field errors ({})", errors)
-            }
-            Self::UnnamedFieldErrors(errors) => {
-                write!(f, "field errors ({})", errors)
-            }
+            Self::NamedFieldErrors(_) | Self::UnnamedFieldErrors(_) => f.write_str("field errors"),
+            Self::DepthLimitExceeded => f.write_str("maximum depth exceeded"),
+            Self::NodeLimitExceeded => f.write_str("maximum number of values exceeded"),
+            Self::StringLengthLimitExceeded => f.write_str("maximum string length exceeded"),
+            Self::ArrayLengthLimitExceeded => f.write_str("maximum array length exceeded"),
+            Self::MapSizeLimitExceeded => f.write_str("maximum map size exceeded"),
+            Self::Line(err) => Display::fmt(err, f),
+            Self::Validation(msg) => f.write_str(msg),
             Self::Custom(msg) => f.write_str(msg),
+            Self::Multiple(_) => f.write_str("multiple errors"),
         }
     }
 }
@@ -260,6 +345,13 @@ impl From<UnnamedFieldError> for Error {
     }
 }
 
+impl From<LineError> for Error {
+    #[inline]
+    fn from(err: LineError) -> Self {
+        Self::Line(Box::new(err))
+    }
+}
+
 /// Error associated with a named field.
 #[derive(Debug, Clone)]
 pub struct NamedFieldError {
@@ -289,6 +381,12 @@ impl NamedFieldError {
         }
     }
 
+    /// Create a new error for a given map key.
+    #[inline]
+    pub(crate) const fn from_key(key: Cow<'static, str>, error: Error) -> Self {
+        Self { field: key, error }
+    }
+
     /// Get the name of the field.
     #[inline]
     pub fn field(&self) -> &str {
@@ -310,7 +408,9 @@ impl NamedFieldError {
 
 impl Display for NamedFieldError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}: {}", self.field, self.error)
+        let path = vec![PathSegment::Field(&self.field)];
+
+        fmt_leaves(f, &collect_leaves(path, &self.error))
     }
 }
 
@@ -354,12 +454,185 @@ impl UnnamedFieldError {
 
 impl Display for UnnamedFieldError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}: {}", self.index, self.error)
+        let path = vec![PathSegment::Index(self.index)];
+
+        fmt_leaves(f, &collect_leaves(path, &self.error))
     }
 }
 
 impl std::error::Error for UnnamedFieldError {}
 
+/// Error associated with a line of a line-based input or output (e.g. JSON
+/// Lines).
+#[derive(Debug, Clone)]
+pub struct LineError {
+    line: usize,
+    error: Error,
+}
+
+impl LineError {
+    /// Create a new error for a given line number (starting from 1).
+    #[inline]
+    pub const fn new(line: usize, error: Error) -> Self {
+        Self { line, error }
+    }
+
+    /// Get the line number.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the error.
+    #[inline]
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Take the error.
+    #[inline]
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// Segment of an error path.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// Named field or map key.
+    Field(&'a str),
+    /// Unnamed field or array index.
+    Index(usize),
+}
+
+/// Path to a nested error.
+///
+/// The path is displayed as a sequence of field names and indices, e.g.
+/// `items[17].price`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ErrorPath<'a> {
+    segments: Vec<PathSegment<'a>>,
+}
+
+impl<'a> ErrorPath<'a> {
+    /// Check if the path is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Get the path segments.
+    #[inline]
+    pub fn segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+
+    /// Format the path as a JSON Pointer (RFC 6901), e.g. `/items/17/price`.
+    pub fn to_json_pointer(&self) -> String {
+        let mut res = String::new();
+
+        for segment in &self.segments {
+            res.push('/');
+
+            match segment {
+                PathSegment::Field(name) => {
+                    res.push_str(&name.replace('~', "~0").replace('/', "~1"));
+                }
+                PathSegment::Index(i) => res.push_str(&i.to_string()),
+            }
+        }
+
+        res
+    }
+}
+
+impl Display for ErrorPath<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (index, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if index == 0 => f.write_str(name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(i) => write!(f, "[{}]", i)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Helper function.
+#[inline(never)]
+fn collect_leaves<'a>(
+    path: Vec<PathSegment<'a>>,
+    err: &'a Error,
+) -> Vec<(ErrorPath<'a>, &'a Error)> {
+    let mut res = Vec::new();
+    let mut stack = vec![(path, err)];
+
+    while let Some((path, err)) = stack.pop() {
+        match err {
+            Error::NamedFieldErrors(errors) => {
+                for err in errors.iter().rev() {
+                    let mut path = path.clone();
+
+                    path.push(PathSegment::Field(err.field()));
+
+                    stack.push((path, err.error()));
+                }
+            }
+            Error::UnnamedFieldErrors(errors) => {
+                for err in errors.iter().rev() {
+                    let mut path = path.clone();
+
+                    path.push(PathSegment::Index(err.field_index()));
+
+                    stack.push((path, err.error()));
+                }
+            }
+            Error::Multiple(errors) => {
+                for err in errors.iter().rev() {
+                    stack.push((path.clone(), err));
+                }
+            }
+            _ => res.push((ErrorPath { segments: path }, err)),
+        }
+    }
+
+    res
+}
+
+/// Helper function.
+#[inline(never)]
+fn fmt_leaves(f: &mut Formatter, leaves: &[(ErrorPath, &Error)]) -> fmt::Result {
+    if leaves.is_empty() {
+        return f.write_str("field errors");
+    }
+
+    for (index, (path, err)) in leaves.iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+
+        if path.is_empty() {
+            err.fmt_leaf(f)?;
+        } else {
+            write!(f, "{}: ", path)?;
+
+            err.fmt_leaf(f)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// List of errors.
 #[derive(Debug, Clone)]
 pub struct ErrorList<T> {
@@ -0,0 +1,623 @@
+//! Resource limits for untrusted input.
+//!
+//! Deeply nested or huge payloads can exhaust the stack or the memory of a
+//! process. `Limits` can be used to reject such payloads while building an
+//! `Intermediate` value:
+//!
+//! ```rust
+//! use serde_lite::{limits::Limits, Error};
+//!
+//! let limits = Limits::new()
+//!     .with_max_depth(16)
+//!     .with_max_string_length(1024);
+//!
+//! let mut deserializer = serde_json::Deserializer::from_str("[[[1]]]");
+//!
+//! let res = limits.deserialize(&mut deserializer);
+//!
+//! assert!(res.is_ok());
+//!
+//! let limits = limits.with_max_depth(2);
+//!
+//! let mut deserializer = serde_json::Deserializer::from_str("[[[1]]]");
+//!
+//! let res = limits.deserialize(&mut deserializer);
+//!
+//! assert!(matches!(res, Err(Error::DepthLimitExceeded)));
+//! ```
+//!
+//! Native codecs can enforce the limits using a `LimitTracker`.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Formatter},
+};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::{Error, Intermediate, Map, Number};
+
+/// Resource limits.
+///
+/// All limits are disabled by default.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Limits {
+    max_depth: usize,
+    max_nodes: usize,
+    max_string_length: usize,
+    max_array_length: usize,
+    max_map_size: usize,
+}
+
+impl Limits {
+    /// Create a new set of limits with all limits disabled.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_nodes: usize::MAX,
+            max_string_length: usize::MAX,
+            max_array_length: usize::MAX,
+            max_map_size: usize::MAX,
+        }
+    }
+
+    /// Set the maximum nesting depth of arrays and maps.
+    ///
+    /// A top-level array or map has depth 1.
+    #[inline]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the maximum total number of values (including all nested
+    /// values).
+    #[inline]
+    pub const fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Set the maximum length (in bytes) of strings, map keys and byte
+    /// strings.
+    #[inline]
+    pub const fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+
+    /// Set the maximum number of array elements.
+    #[inline]
+    pub const fn with_max_array_length(mut self, max_array_length: usize) -> Self {
+        self.max_array_length = max_array_length;
+        self
+    }
+
+    /// Set the maximum number of map entries.
+    #[inline]
+    pub const fn with_max_map_size(mut self, max_map_size: usize) -> Self {
+        self.max_map_size = max_map_size;
+        self
+    }
+
+    /// Get the maximum nesting depth.
+    #[inline]
+    pub const fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Get the maximum total number of values.
+    #[inline]
+    pub const fn max_nodes(&self) -> usize {
+        self.max_nodes
+    }
+
+    /// Get the maximum string length.
+    #[inline]
+    pub const fn max_string_length(&self) -> usize {
+        self.max_string_length
+    }
+
+    /// Get the maximum number of array elements.
+    #[inline]
+    pub const fn max_array_length(&self) -> usize {
+        self.max_array_length
+    }
+
+    /// Get the maximum number of map entries.
+    #[inline]
+    pub const fn max_map_size(&self) -> usize {
+        self.max_map_size
+    }
+
+    /// Build an intermediate value from a given serde deserializer while
+    /// enforcing the limits.
+    ///
+    /// Limit violations are reported using the corresponding `Error`
+    /// variants. Errors of the underlying deserializer are converted into
+    /// `Error::Custom`.
+    pub fn deserialize<'de, D>(&self, deserializer: D) -> Result<Intermediate<'de>, Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut context = Context {
+            tracker: LimitTracker::new(*self),
+            error: None,
+        };
+
+        let seed = ValueSeed {
+            context: &mut context,
+        };
+
+        seed.deserialize(deserializer)
+            .map_err(|err| context.error.take().unwrap_or_else(|| Error::custom(err)))
+    }
+
+    /// Check if a given intermediate value is within the limits.
+    #[inline(never)]
+    pub fn check(&self, val: &Intermediate) -> Result<(), Error> {
+        let mut tracker = LimitTracker::new(*self);
+
+        let mut stack = vec![(val, 0)];
+
+        while let Some((val, depth)) = stack.pop() {
+            tracker.node()?;
+
+            match val {
+                Intermediate::String(v) => tracker.check_string_length(v.len())?,
+                Intermediate::Bytes(v) => tracker.check_string_length(v.len())?,
+                Intermediate::Array(arr) => {
+                    if depth >= self.max_depth {
+                        return Err(Error::DepthLimitExceeded);
+                    }
+
+                    tracker.check_array_length(arr.len())?;
+
+                    stack.extend(arr.iter().map(|elem| (elem, depth + 1)));
+                }
+                Intermediate::Map(map) => {
+                    if depth >= self.max_depth {
+                        return Err(Error::DepthLimitExceeded);
+                    }
+
+                    tracker.check_map_size(map.len())?;
+
+                    for (k, v) in map {
+                        tracker.check_string_length(k.len())?;
+
+                        stack.push((v, depth + 1));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Helper for enforcing limits in codecs.
+///
+/// A codec is expected to call `node` for every value, `enter` and `leave`
+/// for every array or map and the `check_*` methods for strings, arrays and
+/// maps.
+#[derive(Debug, Clone)]
+pub struct LimitTracker {
+    limits: Limits,
+    depth: usize,
+    nodes: usize,
+}
+
+impl LimitTracker {
+    /// Create a new tracker for given limits.
+    #[inline]
+    pub const fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            nodes: 0,
+        }
+    }
+
+    /// Get the limits.
+    #[inline]
+    pub const fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Get the current depth.
+    #[inline]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Count a new value.
+    #[inline]
+    pub fn node(&mut self) -> Result<(), Error> {
+        if self.nodes >= self.limits.max_nodes {
+            return Err(Error::NodeLimitExceeded);
+        }
+
+        self.nodes += 1;
+
+        Ok(())
+    }
+
+    /// Enter an array or a map.
+    #[inline]
+    pub fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.limits.max_depth {
+            return Err(Error::DepthLimitExceeded);
+        }
+
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    /// Leave an array or a map.
+    #[inline]
+    pub fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Check a given string length.
+    #[inline]
+    pub fn check_string_length(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_string_length {
+            Err(Error::StringLengthLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check a given array length.
+    #[inline]
+    pub fn check_array_length(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_array_length {
+            Err(Error::ArrayLengthLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check a given map size.
+    #[inline]
+    pub fn check_map_size(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_map_size {
+            Err(Error::MapSizeLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Deserialization context.
+struct Context {
+    tracker: LimitTracker,
+    error: Option<Error>,
+}
+
+impl Context {
+    /// Convert a given limit violation into a deserializer error.
+    #[inline(never)]
+    fn check<E>(&mut self, res: Result<(), Error>) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        res.map_err(|err| {
+            let res = E::custom(&err);
+
+            self.error = Some(err);
+
+            res
+        })
+    }
+}
+
+/// Seed for deserializing intermediate values with limits.
+struct ValueSeed<'a> {
+    context: &'a mut Context,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_> {
+    type Value = Intermediate<'de>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let res = self.context.tracker.node();
+
+        self.context.check(res)?;
+
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_> {
+    type Value = Intermediate<'de>;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Intermediate::Bool(value))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::SignedInt(value)))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::UnsignedInt(value)))
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::from(value)))
+    }
+
+    #[inline]
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::from(value)))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Intermediate::Number(Number::Float(value)))
+    }
+
+    #[inline]
+    fn visit_char<E>(self, value: char) -> Result<Self::Value, E> {
+        Ok(Intermediate::String(Cow::Owned(value.to_string())))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::String(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // the length is checked before copying the string
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::String(Cow::Owned(String::from(value))))
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::String(Cow::Borrowed(value)))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::Bytes(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // the length is checked before copying the bytes
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::Bytes(Cow::Owned(value.to_vec())))
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Intermediate::Bytes(Cow::Borrowed(value)))
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Intermediate::None)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Intermediate::None)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let context = self.context;
+
+        let res = context.tracker.enter();
+
+        context.check(res)?;
+
+        let mut res = Vec::new();
+
+        if let Some(size) = seq.size_hint() {
+            res.reserve(cautious(size));
+        }
+
+        while let Some(elem) = seq.next_element_seed(ValueSeed {
+            context: &mut *context,
+        })? {
+            res.push(elem);
+
+            let check = context.tracker.check_array_length(res.len());
+
+            context.check(check)?;
+        }
+
+        context.tracker.leave();
+
+        Ok(Intermediate::Array(res))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let context = self.context;
+
+        let res = context.tracker.enter();
+
+        context.check(res)?;
+
+        let mut res = Map::new();
+
+        if let Some(size) = map.size_hint() {
+            res.reserve(cautious(size));
+        }
+
+        while let Some(k) = map.next_key_seed(KeySeed {
+            context: &mut *context,
+        })? {
+            let v = map.next_value_seed(ValueSeed {
+                context: &mut *context,
+            })?;
+
+            res.insert(k, v);
+
+            let check = context.tracker.check_map_size(res.len());
+
+            context.check(check)?;
+        }
+
+        context.tracker.leave();
+
+        Ok(Intermediate::Map(res))
+    }
+}
+
+/// Seed for deserializing map keys with limits.
+///
+/// The key length is checked before the key is copied.
+struct KeySeed<'a> {
+    context: &'a mut Context,
+}
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = Cow<'de, str>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed<'_> {
+    type Value = Cow<'de, str>;
+
+    #[inline]
+    fn expecting(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("a string")
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Cow::Borrowed(value))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Cow::Owned(String::from(value)))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let res = self.context.tracker.check_string_length(value.len());
+
+        self.context.check(res)?;
+
+        Ok(Cow::Owned(value))
+    }
+}
+
+/// Maximum number of elements preallocated based on a size hint.
+const MAX_PREALLOCATED: usize = 1024;
+
+/// Get the number of elements to be preallocated for a given size hint.
+///
+/// Size hints come from the input and they cannot be trusted, so the
+/// preallocation is capped. Containers grow as needed past the cap.
+#[inline]
+fn cautious(hint: usize) -> usize {
+    hint.min(MAX_PREALLOCATED)
+}
@@ -1,9 +1,10 @@
 use std::{
     borrow::Cow,
+    mem,
     ops::{Deref, DerefMut},
 };
 
-use crate::Intermediate;
+use crate::{intermediate, Intermediate};
 
 /// Type alias.
 #[cfg(feature = "preserve-order")]
@@ -13,16 +14,24 @@ pub type MapImpl<K, V> = indexmap::IndexMap<K, V>;
 #[cfg(not(feature = "preserve-order"))]
 pub type MapImpl<K, V> = std::collections::HashMap<K, V>;
 
+/// Map iterator.
+#[cfg(feature = "preserve-order")]
+pub(crate) type Iter<'a, 'b> = indexmap::map::Iter<'a, Cow<'b, str>, Intermediate<'b>>;
+
+/// Map iterator.
+#[cfg(not(feature = "preserve-order"))]
+pub(crate) type Iter<'a, 'b> = std::collections::hash_map::Iter<'a, Cow<'b, str>, Intermediate<'b>>;
+
 /// Map from string keys to `Intermediate` values.
 ///
 /// It wraps the underlying map implementation and prohibits inlining of some
 /// methods in order to make the generated code smaller.
-#[derive(Debug, Clone)]
-pub struct Map {
-    inner: MapImpl<Cow<'static, str>, Intermediate>,
+#[derive(Debug)]
+pub struct Map<'a> {
+    inner: MapImpl<Cow<'a, str>, Intermediate<'a>>,
 }
 
-impl Map {
+impl<'a> Map<'a> {
     /// Create a new map.
     #[inline(never)]
     pub fn new() -> Self {
@@ -41,46 +50,86 @@ impl Map {
 
     /// Get value associated with a given key.
     #[inline(never)]
-    pub fn get(&self, key: &str) -> Option<&Intermediate> {
+    pub fn get(&self, key: &str) -> Option<&Intermediate<'a>> {
         self.inner.get(key)
     }
 
     /// Insert a given key-value pair into the map.
     #[inline(never)]
-    pub fn insert_with_static_key(&mut self, key: &'static str, value: Intermediate) {
+    pub fn insert_with_static_key(&mut self, key: &'static str, value: Intermediate<'a>) {
         self.inner.insert(Cow::Borrowed(key), value);
     }
 
     /// Insert a given key-value pair into the map.
     #[inline(never)]
-    pub fn insert_with_owned_key(&mut self, key: String, value: Intermediate) {
+    pub fn insert_with_owned_key(&mut self, key: String, value: Intermediate<'a>) {
         self.inner.insert(Cow::Owned(key), value);
     }
+
+    /// Move all nested arrays and maps to a given stack.
+    #[inline]
+    pub(crate) fn take_children(&mut self, stack: &mut Vec<Intermediate<'a>>) {
+        let values = mem::take(&mut self.inner).into_values();
+
+        stack.extend(values.filter(Intermediate::has_children));
+    }
+}
+
+/// Convert a given map key into a key that does not borrow anything.
+#[inline]
+pub(crate) fn to_owned_key(key: &str) -> Cow<'static, str> {
+    Cow::Owned(String::from(key))
+}
+
+impl Clone for Map<'_> {
+    #[inline(never)]
+    fn clone(&self) -> Self {
+        let mut res = Self::with_capacity(self.len());
+
+        for (k, v) in self {
+            res.inner.insert(k.clone(), v.clone());
+        }
+
+        res
+    }
+}
+
+impl Drop for Map<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.inner.is_empty() {
+            let mut stack = Vec::new();
+
+            self.take_children(&mut stack);
+
+            intermediate::drop_all(stack);
+        }
+    }
 }
 
-impl Default for Map {
+impl Default for Map<'_> {
     #[inline]
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl From<MapImpl<Cow<'static, str>, Intermediate>> for Map {
+impl<'a> From<MapImpl<Cow<'a, str>, Intermediate<'a>>> for Map<'a> {
     #[inline]
-    fn from(map: MapImpl<Cow<'static, str>, Intermediate>) -> Self {
+    fn from(map: MapImpl<Cow<'a, str>, Intermediate<'a>>) -> Self {
         Self { inner: map }
     }
 }
 
-impl From<Map> for MapImpl<Cow<'static, str>, Intermediate> {
+impl<'a> From<Map<'a>> for MapImpl<Cow<'a, str>, Intermediate<'a>> {
     #[inline]
-    fn from(map: Map) -> Self {
-        map.inner
+    fn from(mut map: Map<'a>) -> Self {
+        mem::take(&mut map.inner)
     }
 }
 
-impl Deref for Map {
-    type Target = MapImpl<Cow<'static, str>, Intermediate>;
+impl<'a> Deref for Map<'a> {
+    type Target = MapImpl<Cow<'a, str>, Intermediate<'a>>;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
@@ -88,36 +137,32 @@ impl Deref for Map {
     }
 }
 
-impl DerefMut for Map {
+impl DerefMut for Map<'_> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
     }
 }
 
-impl IntoIterator for Map {
-    type Item = (Cow<'static, str>, Intermediate);
+impl<'a> IntoIterator for Map<'a> {
+    type Item = (Cow<'a, str>, Intermediate<'a>);
 
     #[cfg(feature = "preserve-order")]
-    type IntoIter = indexmap::map::IntoIter<Cow<'static, str>, Intermediate>;
+    type IntoIter = indexmap::map::IntoIter<Cow<'a, str>, Intermediate<'a>>;
 
     #[cfg(not(feature = "preserve-order"))]
-    type IntoIter = std::collections::hash_map::IntoIter<Cow<'static, str>, Intermediate>;
+    type IntoIter = std::collections::hash_map::IntoIter<Cow<'a, str>, Intermediate<'a>>;
 
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
+    fn into_iter(mut self) -> Self::IntoIter {
+        mem::take(&mut self.inner).into_iter()
     }
 }
 
-impl<'a> IntoIterator for &'a Map {
-    type Item = (&'a Cow<'static, str>, &'a Intermediate);
+impl<'a, 'b> IntoIterator for &'a Map<'b> {
+    type Item = (&'a Cow<'b, str>, &'a Intermediate<'b>);
 
-    #[cfg(feature = "preserve-order")]
-    type IntoIter = indexmap::map::Iter<'a, Cow<'static, str>, Intermediate>;
-
-    #[cfg(not(feature = "preserve-order"))]
-    type IntoIter = std::collections::hash_map::Iter<'a, Cow<'static, str>, Intermediate>;
+    type IntoIter = Iter<'a, 'b>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -0,0 +1,613 @@
+//! Simple regular expressions.
+//!
+//! `Pattern` implements a small subset of regular expressions that is
+//! sufficient for typical validation patterns (e.g. the `pattern` keyword
+//! of JSON Schema). The following constructs are supported:
+//!
+//! * literal characters and escaped special characters (e.g. `\.`)
+//! * any character (`.`)
+//! * character classes including ranges and negation (e.g. `[^a-z0-9_]`)
+//! * class escapes `\d`, `\D`, `\w`, `\W`, `\s` and `\S`
+//! * anchors `^` and `$`
+//! * groups with alternatives (e.g. `(a|bc)`, `(?:a|bc)`)
+//! * greedy quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`
+//!
+//! Like in JSON Schema, patterns are not anchored implicitly, i.e. a pattern
+//! matches a string if it matches any part of it:
+//!
+//! ```rust
+//! use serde_lite::pattern::Pattern;
+//!
+//! let pattern = Pattern::new(r"^[a-z]+(-[a-z0-9]+)*$").unwrap();
+//!
+//! assert!(pattern.is_match("serde-lite"));
+//! assert!(!pattern.is_match("Serde"));
+//!
+//! assert!(Pattern::new(r"\d{3}").unwrap().is_match("abc1234"));
+//! ```
+//!
+//! Patterns are compiled into a program for a Pike VM that follows all
+//! possible paths through the pattern at once. There is no backtracking, so
+//! the matching time is linear in the length of the input even for patterns
+//! like `^(a+)+$` and the matcher does not use recursion. The size of
+//! compiled patterns and the nesting of groups are limited, so patterns
+//! from untrusted sources (e.g. JSON schemas) can be compiled safely as
+//! well.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    mem,
+};
+
+use crate::Error;
+
+/// Maximum number of instructions of a compiled pattern.
+const MAX_PROGRAM_SIZE: usize = 10_000;
+
+/// Maximum nesting of groups.
+const MAX_NESTING: usize = 64;
+
+/// Compiled pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    program: Vec<Inst>,
+}
+
+impl Pattern {
+    /// Compile a given pattern.
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            input: pattern.chars().collect(),
+            pos: 0,
+            depth: 0,
+        };
+
+        let root = parser.parse_alternatives()?;
+
+        if parser.pos < parser.input.len() {
+            return Err(Error::custom_static(
+                "invalid pattern (unmatched parenthesis)",
+            ));
+        }
+
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+
+        compiler.compile_alternatives(&root)?;
+        compiler.push(Inst::Match)?;
+
+        let res = Self {
+            source: String::from(pattern),
+            program: compiler.program,
+        };
+
+        Ok(res)
+    }
+
+    /// Get the pattern source.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Check if the pattern matches any part of a given string.
+    pub fn is_match(&self, s: &str) -> bool {
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+
+        let mut stack = Vec::new();
+
+        let mut pos = 0;
+
+        loop {
+            let at_end = pos == s.len();
+
+            // start a new match attempt at every position
+            current.add(&self.program, 0, pos == 0, at_end, &mut stack);
+
+            let c = s[pos..].chars().next();
+
+            let next_pos = pos + c.map(char::len_utf8).unwrap_or(0);
+            let next_at_end = next_pos == s.len();
+
+            for &pc in &current.list {
+                let matched = match &self.program[pc] {
+                    Inst::Match => return true,
+                    Inst::Char(expected) => c == Some(*expected),
+                    Inst::Any => matches!(c, Some(c) if c != '\n'),
+                    Inst::Class(class) => matches!(c, Some(c) if class.contains(c)),
+                    _ => false,
+                };
+
+                if matched {
+                    next.add(&self.program, pc + 1, false, next_at_end, &mut stack);
+                }
+            }
+
+            if c.is_none() {
+                return false;
+            }
+
+            mem::swap(&mut current, &mut next);
+
+            next.clear();
+
+            pos = next_pos;
+        }
+    }
+}
+
+impl Display for Pattern {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Pattern node.
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Group(Vec<Vec<Node>>),
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+/// Character class.
+#[derive(Debug, Clone)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+impl Class {
+    /// Check if a given character belongs to the class.
+    fn contains(&self, c: char) -> bool {
+        self.items.iter().any(|item| item.contains(c)) != self.negated
+    }
+}
+
+/// Character class item.
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    /// Check if a given character matches the item.
+    fn contains(&self, c: char) -> bool {
+        match *self {
+            Self::Range(start, end) => start <= c && c <= end,
+            Self::Digit(negated) => c.is_ascii_digit() != negated,
+            Self::Word(negated) => (c.is_ascii_alphanumeric() || c == '_') != negated,
+            Self::Space(negated) => c.is_whitespace() != negated,
+        }
+    }
+}
+
+/// Pattern parser.
+struct Parser {
+    input: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// Parse alternatives until the end of the input or a closing
+    /// parenthesis.
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Node>>, Error> {
+        let mut res = vec![self.parse_sequence()?];
+
+        while self.peek() == Some('|') {
+            self.pos += 1;
+
+            res.push(self.parse_sequence()?);
+        }
+
+        Ok(res)
+    }
+
+    /// Parse a sequence of nodes until the end of the input, an alternative
+    /// separator or a closing parenthesis.
+    fn parse_sequence(&mut self) -> Result<Vec<Node>, Error> {
+        let mut res = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+
+            let node = self.parse_atom()?;
+            let node = self.parse_quantifier(node)?;
+
+            res.push(node);
+        }
+
+        Ok(res)
+    }
+
+    /// Parse a single atom.
+    fn parse_atom(&mut self) -> Result<Node, Error> {
+        let c = self.next().ok_or_else(|| invalid("unexpected end"))?;
+
+        let res = match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '[' => Node::Class(self.parse_class()?),
+            '(' => {
+                // non-capturing groups are the same as groups here
+                if self.peek() == Some('?') {
+                    self.pos += 1;
+
+                    if self.next() != Some(':') {
+                        return Err(invalid("unsupported group"));
+                    }
+                }
+
+                if self.depth >= MAX_NESTING {
+                    return Err(invalid("too deeply nested"));
+                }
+
+                self.depth += 1;
+
+                let alternatives = self.parse_alternatives()?;
+
+                self.depth -= 1;
+
+                if self.next() != Some(')') {
+                    return Err(invalid("unmatched parenthesis"));
+                }
+
+                Node::Group(alternatives)
+            }
+            '\\' => match self.parse_escape()? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                other => Node::Class(Class {
+                    negated: false,
+                    items: vec![other],
+                }),
+            },
+            '*' | '+' | '?' | '{' => return Err(invalid("nothing to repeat")),
+            c => Node::Char(c),
+        };
+
+        Ok(res)
+    }
+
+    /// Parse an optional quantifier of a given node.
+    fn parse_quantifier(&mut self, node: Node) -> Result<Node, Error> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+
+                let min = self.parse_number()?;
+
+                let max = match self.next() {
+                    Some('}') => Some(min),
+                    Some(',') if self.peek() == Some('}') => {
+                        self.pos += 1;
+
+                        None
+                    }
+                    Some(',') => {
+                        let max = self.parse_number()?;
+
+                        if self.next() != Some('}') || max < min {
+                            return Err(invalid("invalid quantifier"));
+                        }
+
+                        Some(max)
+                    }
+                    _ => return Err(invalid("invalid quantifier")),
+                };
+
+                return Ok(Node::Repeat(Box::new(node), min, max));
+            }
+            _ => return Ok(node),
+        };
+
+        self.pos += 1;
+
+        Ok(Node::Repeat(Box::new(node), min, max))
+    }
+
+    /// Parse a decimal number.
+    fn parse_number(&mut self) -> Result<usize, Error> {
+        let start = self.pos;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.input[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| invalid("invalid quantifier"))
+    }
+
+    /// Parse a character class (after the opening bracket).
+    fn parse_class(&mut self) -> Result<Class, Error> {
+        let negated = self.peek() == Some('^');
+
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            let c = self
+                .next()
+                .ok_or_else(|| invalid("unterminated character class"))?;
+
+            // a closing bracket at the beginning is a literal
+            if c == ']' && !items.is_empty() {
+                break;
+            }
+
+            let item = if c == '\\' {
+                self.parse_escape()?
+            } else {
+                ClassItem::Range(c, c)
+            };
+
+            let item = match item {
+                ClassItem::Range(start, _)
+                    if self.peek() == Some('-') && self.input.get(self.pos + 1) != Some(&']') =>
+                {
+                    self.pos += 1;
+
+                    let end = match self.next() {
+                        Some('\\') => self.parse_escape()?,
+                        Some(c) => ClassItem::Range(c, c),
+                        None => return Err(invalid("unterminated character class")),
+                    };
+
+                    match end {
+                        ClassItem::Range(end, _) if start <= end => ClassItem::Range(start, end),
+                        _ => return Err(invalid("invalid character range")),
+                    }
+                }
+                other => other,
+            };
+
+            items.push(item);
+        }
+
+        Ok(Class { negated, items })
+    }
+
+    /// Parse an escape sequence (after the backslash).
+    fn parse_escape(&mut self) -> Result<ClassItem, Error> {
+        let c = self.next().ok_or_else(|| invalid("unexpected end"))?;
+
+        let res = match c {
+            'd' => ClassItem::Digit(false),
+            'D' => ClassItem::Digit(true),
+            'w' => ClassItem::Word(false),
+            'W' => ClassItem::Word(true),
+            's' => ClassItem::Space(false),
+            'S' => ClassItem::Space(true),
+            'n' => ClassItem::Range('\n', '\n'),
+            'r' => ClassItem::Range('\r', '\r'),
+            't' => ClassItem::Range('\t', '\t'),
+            c if c.is_ascii_alphanumeric() => return Err(invalid("unsupported escape sequence")),
+            c => ClassItem::Range(c, c),
+        };
+
+        Ok(res)
+    }
+
+    /// Peek at the next character.
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.pos).copied()
+    }
+
+    /// Take the next character.
+    fn next(&mut self) -> Option<char> {
+        let res = self.peek();
+
+        if res.is_some() {
+            self.pos += 1;
+        }
+
+        res
+    }
+}
+
+/// Pike VM instruction.
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// Pattern compiler.
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    /// Append a given instruction and return its index.
+    fn push(&mut self, inst: Inst) -> Result<usize, Error> {
+        let index = self.program.len();
+
+        if index >= MAX_PROGRAM_SIZE {
+            return Err(invalid("pattern too large"));
+        }
+
+        self.program.push(inst);
+
+        Ok(index)
+    }
+
+    /// Compile given alternatives.
+    fn compile_alternatives(&mut self, alternatives: &[Vec<Node>]) -> Result<(), Error> {
+        let mut jumps = Vec::new();
+
+        let (last, rest) = alternatives
+            .split_last()
+            .ok_or_else(|| invalid("empty group"))?;
+
+        for sequence in rest {
+            let split = self.push(Inst::Split(0, 0))?;
+
+            self.compile_sequence(sequence)?;
+
+            jumps.push(self.push(Inst::Jump(0))?);
+
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+
+        self.compile_sequence(last)?;
+
+        let end = self.program.len();
+
+        for jump in jumps {
+            self.program[jump] = Inst::Jump(end);
+        }
+
+        Ok(())
+    }
+
+    /// Compile a given sequence of nodes.
+    fn compile_sequence(&mut self, nodes: &[Node]) -> Result<(), Error> {
+        for node in nodes {
+            self.compile_node(node)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compile a given node.
+    fn compile_node(&mut self, node: &Node) -> Result<(), Error> {
+        match node {
+            Node::Char(c) => self.push(Inst::Char(*c)).map(|_| ()),
+            Node::Any => self.push(Inst::Any).map(|_| ()),
+            Node::Class(class) => self.push(Inst::Class(class.clone())).map(|_| ()),
+            Node::Start => self.push(Inst::Start).map(|_| ()),
+            Node::End => self.push(Inst::End).map(|_| ()),
+            Node::Group(alternatives) => self.compile_alternatives(alternatives),
+            Node::Repeat(node, min, max) => self.compile_repeat(node, *min, *max),
+        }
+    }
+
+    /// Compile a repeated node.
+    fn compile_repeat(&mut self, node: &Node, min: usize, max: Option<usize>) -> Result<(), Error> {
+        let start = self.program.len();
+
+        for _ in 0..min {
+            self.compile_node(node)?;
+
+            // repeating an empty node is the same as the empty node
+            if self.program.len() == start {
+                return Ok(());
+            }
+        }
+
+        if let Some(max) = max {
+            let mut splits = Vec::new();
+
+            for _ in min..max {
+                splits.push(self.push(Inst::Split(0, 0))?);
+
+                self.compile_node(node)?;
+            }
+
+            let end = self.program.len();
+
+            for split in splits {
+                self.program[split] = Inst::Split(split + 1, end);
+            }
+        } else {
+            let split = self.push(Inst::Split(0, 0))?;
+
+            self.compile_node(node)?;
+            self.push(Inst::Jump(split))?;
+
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Set of Pike VM threads (i.e. instruction indices) at a given position.
+struct Threads {
+    list: Vec<usize>,
+    visited: Vec<usize>,
+    generation: usize,
+}
+
+impl Threads {
+    /// Create a new empty set for a program of a given size.
+    fn new(size: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            visited: vec![0; size],
+            generation: 1,
+        }
+    }
+
+    /// Add a thread at a given instruction and follow all jumps, splits and
+    /// assertions from there.
+    fn add(
+        &mut self,
+        program: &[Inst],
+        pc: usize,
+        at_start: bool,
+        at_end: bool,
+        stack: &mut Vec<usize>,
+    ) {
+        stack.push(pc);
+
+        while let Some(pc) = stack.pop() {
+            if mem::replace(&mut self.visited[pc], self.generation) == self.generation {
+                continue;
+            }
+
+            match program[pc] {
+                Inst::Jump(target) => stack.push(target),
+                Inst::Split(first, second) => {
+                    stack.push(second);
+                    stack.push(first);
+                }
+                Inst::Start if at_start => stack.push(pc + 1),
+                Inst::End if at_end => stack.push(pc + 1),
+                Inst::Start | Inst::End => (),
+                _ => self.list.push(pc),
+            }
+        }
+    }
+
+    /// Remove all threads.
+    fn clear(&mut self) {
+        self.list.clear();
+
+        self.generation += 1;
+    }
+}
+
+/// Create an invalid pattern error.
+fn invalid(msg: &str) -> Error {
+    Error::custom(format!("invalid pattern ({})", msg))
+}
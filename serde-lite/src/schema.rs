@@ -0,0 +1,990 @@
+//! JSON Schema generation.
+//!
+//! The functions in this module generate JSON Schema (draft 2020-12)
+//! documents from type descriptions provided by the `Describe` trait. The
+//! documents are returned in the intermediate representation, so they can be
+//! serialized using any supported format:
+//!
+//! ```rust
+//! use serde_json::json;
+//! use serde_lite::schema;
+//! use serde_lite_derive::Describe;
+//!
+//! #[derive(Describe)]
+//! struct Point {
+//!     x: i32,
+//!     #[serde(default)]
+//!     label: String,
+//! }
+//!
+//! let schema = schema::json_schema::<Point>();
+//!
+//! let expected = json!({
+//!     "$schema": "https://json-schema.org/draft/2020-12/schema",
+//!     "title": "Point",
+//!     "type": "object",
+//!     "properties": {
+//!         "x": {
+//!             "type": "integer",
+//!             "minimum": i32::MIN,
+//!             "maximum": i32::MAX,
+//!         },
+//!         "label": {
+//!             "type": "string",
+//!         },
+//!     },
+//!     "required": ["x"],
+//! });
+//!
+//! assert_eq!(serde_json::to_value(&schema).unwrap(), expected);
+//! ```
+//!
+//! The generated schema describes both the serialized values and the values
+//! accepted by deserialization. Fields that may be missing in either of them
+//! (i.e. fields with the `default`, `skip_serializing`, `skip_deserializing`
+//! or `skip_serializing_if` attribute) are not required. Fields skipped
+//! completely are omitted. Unknown fields are allowed because they are
+//! ignored by deserialization.
+//!
+//! Structs and enums other than the root type are placed into `$defs` and
+//! referenced using `$ref`, so recursive types are supported. Byte sequences
+//! are described as arrays of bytes because that is how they are written
+//! into JSON.
+//!
+//! # Validation
+//!
+//! `Validator` validates intermediate values against JSON Schema documents
+//! (e.g. before deserialization). All violations are collected into the
+//! usual tree of field errors (several violations at the same location are
+//! combined using `Error::Multiple`), so the path of every violation is
+//! available and it can be formatted as a JSON Pointer:
+//!
+//! ```rust
+//! use serde_lite::{intermediate, schema::Validator};
+//!
+//! let schema = intermediate!({
+//!     "type": "object",
+//!     "properties": {
+//!         "name": { "type": "string", "minLength": 1 },
+//!         "tags": { "type": "array", "items": { "type": "string" } },
+//!     },
+//!     "required": ["name"],
+//! });
+//!
+//! let value = intermediate!({
+//!     "name": "",
+//!     "tags": ["a", 1],
+//! });
+//!
+//! let err = Validator::new(&schema).validate(&value).unwrap_err();
+//!
+//! let mut violations = err
+//!     .leaves()
+//!     .into_iter()
+//!     .map(|(path, err)| format!("{}: {}", path.to_json_pointer(), err))
+//!     .collect::<Vec<_>>();
+//!
+//! violations.sort();
+//!
+//! assert_eq!(
+//!     violations,
+//!     [
+//!         "/name: string must be at least 1 characters long",
+//!         "/tags/1: invalid value (string expected)",
+//!     ]
+//! );
+//! ```
+//!
+//! The validator supports the following keywords: `type`, `enum`, `const`,
+//! `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`,
+//! `minLength`, `maxLength`, `pattern` (see the `pattern` module for the
+//! supported syntax), `items`, `prefixItems`, `minItems`, `maxItems`,
+//! `properties`, `required`, `additionalProperties`, `allOf`, `anyOf`,
+//! `oneOf` and `$ref` (local references only, e.g. `#/$defs/Name`). Other
+//! keywords are ignored.
+
+use std::{borrow::Cow, cell::RefCell, cmp::Ordering, collections::HashMap, convert::TryFrom};
+
+use crate::{
+    describe::{EnumRepr, Field, Fields, Shape, ShapeFn, Variant},
+    pattern::Pattern,
+    Describe, Error, ErrorList, Intermediate, Map, NamedFieldError, Number, UnnamedFieldError,
+};
+
+/// JSON Schema dialect of the generated documents.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Generate JSON Schema of a given type.
+pub fn json_schema<T>() -> Intermediate<'static>
+where
+    T: Describe + ?Sized,
+{
+    from_shape(T::describe())
+}
+
+/// Generate JSON Schema of a given shape.
+pub fn from_shape(shape: Shape) -> Intermediate<'static> {
+    Generator::new().generate(shape)
+}
+
+/// JSON Schema generator.
+struct Generator {
+    root: Option<&'static str>,
+    names: HashMap<&'static str, String>,
+    defs: Map<'static>,
+}
+
+impl Generator {
+    /// Create a new generator.
+    fn new() -> Self {
+        Self {
+            root: None,
+            names: HashMap::new(),
+            defs: Map::new(),
+        }
+    }
+
+    /// Generate the schema document for a given root shape.
+    fn generate(mut self, shape: Shape) -> Intermediate<'static> {
+        let mut res = Map::new();
+
+        res.insert_with_static_key("$schema", Intermediate::from(DIALECT));
+
+        let schema = if let Some(type_name) = shape.type_name() {
+            // the root name is reserved in order to avoid confusion
+            self.names.insert(type_name, self.def_name(&shape));
+            self.root = Some(type_name);
+            self.named_schema(shape)
+        } else {
+            self.schema(shape)
+        };
+
+        if let Ok(schema) = schema.into_map() {
+            res.extend(schema);
+        }
+
+        if !self.defs.is_empty() {
+            res.insert_with_static_key("$defs", Intermediate::Map(self.defs));
+        }
+
+        Intermediate::Map(res)
+    }
+
+    /// Generate schema of a given shape.
+    ///
+    /// Structs and enums are referenced.
+    fn schema(&mut self, shape: Shape) -> Intermediate<'static> {
+        match shape {
+            Shape::Any => Intermediate::Map(Map::new()),
+            Shape::Null => schema_type("null"),
+            Shape::Bool => schema_type("boolean"),
+            Shape::Integer { min, max } => {
+                let mut res = Map::with_capacity(3);
+
+                res.insert_with_static_key("type", Intermediate::from("integer"));
+                res.insert_with_static_key("minimum", Intermediate::from(min));
+                res.insert_with_static_key("maximum", Intermediate::from(max));
+
+                Intermediate::Map(res)
+            }
+            Shape::Float => schema_type("number"),
+            Shape::Char => {
+                let mut res = Map::with_capacity(3);
+
+                res.insert_with_static_key("type", Intermediate::from("string"));
+                res.insert_with_static_key("minLength", Intermediate::from(1));
+                res.insert_with_static_key("maxLength", Intermediate::from(1));
+
+                Intermediate::Map(res)
+            }
+            Shape::String => schema_type("string"),
+            Shape::Bytes => self.schema(Shape::Seq(u8::describe)),
+            Shape::Option(inner) => {
+                let inner = self.schema(inner());
+
+                let mut res = Map::with_capacity(1);
+
+                res.insert_with_static_key(
+                    "anyOf",
+                    Intermediate::Array(vec![inner, schema_type("null")]),
+                );
+
+                Intermediate::Map(res)
+            }
+            Shape::Seq(inner) => array_schema(self.schema(inner()), None),
+            Shape::Array(inner, len) => array_schema(self.schema(inner()), Some(len)),
+            Shape::Tuple(elements) => self.tuple_schema(&elements),
+            Shape::Map(inner) => {
+                let mut res = Map::with_capacity(2);
+
+                res.insert_with_static_key("type", Intermediate::from("object"));
+                res.insert_with_static_key("additionalProperties", self.schema(inner()));
+
+                Intermediate::Map(res)
+            }
+            Shape::Struct(_) | Shape::Enum(_) => self.reference(shape),
+        }
+    }
+
+    /// Get reference to a given struct or enum shape.
+    ///
+    /// The definition is generated if it does not exist yet.
+    fn reference(&mut self, shape: Shape) -> Intermediate<'static> {
+        // NOTE: only structs and enums can be referenced
+        let type_name = shape.type_name().unwrap_or_default();
+
+        let target = if self.root == Some(type_name) {
+            String::from("#")
+        } else if let Some(name) = self.names.get(type_name) {
+            format!("#/$defs/{}", name)
+        } else {
+            let name = self.def_name(&shape);
+
+            // the name must be registered before generating the definition
+            // in order to support recursive types
+            self.names.insert(type_name, name.clone());
+
+            let schema = self.named_schema(shape);
+
+            let target = format!("#/$defs/{}", name);
+
+            self.defs.insert_with_owned_key(name, schema);
+
+            target
+        };
+
+        let mut res = Map::with_capacity(1);
+
+        res.insert_with_static_key("$ref", Intermediate::String(Cow::Owned(target)));
+
+        Intermediate::Map(res)
+    }
+
+    /// Get an unused definition name for a given struct or enum shape.
+    fn def_name(&self, shape: &Shape) -> String {
+        let name = shape.name().unwrap_or("Type");
+
+        let mut res = String::from(name);
+        let mut index = 1;
+
+        // different types may have the same name (e.g. generic types)
+        while self.names.values().any(|n| *n == res) {
+            index += 1;
+            res = format!("{}{}", name, index);
+        }
+
+        res
+    }
+
+    /// Generate schema of a given struct or enum shape.
+    fn named_schema(&mut self, shape: Shape) -> Intermediate<'static> {
+        let (name, schema) = match shape {
+            Shape::Struct(s) => (s.name(), self.fields_schema(s.fields())),
+            Shape::Enum(e) => {
+                let variants = e
+                    .variants()
+                    .iter()
+                    .map(|variant| self.variant_schema(variant, e.repr()))
+                    .collect();
+
+                let mut res = Map::with_capacity(1);
+
+                res.insert_with_static_key("oneOf", Intermediate::Array(variants));
+
+                (e.name(), Intermediate::Map(res))
+            }
+            other => return self.schema(other),
+        };
+
+        let mut res = Map::new();
+
+        res.insert_with_static_key("title", Intermediate::from(name));
+
+        if let Ok(schema) = schema.into_map() {
+            res.extend(schema);
+        }
+
+        Intermediate::Map(res)
+    }
+
+    /// Generate schema of a given enum variant.
+    fn variant_schema(&mut self, variant: &Variant, repr: EnumRepr) -> Intermediate<'static> {
+        let name = variant.name();
+        let fields = variant.fields();
+
+        match repr {
+            EnumRepr::External if is_unit(fields) => const_schema(name),
+            EnumRepr::External => {
+                let mut res = ObjectSchema::new();
+
+                res.properties
+                    .insert_with_static_key(name, self.fields_schema(fields));
+                res.required.push(Intermediate::from(name));
+
+                res.into_schema()
+            }
+            EnumRepr::Internal { tag } => {
+                let mut res = ObjectSchema::new();
+
+                res.properties
+                    .insert_with_static_key(tag, const_schema(name));
+                res.required.push(Intermediate::from(tag));
+
+                match fields {
+                    Fields::Named(fields) => self.add_fields(&mut res, fields),
+                    _ if is_unit(fields) => (),
+                    _ => res.all_of.push(self.fields_schema(fields)),
+                }
+
+                res.into_schema()
+            }
+            EnumRepr::Adjacent { tag, content } => {
+                let mut res = ObjectSchema::new();
+
+                res.properties
+                    .insert_with_static_key(tag, const_schema(name));
+                res.required.push(Intermediate::from(tag));
+
+                res.properties
+                    .insert_with_static_key(content, self.fields_schema(fields));
+
+                if !is_unit(fields) {
+                    res.required.push(Intermediate::from(content));
+                }
+
+                res.into_schema()
+            }
+        }
+    }
+
+    /// Generate schema of given struct or enum variant fields.
+    fn fields_schema(&mut self, fields: &Fields) -> Intermediate<'static> {
+        match fields {
+            Fields::Unit => schema_type("null"),
+            Fields::Unnamed(fields) => match fields.len() {
+                0 => schema_type("null"),
+                1 => self.schema(fields[0]()),
+                _ => self.tuple_schema(fields),
+            },
+            Fields::Named(fields) => {
+                let mut res = ObjectSchema::new();
+
+                self.add_fields(&mut res, fields);
+
+                res.into_schema()
+            }
+        }
+    }
+
+    /// Add given named fields into a given object schema.
+    fn add_fields(&mut self, object: &mut ObjectSchema, fields: &[Field]) {
+        for field in fields {
+            if field.is_skipped() {
+                continue;
+            }
+
+            if field.is_flattened() {
+                match field.shape() {
+                    Shape::Struct(s) => match s.fields() {
+                        Fields::Named(fields) => self.add_fields(object, fields),
+                        other => object.all_of.push(self.fields_schema(other)),
+                    },
+                    Shape::Map(inner) => object.additional = Some(self.schema(inner())),
+                    other => object.all_of.push(self.schema(other)),
+                }
+            } else {
+                let name = field.name();
+
+                object
+                    .properties
+                    .insert_with_static_key(name, self.schema(field.shape()));
+
+                let optional =
+                    field.is_optional() || field.skip_serializing() || field.skip_serializing_if();
+
+                if !optional {
+                    object.required.push(Intermediate::from(name));
+                }
+            }
+        }
+    }
+
+    /// Generate schema of a tuple with given element shapes.
+    fn tuple_schema(&mut self, elements: &[ShapeFn]) -> Intermediate<'static> {
+        let len = elements.len();
+
+        let elements = elements
+            .iter()
+            .map(|element| self.schema(element()))
+            .collect();
+
+        let mut res = Map::with_capacity(4);
+
+        res.insert_with_static_key("type", Intermediate::from("array"));
+        res.insert_with_static_key("prefixItems", Intermediate::Array(elements));
+        res.insert_with_static_key("minItems", Intermediate::from(len as u64));
+        res.insert_with_static_key("maxItems", Intermediate::from(len as u64));
+
+        Intermediate::Map(res)
+    }
+}
+
+/// Object schema builder.
+struct ObjectSchema {
+    properties: Map<'static>,
+    required: Vec<Intermediate<'static>>,
+    additional: Option<Intermediate<'static>>,
+    all_of: Vec<Intermediate<'static>>,
+}
+
+impl ObjectSchema {
+    /// Create a new object schema builder.
+    fn new() -> Self {
+        Self {
+            properties: Map::new(),
+            required: Vec::new(),
+            additional: None,
+            all_of: Vec::new(),
+        }
+    }
+
+    /// Build the schema.
+    fn into_schema(self) -> Intermediate<'static> {
+        let mut res = Map::with_capacity(5);
+
+        res.insert_with_static_key("type", Intermediate::from("object"));
+        res.insert_with_static_key("properties", Intermediate::Map(self.properties));
+
+        if !self.required.is_empty() {
+            res.insert_with_static_key("required", Intermediate::Array(self.required));
+        }
+
+        if let Some(additional) = self.additional {
+            res.insert_with_static_key("additionalProperties", additional);
+        }
+
+        if !self.all_of.is_empty() {
+            res.insert_with_static_key("allOf", Intermediate::Array(self.all_of));
+        }
+
+        Intermediate::Map(res)
+    }
+}
+
+/// Check if given fields are serialized as null.
+fn is_unit(fields: &Fields) -> bool {
+    match fields {
+        Fields::Unit => true,
+        Fields::Unnamed(fields) => fields.is_empty(),
+        Fields::Named(_) => false,
+    }
+}
+
+/// Create schema with a given type.
+fn schema_type(ty: &'static str) -> Intermediate<'static> {
+    let mut res = Map::with_capacity(1);
+
+    res.insert_with_static_key("type", Intermediate::from(ty));
+
+    Intermediate::Map(res)
+}
+
+/// Create schema matching a given string constant.
+fn const_schema(value: &'static str) -> Intermediate<'static> {
+    let mut res = Map::with_capacity(1);
+
+    res.insert_with_static_key("const", Intermediate::from(value));
+
+    Intermediate::Map(res)
+}
+
+/// Create array schema with a given item schema and an optional fixed
+/// length.
+fn array_schema(items: Intermediate<'static>, len: Option<usize>) -> Intermediate<'static> {
+    let mut res = Map::with_capacity(4);
+
+    res.insert_with_static_key("type", Intermediate::from("array"));
+    res.insert_with_static_key("items", items);
+
+    if let Some(len) = len {
+        res.insert_with_static_key("minItems", Intermediate::from(len as u64));
+        res.insert_with_static_key("maxItems", Intermediate::from(len as u64));
+    }
+
+    Intermediate::Map(res)
+}
+
+/// Maximum nesting depth of evaluated schemas.
+const MAX_DEPTH: usize = 256;
+
+/// JSON Schema validator.
+///
+/// Patterns used by the schema are compiled on first use and cached, so the
+/// same validator should be reused for validating many values.
+pub struct Validator<'a> {
+    root: &'a Intermediate<'a>,
+    patterns: RefCell<HashMap<String, Result<Pattern, Error>>>,
+}
+
+impl<'a> Validator<'a> {
+    /// Create a new validator for a given schema document.
+    #[inline]
+    pub fn new(schema: &'a Intermediate<'a>) -> Self {
+        Self {
+            root: schema,
+            patterns: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Validate a given value.
+    ///
+    /// All violations are returned as field errors. Violations of the value
+    /// itself are returned as a single error or combined with the field
+    /// errors using `Error::Multiple`.
+    pub fn validate(&self, value: &Intermediate) -> Result<(), Error> {
+        let mut violations = Violations::new();
+
+        self.check(self.root, value, 0, &mut violations);
+
+        violations.into_result()
+    }
+
+    /// Check a given value against a given schema.
+    fn check(
+        &self,
+        schema: &Intermediate,
+        value: &Intermediate,
+        depth: usize,
+        out: &mut Violations,
+    ) {
+        if depth > MAX_DEPTH {
+            return out.push(Error::DepthLimitExceeded);
+        }
+
+        let schema = match schema {
+            Intermediate::Bool(true) => return,
+            Intermediate::Bool(false) => {
+                return out.push(Error::custom_static("value is not allowed"));
+            }
+            Intermediate::Map(schema) => schema,
+            _ => return out.push(Error::custom_static("invalid schema")),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Intermediate::as_str) {
+            match self.resolve(reference) {
+                Some(target) => self.check(target, value, depth + 1, out),
+                None => out.push(Error::custom(format!(
+                    "invalid schema reference ({})",
+                    reference
+                ))),
+            }
+        }
+
+        if let Some(ty) = schema.get("type") {
+            check_type(ty, value, out);
+        }
+
+        if let Some(Intermediate::Array(allowed)) = schema.get("enum") {
+            if !allowed.iter().any(|v| equal(v, value)) {
+                out.push(Error::custom_static(
+                    "value is not one of the allowed values",
+                ));
+            }
+        }
+
+        if let Some(expected) = schema.get("const") {
+            if !equal(expected, value) {
+                out.push(Error::custom_static("value does not match the constant"));
+            }
+        }
+
+        match value {
+            Intermediate::Number(n) => check_number(schema, *n, out),
+            Intermediate::String(s) => self.check_string(schema, s, out),
+            Intermediate::Array(elements) => self.check_array(schema, elements, depth, out),
+            Intermediate::Bytes(bytes) => {
+                let elements = bytes
+                    .iter()
+                    .map(|&b| Intermediate::from(b))
+                    .collect::<Vec<_>>();
+
+                self.check_array(schema, &elements, depth, out);
+            }
+            Intermediate::Map(map) => self.check_map(schema, map, depth, out),
+            _ => (),
+        }
+
+        if let Some(Intermediate::Array(schemas)) = schema.get("allOf") {
+            for schema in schemas {
+                self.check(schema, value, depth + 1, out);
+            }
+        }
+
+        if let Some(Intermediate::Array(schemas)) = schema.get("anyOf") {
+            if !schemas.iter().any(|s| self.is_valid(s, value, depth)) {
+                out.push(Error::custom_static(
+                    "value does not match any of the allowed schemas",
+                ));
+            }
+        }
+
+        if let Some(Intermediate::Array(schemas)) = schema.get("oneOf") {
+            let matched = schemas
+                .iter()
+                .filter(|s| self.is_valid(s, value, depth))
+                .count();
+
+            if matched == 0 {
+                out.push(Error::custom_static(
+                    "value does not match any of the allowed schemas",
+                ));
+            } else if matched > 1 {
+                out.push(Error::custom(format!(
+                    "value matches {} schemas but exactly one is allowed",
+                    matched
+                )));
+            }
+        }
+    }
+
+    /// Check if a given value is valid against a given subschema.
+    fn is_valid(&self, schema: &Intermediate, value: &Intermediate, depth: usize) -> bool {
+        let mut violations = Violations::new();
+
+        self.check(schema, value, depth + 1, &mut violations);
+
+        violations.is_empty()
+    }
+
+    /// Check array keywords.
+    fn check_array(
+        &self,
+        schema: &Map,
+        elements: &[Intermediate],
+        depth: usize,
+        out: &mut Violations,
+    ) {
+        if let Some(min) = get_count(schema, "minItems") {
+            if elements.len() < min {
+                out.push(Error::custom(format!(
+                    "array must contain at least {} items",
+                    min
+                )));
+            }
+        }
+
+        if let Some(max) = get_count(schema, "maxItems") {
+            if elements.len() > max {
+                out.push(Error::custom(format!(
+                    "array must contain at most {} items",
+                    max
+                )));
+            }
+        }
+
+        let mut prefix_len = 0;
+
+        if let Some(Intermediate::Array(schemas)) = schema.get("prefixItems") {
+            prefix_len = schemas.len();
+
+            for (index, (schema, element)) in schemas.iter().zip(elements).enumerate() {
+                let mut violations = Violations::new();
+
+                self.check(schema, element, depth + 1, &mut violations);
+
+                out.push_index(index, violations);
+            }
+        }
+
+        if let Some(schema) = schema.get("items") {
+            for (index, element) in elements.iter().enumerate().skip(prefix_len) {
+                let mut violations = Violations::new();
+
+                self.check(schema, element, depth + 1, &mut violations);
+
+                out.push_index(index, violations);
+            }
+        }
+    }
+
+    /// Check object keywords.
+    fn check_map(&self, schema: &Map, map: &Map, depth: usize, out: &mut Violations) {
+        if let Some(Intermediate::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Intermediate::as_str) {
+                if map.get(name).is_none() {
+                    out.push_field(name, Violations::from(Error::MissingField));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Intermediate::as_map);
+        let additional = schema.get("additionalProperties");
+
+        if properties.is_none() && additional.is_none() {
+            return;
+        }
+
+        for (name, value) in map {
+            let property = properties.and_then(|properties| properties.get(name));
+
+            if let Some(schema) = property.or(additional) {
+                let mut violations = Violations::new();
+
+                if property.is_none() && matches!(schema, Intermediate::Bool(false)) {
+                    violations.push(Error::custom_static("unknown field"));
+                } else {
+                    self.check(schema, value, depth + 1, &mut violations);
+                }
+
+                out.push_field(name, violations);
+            }
+        }
+    }
+
+    /// Check string keywords.
+    fn check_string(&self, schema: &Map, s: &str, out: &mut Violations) {
+        let len = s.chars().count();
+
+        if let Some(min) = get_count(schema, "minLength") {
+            if len < min {
+                out.push(Error::custom(format!(
+                    "string must be at least {} characters long",
+                    min
+                )));
+            }
+        }
+
+        if let Some(max) = get_count(schema, "maxLength") {
+            if len > max {
+                out.push(Error::custom(format!(
+                    "string must be at most {} characters long",
+                    max
+                )));
+            }
+        }
+
+        if let Some(pattern) = schema.get("pattern").and_then(Intermediate::as_str) {
+            let mut patterns = self.patterns.borrow_mut();
+
+            let compiled = patterns
+                .entry(String::from(pattern))
+                .or_insert_with(|| Pattern::new(pattern));
+
+            match compiled {
+                Ok(p) if p.is_match(s) => (),
+                Ok(_) => out.push(Error::custom(format!(
+                    "string does not match the pattern {}",
+                    pattern
+                ))),
+                Err(err) => out.push(err.clone()),
+            }
+        }
+    }
+
+    /// Resolve a given local reference.
+    fn resolve(&self, reference: &str) -> Option<&'a Intermediate<'a>> {
+        let pointer = reference.strip_prefix('#')?;
+
+        if pointer.is_empty() {
+            return Some(self.root);
+        }
+
+        let pointer = pointer.strip_prefix('/')?;
+
+        let mut current = self.root;
+
+        for token in pointer.split('/') {
+            let token = token.replace("~1", "/").replace("~0", "~");
+
+            current = match current {
+                Intermediate::Map(map) => map.get(&token)?,
+                Intermediate::Array(elements) => elements.get(token.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+
+        Some(current)
+    }
+}
+
+/// Violations found at a single location.
+struct Violations {
+    leaves: Vec<Error>,
+    named: ErrorList<NamedFieldError>,
+    unnamed: ErrorList<UnnamedFieldError>,
+}
+
+impl Violations {
+    /// Create an empty set of violations.
+    fn new() -> Self {
+        Self {
+            leaves: Vec::new(),
+            named: ErrorList::new(),
+            unnamed: ErrorList::new(),
+        }
+    }
+
+    /// Check if there are no violations.
+    fn is_empty(&self) -> bool {
+        self.leaves.is_empty() && self.named.is_empty() && self.unnamed.is_empty()
+    }
+
+    /// Add a given violation.
+    fn push(&mut self, err: Error) {
+        self.leaves.push(err);
+    }
+
+    /// Add violations of a given field.
+    fn push_field(&mut self, field: &str, violations: Violations) {
+        for err in violations.into_errors() {
+            self.named.push(NamedFieldError::new(field, err));
+        }
+    }
+
+    /// Add violations of a given array element.
+    fn push_index(&mut self, index: usize, violations: Violations) {
+        for err in violations.into_errors() {
+            self.unnamed.push(UnnamedFieldError::new(index, err));
+        }
+    }
+
+    /// Get all violations as a list of errors.
+    ///
+    /// Every violation at this location is a separate error. Violations
+    /// of nested fields are grouped into a single field error.
+    fn into_errors(self) -> Vec<Error> {
+        let mut res = self.leaves;
+
+        if !self.named.is_empty() {
+            res.push(Error::NamedFieldErrors(self.named));
+        }
+
+        if !self.unnamed.is_empty() {
+            res.push(Error::UnnamedFieldErrors(self.unnamed));
+        }
+
+        res
+    }
+
+    /// Convert the violations into a result.
+    fn into_result(self) -> Result<(), Error> {
+        let mut errors = self.into_errors();
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+}
+
+impl From<Error> for Violations {
+    #[inline]
+    fn from(err: Error) -> Self {
+        let mut res = Self::new();
+
+        res.push(err);
+        res
+    }
+}
+
+/// Check the type keyword.
+fn check_type(ty: &Intermediate, value: &Intermediate, out: &mut Violations) {
+    let types = match ty {
+        Intermediate::String(ty) => vec![&**ty],
+        Intermediate::Array(types) => types.iter().filter_map(Intermediate::as_str).collect(),
+        _ => return out.push(Error::custom_static("invalid schema")),
+    };
+
+    let matches = types.iter().any(|&ty| match ty {
+        "null" => value.is_none(),
+        "boolean" => matches!(value, Intermediate::Bool(_)),
+        "integer" => matches!(value, Intermediate::Number(n) if is_integer(*n)),
+        "number" => matches!(value, Intermediate::Number(_)),
+        "string" => matches!(value, Intermediate::String(_)),
+        "array" => matches!(value, Intermediate::Array(_) | Intermediate::Bytes(_)),
+        "object" => matches!(value, Intermediate::Map(_)),
+        _ => false,
+    });
+
+    if !matches {
+        out.push(Error::invalid_value(types.join(" or ")));
+    }
+}
+
+/// Numeric bound keyword, its description and a comparison check.
+type Bound = (&'static str, &'static str, fn(Ordering) -> bool);
+
+/// Check numeric keywords.
+fn check_number(schema: &Map, n: Number, out: &mut Violations) {
+    let bounds: [Bound; 4] = [
+        ("minimum", "greater than or equal to", Ordering::is_ge),
+        ("exclusiveMinimum", "greater than", Ordering::is_gt),
+        ("maximum", "less than or equal to", Ordering::is_le),
+        ("exclusiveMaximum", "less than", Ordering::is_lt),
+    ];
+
+    for (keyword, description, valid) in bounds {
+        let Some(bound) = schema.get(keyword).and_then(Intermediate::as_number) else {
+            continue;
+        };
+
+        if !compare_numbers(n, bound).map(valid).unwrap_or(false) {
+            out.push(Error::custom(format!(
+                "value must be {} {}",
+                description,
+                f64::from(bound)
+            )));
+        }
+    }
+}
+
+/// Get a non-negative integer value of a given keyword.
+fn get_count(schema: &Map, keyword: &str) -> Option<usize> {
+    schema
+        .get(keyword)
+        .and_then(Intermediate::as_number)
+        .and_then(|n| u64::try_from(n).ok())
+        .and_then(|n| usize::try_from(n).ok())
+}
+
+/// Check if a given number is an integer.
+fn is_integer(n: Number) -> bool {
+    match n {
+        Number::Float(v) => v.is_finite() && v.fract() == 0.0,
+        _ => true,
+    }
+}
+
+/// Compare given numbers.
+fn compare_numbers(a: Number, b: Number) -> Option<Ordering> {
+    if let (Ok(a), Ok(b)) = (i128::try_from(a), i128::try_from(b)) {
+        Some(a.cmp(&b))
+    } else if let (Ok(a), Ok(b)) = (u128::try_from(a), u128::try_from(b)) {
+        Some(a.cmp(&b))
+    } else {
+        f64::from(a).partial_cmp(&f64::from(b))
+    }
+}
+
+/// Check if given values are equal.
+fn equal(a: &Intermediate, b: &Intermediate) -> bool {
+    match (a, b) {
+        (Intermediate::None, Intermediate::None) => true,
+        (Intermediate::Bool(a), Intermediate::Bool(b)) => a == b,
+        (Intermediate::Number(a), Intermediate::Number(b)) => {
+            compare_numbers(*a, *b) == Some(Ordering::Equal)
+        }
+        (Intermediate::String(a), Intermediate::String(b)) => a == b,
+        (Intermediate::Bytes(a), Intermediate::Bytes(b)) => a == b,
+        (Intermediate::Array(a), Intermediate::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b))
+        }
+        (Intermediate::Map(a), Intermediate::Map(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(k, v)| b.get(k).map(|other| equal(v, other)).unwrap_or(false))
+        }
+        _ => false,
+    }
+}
@@ -7,7 +7,10 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use crate::{Error, Intermediate, Map, Number};
+use crate::{
+    emit::{self, Emitter},
+    Error, ErrorList, Intermediate, Map, NamedFieldError, Number, UnnamedFieldError,
+};
 
 /// Serialize trait.
 ///
@@ -15,40 +18,50 @@ use crate::{Error, Intermediate, Map, Number};
 /// intermediate representation.
 pub trait Serialize {
     /// Serialize the object.
-    fn serialize(&self) -> Result<Intermediate, Error>;
+    fn serialize(&self) -> Result<Intermediate<'_>, Error>;
+
+    /// Serialize the object into a given emitter.
+    ///
+    /// The default implementation serializes the object into the
+    /// intermediate representation at first. Unlike `serialize`, the method
+    /// stops at the first error.
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        emit::emit(&self.serialize()?, emitter)
+    }
 }
 
 impl Serialize for bool {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Bool(*self))
     }
 }
 
 impl Serialize for i64 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Number(Number::SignedInt(*self)))
     }
 }
 
 impl Serialize for u64 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Number(Number::UnsignedInt(*self)))
     }
 }
 
 impl Serialize for f32 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Number(Number::Float(*self as _)))
     }
 }
 
 impl Serialize for f64 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Number(Number::Float(*self)))
     }
 }
@@ -57,7 +70,7 @@ macro_rules! serialize_for_signed_int {
     ( $x:ty ) => {
         impl Serialize for $x {
             #[inline]
-            fn serialize(&self) -> Result<Intermediate, Error> {
+            fn serialize(&self) -> Result<Intermediate<'_>, Error> {
                 Ok(Intermediate::Number(Number::SignedInt(i64::from(*self))))
             }
         }
@@ -68,7 +81,7 @@ macro_rules! serialize_for_unsigned_int {
     ( $x:ty ) => {
         impl Serialize for $x {
             #[inline]
-            fn serialize(&self) -> Result<Intermediate, Error> {
+            fn serialize(&self) -> Result<Intermediate<'_>, Error> {
                 Ok(Intermediate::Number(Number::UnsignedInt(u64::from(*self))))
             }
         }
@@ -85,25 +98,21 @@ serialize_for_unsigned_int!(u32);
 
 impl Serialize for i128 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        i64::try_from(*self)
-            .map(|v| Intermediate::Number(Number::SignedInt(v)))
-            .map_err(|_| Error::OutOfBounds)
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        Ok(Intermediate::Number(Number::from(*self)))
     }
 }
 
 impl Serialize for u128 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        u64::try_from(*self)
-            .map(|v| Intermediate::Number(Number::UnsignedInt(v)))
-            .map_err(|_| Error::OutOfBounds)
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        Ok(Intermediate::Number(Number::from(*self)))
     }
 }
 
 impl Serialize for isize {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         i64::try_from(*self)
             .map(|v| Intermediate::Number(Number::SignedInt(v)))
             .map_err(|_| Error::OutOfBounds)
@@ -112,7 +121,7 @@ impl Serialize for isize {
 
 impl Serialize for usize {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         u64::try_from(*self)
             .map(|v| Intermediate::Number(Number::UnsignedInt(v)))
             .map_err(|_| Error::OutOfBounds)
@@ -121,22 +130,22 @@ impl Serialize for usize {
 
 impl Serialize for char {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::String(Cow::Owned(self.to_string())))
     }
 }
 
 impl Serialize for String {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        Ok(Intermediate::String(Cow::Owned(self.clone())))
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        Ok(Intermediate::String(Cow::Borrowed(self)))
     }
 }
 
 impl Serialize for &str {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        Ok(Intermediate::String(Cow::Owned(String::from(*self))))
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        Ok(Intermediate::String(Cow::Borrowed(self)))
     }
 }
 
@@ -145,13 +154,22 @@ where
     T: Serialize,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         if let Some(inner) = self.as_ref() {
             inner.serialize()
         } else {
             Ok(Intermediate::None)
         }
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        if let Some(inner) = self.as_ref() {
+            inner.serialize_to(emitter)
+        } else {
+            emitter.scalar(emit::Scalar::None)
+        }
+    }
 }
 
 impl<T> Serialize for &[T]
@@ -159,9 +177,14 @@ where
     T: Serialize,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         serialize_slice(self)
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        serialize_slice_to(self, emitter)
+    }
 }
 
 impl<T> Serialize for &mut [T]
@@ -169,9 +192,14 @@ where
     T: Serialize,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         serialize_slice(self)
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        serialize_slice_to(self, emitter)
+    }
 }
 
 impl<T> Serialize for Vec<T>
@@ -179,14 +207,19 @@ where
     T: Serialize,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         serialize_slice(self)
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        serialize_slice_to(self, emitter)
+    }
 }
 
 impl<T> Serialize for [T; 0] {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Array(Vec::new()))
     }
 }
@@ -198,9 +231,14 @@ macro_rules! serialize_array {
             T: Serialize,
         {
             #[inline]
-            fn serialize(&self) -> Result<Intermediate, Error> {
+            fn serialize(&self) -> Result<Intermediate<'_>, Error> {
                 serialize_slice(&self[..])
             }
+
+            #[inline]
+            fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+                serialize_slice_to(&self[..], emitter)
+            }
         }
     };
 }
@@ -240,7 +278,7 @@ serialize_array!(32);
 
 impl Serialize for () {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         Ok(Intermediate::Array(Vec::new()))
     }
 }
@@ -251,14 +289,34 @@ macro_rules! serialize_tuple {
         where
             $($ty: Serialize,)+
         {
-            fn serialize(&self) -> Result<Intermediate, Error> {
-                let res = vec![
-                    $(
-                        self.$n.serialize()?,
-                    )+
-                ];
-
-                Ok(Intermediate::Array(res))
+            fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+                let mut res = Vec::with_capacity($len);
+                let mut errors = ErrorList::new();
+
+                $(
+                    match self.$n.serialize() {
+                        Ok(v) => res.push(v),
+                        Err(err) => errors.push(UnnamedFieldError::new($n, err)),
+                    }
+                )+
+
+                if errors.is_empty() {
+                    Ok(Intermediate::Array(res))
+                } else {
+                    Err(Error::UnnamedFieldErrors(errors))
+                }
+            }
+
+            fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+                emitter.begin_array(Some($len))?;
+
+                $(
+                    self.$n
+                        .serialize_to(emitter)
+                        .map_err(|err| UnnamedFieldError::new($n, err))?;
+                )+
+
+                emitter.end_array()
             }
         }
     };
@@ -286,14 +344,14 @@ where
     K: ToString,
     V: Serialize,
 {
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        let mut res = Map::with_capacity(self.len());
-
-        for (k, v) in self.iter() {
-            res.insert_with_owned_key(k.to_string(), v.serialize()?);
-        }
+    #[inline]
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        serialize_map(self.len(), self.iter())
+    }
 
-        Ok(Intermediate::Map(res))
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        serialize_map_to(self.len(), self.iter(), emitter)
     }
 }
 
@@ -303,14 +361,14 @@ where
     K: ToString,
     V: Serialize,
 {
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        let mut res = Map::with_capacity(self.len());
-
-        for (k, v) in self.iter() {
-            res.insert_with_owned_key(k.to_string(), v.serialize()?);
-        }
+    #[inline]
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        serialize_map(self.len(), self.iter())
+    }
 
-        Ok(Intermediate::Map(res))
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        serialize_map_to(self.len(), self.iter(), emitter)
     }
 }
 
@@ -319,9 +377,14 @@ where
     T: Serialize + ?Sized,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         <T as Serialize>::serialize(self)
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        <T as Serialize>::serialize_to(self, emitter)
+    }
 }
 
 impl<T> Serialize for &mut T
@@ -329,9 +392,14 @@ where
     T: Serialize + ?Sized,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
         <T as Serialize>::serialize(self)
     }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        <T as Serialize>::serialize_to(self, emitter)
+    }
 }
 
 macro_rules! serialize_wrapper {
@@ -341,9 +409,14 @@ macro_rules! serialize_wrapper {
             T: Serialize + ?Sized,
         {
             #[inline]
-            fn serialize(&self) -> Result<Intermediate, Error> {
+            fn serialize(&self) -> Result<Intermediate<'_>, Error> {
                 <T as Serialize>::serialize(&*self)
             }
+
+            #[inline]
+            fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+                <T as Serialize>::serialize_to(&*self, emitter)
+            }
         }
     };
 }
@@ -357,8 +430,16 @@ where
     T: Serialize + ?Sized,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        self.lock().unwrap().serialize()
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        self.lock()
+            .unwrap()
+            .serialize()
+            .map(Intermediate::into_owned)
+    }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        self.lock().unwrap().serialize_to(emitter)
     }
 }
 
@@ -367,21 +448,101 @@ where
     T: Serialize + ?Sized,
 {
     #[inline]
-    fn serialize(&self) -> Result<Intermediate, Error> {
-        self.borrow().serialize()
+    fn serialize(&self) -> Result<Intermediate<'_>, Error> {
+        self.borrow().serialize().map(Intermediate::into_owned)
+    }
+
+    #[inline]
+    fn serialize_to(&self, emitter: &mut dyn Emitter) -> Result<(), Error> {
+        self.borrow().serialize_to(emitter)
     }
 }
 
 /// Helper function.
-fn serialize_slice<T>(v: &[T]) -> Result<Intermediate, Error>
+fn serialize_slice<T>(v: &[T]) -> Result<Intermediate<'_>, Error>
 where
     T: Serialize,
 {
     let mut res = Vec::with_capacity(v.len());
+    let mut errors = ErrorList::new();
+
+    for (index, elem) in v.iter().enumerate() {
+        match elem.serialize() {
+            Ok(elem) => res.push(elem),
+            Err(err) => errors.push(UnnamedFieldError::new(index, err)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Intermediate::Array(res))
+    } else {
+        Err(Error::UnnamedFieldErrors(errors))
+    }
+}
+
+/// Helper function.
+fn serialize_map<'a, K, V, I>(len: usize, iter: I) -> Result<Intermediate<'a>, Error>
+where
+    K: ToString + 'a,
+    V: Serialize + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let mut res = Map::with_capacity(len);
+    let mut errors = ErrorList::new();
+
+    for (k, v) in iter {
+        let k = k.to_string();
 
-    for elem in v.iter() {
-        res.push(elem.serialize()?);
+        match v.serialize() {
+            Ok(v) => res.insert_with_owned_key(k, v),
+            Err(err) => errors.push(NamedFieldError::from_key(Cow::Owned(k), err)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Intermediate::Map(res))
+    } else {
+        Err(Error::NamedFieldErrors(errors))
+    }
+}
+
+/// Helper function.
+fn serialize_slice_to<T>(v: &[T], emitter: &mut dyn Emitter) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    emitter.begin_array(Some(v.len()))?;
+
+    for (index, elem) in v.iter().enumerate() {
+        elem.serialize_to(emitter)
+            .map_err(|err| UnnamedFieldError::new(index, err))?;
+    }
+
+    emitter.end_array()
+}
+
+/// Helper function.
+fn serialize_map_to<'a, K, V, I>(
+    len: usize,
+    iter: I,
+    emitter: &mut dyn Emitter,
+) -> Result<(), Error>
+where
+    K: ToString + 'a,
+    V: Serialize + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    emitter.begin_map(Some(len))?;
+
+    for (k, v) in iter {
+        let k = k.to_string();
+
+        emitter.key(&k)?;
+
+        if let Err(err) = v.serialize_to(emitter) {
+            return Err(NamedFieldError::from_key(Cow::Owned(k), err).into());
+        }
     }
 
-    Ok(Intermediate::Array(res))
+    emitter.end_map()
 }
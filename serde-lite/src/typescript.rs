@@ -0,0 +1,465 @@
+//! TypeScript type declarations.
+//!
+//! `Declarations` generates TypeScript declarations of the serialized form
+//! of types implementing the `Describe` trait. Structs with named fields are
+//! declared as interfaces, other structs and enums are declared as type
+//! aliases. Enums become discriminated unions matching their
+//! representation. All structs and enums referenced by the added types are
+//! declared as well:
+//!
+//! ```rust
+//! use serde_lite::typescript::Declarations;
+//! use serde_lite_derive::Describe;
+//!
+//! #[derive(Describe)]
+//! struct User {
+//!     name: String,
+//!     #[serde(skip_serializing_if = "Option::is_none")]
+//!     email: Option<String>,
+//!     phone: Option<String>,
+//!     status: Status,
+//! }
+//!
+//! #[derive(Describe)]
+//! #[serde(tag = "type")]
+//! enum Status {
+//!     Active,
+//!     Suspended { reason: String },
+//! }
+//!
+//! let mut declarations = Declarations::new();
+//!
+//! declarations.add::<User>();
+//!
+//! assert_eq!(
+//!     declarations.to_string(),
+//!     r#"export type Status =
+//!     | { type: "Active" }
+//!     | { type: "Suspended"; reason: string };
+//!
+//! export interface User {
+//!     name: string;
+//!     email?: string;
+//!     phone: string | null;
+//!     status: Status;
+//! }
+//! "#
+//! );
+//! ```
+//!
+//! The declarations describe the serialized values, i.e. fields that are
+//! never serialized are omitted and fields with the `skip_serializing_if`
+//! attribute are optional. Optional values that are always serialized are
+//! declared as `T | null`. Different types with the same name (e.g.
+//! instances of a generic type) get a numeric suffix.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    io::{self, Write},
+};
+
+use crate::{
+    describe::{EnumRepr, Field, Fields, Shape, ShapeFn, Variant},
+    Describe,
+};
+
+/// Indentation of declaration members.
+const INDENT: &str = "    ";
+
+/// Maximum length of fixed-size arrays declared as tuples.
+const MAX_TUPLE_LEN: usize = 16;
+
+/// TypeScript declarations of a set of types.
+#[derive(Default)]
+pub struct Declarations {
+    names: HashMap<&'static str, String>,
+    declarations: Vec<String>,
+}
+
+impl Declarations {
+    /// Create a new empty set of declarations.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add declaration of a given type (and all types it references).
+    ///
+    /// Only structs and enums are declared. Types that have been already
+    /// added are ignored.
+    pub fn add<T>(&mut self) -> &mut Self
+    where
+        T: Describe + ?Sized,
+    {
+        self.add_shape(T::describe())
+    }
+
+    /// Add declaration of a given shape (and all types it references).
+    pub fn add_shape(&mut self, shape: Shape) -> &mut Self {
+        if shape.type_name().is_some() {
+            self.reference(shape);
+        }
+
+        self
+    }
+
+    /// Write the declarations into a given writer.
+    pub fn to_writer<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "{}", self)
+    }
+
+    /// Get TypeScript type of a given shape.
+    fn type_expr(&mut self, shape: Shape) -> String {
+        match shape {
+            Shape::Any => String::from("unknown"),
+            Shape::Null => String::from("null"),
+            Shape::Bool => String::from("boolean"),
+            Shape::Integer { .. } | Shape::Float => String::from("number"),
+            Shape::Char | Shape::String => String::from("string"),
+            Shape::Bytes => String::from("number[]"),
+            Shape::Option(inner) => format!("{} | null", self.type_expr(inner())),
+            Shape::Seq(inner) => array_type(self.type_expr(inner())),
+            Shape::Array(inner, len) if len <= MAX_TUPLE_LEN => {
+                let element = self.type_expr(inner());
+
+                format!("[{}]", vec![element; len].join(", "))
+            }
+            Shape::Array(inner, _) => array_type(self.type_expr(inner())),
+            Shape::Tuple(elements) => self.tuple_type(&elements),
+            Shape::Map(inner) => format!("Record<string, {}>", self.type_expr(inner())),
+            Shape::Struct(_) | Shape::Enum(_) => self.reference(shape),
+        }
+    }
+
+    /// Get name of a given struct or enum shape.
+    ///
+    /// The declaration is generated if it does not exist yet.
+    fn reference(&mut self, shape: Shape) -> String {
+        // NOTE: only structs and enums can be referenced
+        let type_name = shape.type_name().unwrap_or_default();
+
+        if let Some(name) = self.names.get(type_name) {
+            return name.clone();
+        }
+
+        let name = self.declaration_name(&shape);
+
+        // the name must be registered before generating the declaration in
+        // order to support recursive types
+        self.names.insert(type_name, name.clone());
+
+        let declaration = match shape {
+            Shape::Struct(s) => self.struct_declaration(&name, s.fields()),
+            Shape::Enum(e) => self.enum_declaration(&name, e.repr(), e.variants()),
+            _ => format!("export type {} = unknown;\n", name),
+        };
+
+        self.declarations.push(declaration);
+
+        name
+    }
+
+    /// Get an unused declaration name for a given struct or enum shape.
+    fn declaration_name(&self, shape: &Shape) -> String {
+        let name = shape.name().unwrap_or("Type");
+
+        let mut res = String::from(name);
+        let mut index = 1;
+
+        // different types may have the same name (e.g. generic types)
+        while self.names.values().any(|n| *n == res) {
+            index += 1;
+            res = format!("{}{}", name, index);
+        }
+
+        res
+    }
+
+    /// Generate declaration of a struct with given fields.
+    fn struct_declaration(&mut self, name: &str, fields: &Fields) -> String {
+        let Fields::Named(fields) = fields else {
+            return format!("export type {} = {};\n", name, self.content_type(fields));
+        };
+
+        let mut object = ObjectType::new();
+
+        self.add_fields(&mut object, fields);
+
+        if object.extra.is_empty() {
+            let mut res = format!("export interface {} ", name);
+
+            object.write_multiline(&mut res);
+
+            res.push('\n');
+            res
+        } else {
+            format!("export type {} = {};\n", name, object.to_multiline())
+        }
+    }
+
+    /// Generate declaration of an enum with given variants.
+    fn enum_declaration(&mut self, name: &str, repr: EnumRepr, variants: &[Variant]) -> String {
+        let mut res = format!("export type {} =", name);
+
+        for variant in variants {
+            let variant = self.variant_type(variant, repr);
+
+            res.push('\n');
+            res.push_str(INDENT);
+            res.push_str("| ");
+            res.push_str(&variant);
+        }
+
+        res.push_str(";\n");
+        res
+    }
+
+    /// Get type of a given enum variant.
+    fn variant_type(&mut self, variant: &Variant, repr: EnumRepr) -> String {
+        let name = quote(variant.name());
+        let fields = variant.fields();
+
+        match repr {
+            EnumRepr::External if is_unit(fields) => name,
+            EnumRepr::External => {
+                let content = self.content_type(fields);
+
+                format!("{{ {}: {} }}", property_name(variant.name()), content)
+            }
+            EnumRepr::Internal { tag } => {
+                let mut object = ObjectType::new();
+
+                object.member(tag, false, name);
+
+                match fields {
+                    Fields::Named(fields) => self.add_fields(&mut object, fields),
+                    _ if is_unit(fields) => (),
+                    _ => object.extra.push(self.content_type(fields)),
+                }
+
+                object.to_inline()
+            }
+            EnumRepr::Adjacent { tag, content } => {
+                let mut object = ObjectType::new();
+
+                object.member(tag, false, name);
+                object.member(content, false, self.content_type(fields));
+                object.to_inline()
+            }
+        }
+    }
+
+    /// Get type of given struct or enum variant fields.
+    fn content_type(&mut self, fields: &Fields) -> String {
+        match fields {
+            Fields::Unit => String::from("null"),
+            Fields::Unnamed(fields) => match fields.len() {
+                0 => String::from("null"),
+                1 => self.type_expr(fields[0]()),
+                _ => self.tuple_type(fields),
+            },
+            Fields::Named(fields) => {
+                let mut object = ObjectType::new();
+
+                self.add_fields(&mut object, fields);
+
+                object.to_inline()
+            }
+        }
+    }
+
+    /// Add given named fields into a given object type.
+    fn add_fields(&mut self, object: &mut ObjectType, fields: &[Field]) {
+        for field in fields {
+            if field.skip_serializing() {
+                continue;
+            }
+
+            let shape = field.shape();
+
+            if field.is_flattened() {
+                let ty = self.type_expr(shape);
+
+                object.extra.push(ty);
+            } else if field.skip_serializing_if() {
+                // the field is omitted instead of being serialized as null
+                let ty = match shape {
+                    Shape::Option(inner) => self.type_expr(inner()),
+                    other => self.type_expr(other),
+                };
+
+                object.member(field.name(), true, ty);
+            } else {
+                let ty = self.type_expr(shape);
+
+                object.member(field.name(), false, ty);
+            }
+        }
+    }
+
+    /// Get type of a tuple with given element shapes.
+    fn tuple_type(&mut self, elements: &[ShapeFn]) -> String {
+        let elements = elements
+            .iter()
+            .map(|element| self.type_expr(element()))
+            .collect::<Vec<_>>();
+
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+impl Display for Declarations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, declaration) in self.declarations.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+
+            f.write_str(declaration)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Object type builder.
+struct ObjectType {
+    members: Vec<String>,
+    extra: Vec<String>,
+}
+
+impl ObjectType {
+    /// Create a new object type builder.
+    fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            extra: Vec::new(),
+        }
+    }
+
+    /// Add a member.
+    fn member(&mut self, name: &str, optional: bool, ty: String) {
+        let optional = if optional { "?" } else { "" };
+
+        self.members
+            .push(format!("{}{}: {}", property_name(name), optional, ty));
+    }
+
+    /// Write the object type literal (without the intersected types) with
+    /// every member on a separate line.
+    fn write_multiline(&self, buf: &mut String) {
+        if self.members.is_empty() {
+            buf.push_str("{}");
+        } else {
+            buf.push_str("{\n");
+
+            for member in &self.members {
+                buf.push_str(INDENT);
+                buf.push_str(member);
+                buf.push_str(";\n");
+            }
+
+            buf.push('}');
+        }
+    }
+
+    /// Get the object type with every member on a separate line.
+    fn to_multiline(&self) -> String {
+        let mut res = String::new();
+
+        if !self.members.is_empty() || self.extra.is_empty() {
+            self.write_multiline(&mut res);
+        }
+
+        self.intersect(res)
+    }
+
+    /// Get the object type on a single line.
+    fn to_inline(&self) -> String {
+        let mut res = String::new();
+
+        if !self.members.is_empty() {
+            res = format!("{{ {} }}", self.members.join("; "));
+        } else if self.extra.is_empty() {
+            res.push_str("{}");
+        }
+
+        self.intersect(res)
+    }
+
+    /// Intersect a given object type literal with the extra types.
+    fn intersect(&self, object: String) -> String {
+        let mut res = object;
+
+        for ty in &self.extra {
+            if !res.is_empty() {
+                res.push_str(" & ");
+            }
+
+            res.push_str(ty);
+        }
+
+        res
+    }
+}
+
+/// Check if given fields are serialized as null.
+fn is_unit(fields: &Fields) -> bool {
+    match fields {
+        Fields::Unit => true,
+        Fields::Unnamed(fields) => fields.is_empty(),
+        Fields::Named(_) => false,
+    }
+}
+
+/// Get type of an array with a given element type.
+fn array_type(element: String) -> String {
+    if element.contains(' ') {
+        format!("({})[]", element)
+    } else {
+        format!("{}[]", element)
+    }
+}
+
+/// Get a given property name (quoted if necessary).
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+
+    let valid = chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if valid {
+        String::from(name)
+    } else {
+        quote(name)
+    }
+}
+
+/// Create a string literal.
+fn quote(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+
+    res.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+
+    res.push('"');
+    res
+}
@@ -8,7 +8,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use crate::{Deserialize, Error, Intermediate};
+use crate::{map, Deserialize, Error, ErrorList, Intermediate, NamedFieldError, UnnamedFieldError};
 
 /// Update trait.
 ///
@@ -16,7 +16,31 @@ use crate::{Deserialize, Error, Intermediate};
 /// representation.
 pub trait Update: Deserialize {
     /// Update the object.
+    ///
+    /// Errors are collected for all fields and elements, so the object may
+    /// be modified partially if the method fails. Use `try_update_atomic` if
+    /// the object must be left unchanged in such case.
     fn update(&mut self, val: &Intermediate) -> Result<(), Error>;
+
+    /// Update the object atomically.
+    ///
+    /// A copy of the object is updated and it replaces the object only if
+    /// the update succeeds, i.e. the object is left unchanged if the method
+    /// fails. Note that cloning shared pointers (e.g. `Arc<Mutex<T>>`) does
+    /// not clone the data behind them, so the guarantee does not apply to
+    /// values behind such pointers.
+    fn try_update_atomic(&mut self, val: &Intermediate) -> Result<(), Error>
+    where
+        Self: Clone,
+    {
+        let mut staged = self.clone();
+
+        staged.update(val)?;
+
+        *self = staged;
+
+        Ok(())
+    }
 }
 
 macro_rules! update_by_replace {
@@ -79,20 +103,33 @@ where
 {
     fn update(&mut self, val: &Intermediate) -> Result<(), Error> {
         if let Some(val) = val.as_array() {
-            match val.len() {
-                len if self.len() > len => self.truncate(len),
-                len if self.len() < len => self.reserve(len - self.len()),
-                _ => (),
-            }
+            let mut errors = ErrorList::new();
+            let mut appended = Vec::with_capacity(val.len().saturating_sub(self.len()));
 
             for (index, elem) in val.iter().enumerate() {
-                if let Some(current) = self.get_mut(index) {
-                    current.update(elem)?;
+                let res = if let Some(current) = self.get_mut(index) {
+                    current.update(elem)
                 } else {
-                    self.push(T::deserialize(elem)?);
+                    T::deserialize(elem).map(|elem| appended.push(elem))
+                };
+
+                if let Err(err) = res {
+                    errors.push(UnnamedFieldError::new(index, err));
                 }
             }
 
+            if !errors.is_empty() {
+                return Err(Error::UnnamedFieldErrors(errors));
+            }
+
+            // the length is changed only if all elements have been updated
+            self.truncate(val.len());
+            self.append(&mut appended);
+
+            Ok(())
+        } else if val.as_bytes().is_some() {
+            *self = Self::deserialize(val)?;
+
             Ok(())
         } else {
             Err(Error::invalid_value_static("array"))
@@ -122,10 +159,22 @@ macro_rules! update_array {
                         )));
                     }
 
-                    for (index, elem) in val.iter().enumerate() {
-                        self[index].update(elem)?;
+                    let mut errors = ErrorList::new();
+
+                    for (index, elem) in val.iter().take($len).enumerate() {
+                        if let Err(err) = self[index].update(elem) {
+                            errors.push(UnnamedFieldError::new(index, err));
+                        }
                     }
 
+                    if errors.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(Error::UnnamedFieldErrors(errors))
+                    }
+                } else if val.as_bytes().is_some() {
+                    *self = Self::deserialize(val)?;
+
                     Ok(())
                 } else {
                     Err(Error::invalid_value_static(concat!(
@@ -190,11 +239,19 @@ macro_rules! update_tuple {
                         return Err(Error::invalid_value_static(concat!("an array of length ", $len)));
                     }
 
+                    let mut errors = ErrorList::new();
+
                     $(
-                        self.$n.update(&val[$n])?;
+                        if let Err(err) = self.$n.update(&val[$n]) {
+                            errors.push(UnnamedFieldError::new($n, err));
+                        }
                     )+
 
-                    Ok(())
+                    if errors.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(Error::UnnamedFieldErrors(errors))
+                    }
                 } else {
                     Err(Error::invalid_value_static(concat!("an array of length ", $len)))
                 }
@@ -230,17 +287,30 @@ where
             .as_map()
             .ok_or_else(|| Error::invalid_value_static("map"))?;
 
+        let mut errors = ErrorList::new();
+        let mut inserted = Vec::new();
+
         for (name, value) in val {
-            if let Some(inner) = self.get_mut(name) {
-                V::update(inner, value)?;
+            let res = if let Some(inner) = self.get_mut(name) {
+                V::update(inner, value)
             } else {
-                let k = K::from(name.clone());
-                let v = V::deserialize(value)?;
+                V::deserialize(value).map(|v| {
+                    inserted.push((K::from(map::to_owned_key(name)), v));
+                })
+            };
 
-                self.insert(k, v);
+            if let Err(err) = res {
+                errors.push(NamedFieldError::from_key(map::to_owned_key(name), err));
             }
         }
 
+        if !errors.is_empty() {
+            return Err(Error::NamedFieldErrors(errors));
+        }
+
+        // new entries are inserted only if all entries have been updated
+        self.extend(inserted);
+
         Ok(())
     }
 }
@@ -256,17 +326,30 @@ where
             .as_map()
             .ok_or_else(|| Error::invalid_value_static("map"))?;
 
+        let mut errors = ErrorList::new();
+        let mut inserted = Vec::new();
+
         for (name, value) in val {
-            if let Some(inner) = self.get_mut(name as &str) {
-                V::update(inner, value)?;
+            let res = if let Some(inner) = self.get_mut(name as &str) {
+                V::update(inner, value)
             } else {
-                let k = K::from(name.clone());
-                let v = V::deserialize(value)?;
+                V::deserialize(value).map(|v| {
+                    inserted.push((K::from(map::to_owned_key(name)), v));
+                })
+            };
 
-                self.insert(k, v);
+            if let Err(err) = res {
+                errors.push(NamedFieldError::from_key(map::to_owned_key(name), err));
             }
         }
 
+        if !errors.is_empty() {
+            return Err(Error::NamedFieldErrors(errors));
+        }
+
+        // new entries are inserted only if all entries have been updated
+        self.extend(inserted);
+
         Ok(())
     }
 }
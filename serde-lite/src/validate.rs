@@ -0,0 +1,448 @@
+//! Declarative field validation.
+//!
+//! The derived `Deserialize`, `DeserializeView` and `Update` implementations
+//! can validate named fields using the `validate` attribute. The validation
+//! runs after a field has been deserialized (or updated) and all violations
+//! are reported as `Error::Validation` field errors together with ordinary
+//! type errors of other fields:
+//!
+//! ```rust
+//! use serde_lite::{intermediate, Deserialize, Error};
+//! use serde_lite_derive::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Product {
+//!     #[serde(validate(length(min = 1, max = 8), pattern = "^[A-Z0-9-]+$"))]
+//!     sku: String,
+//!     #[serde(validate(range(min = 1, max = 100)))]
+//!     quantity: u32,
+//!     #[serde(validate(non_empty, custom = "validate_tags"))]
+//!     tags: Vec<String>,
+//!     price: f64,
+//! }
+//!
+//! fn validate_tags(tags: &[String]) -> Result<(), Error> {
+//!     if tags.iter().all(|tag| tag.is_ascii()) {
+//!         Ok(())
+//!     } else {
+//!         Err(Error::validation_static("tags must be ASCII strings"))
+//!     }
+//! }
+//!
+//! let input = intermediate!({
+//!     "sku": "abc",
+//!     "quantity": 0,
+//!     "tags": [],
+//!     "price": "free",
+//! });
+//!
+//! let err = Product::deserialize(&input).err().unwrap();
+//!
+//! let mut errors = err
+//!     .leaves()
+//!     .into_iter()
+//!     .map(|(path, err)| format!("{}: {}", path, err))
+//!     .collect::<Vec<_>>();
+//!
+//! errors.sort();
+//!
+//! assert_eq!(
+//!     errors,
+//!     [
+//!         "price: invalid value (number expected)",
+//!         "quantity: value must be between 1 and 100",
+//!         "sku: value does not match the pattern ^[A-Z0-9-]+$",
+//!         "tags: value must not be empty",
+//!     ]
+//! );
+//! ```
+//!
+//! The following rules are supported:
+//!
+//! * `range(min = ..., max = ...)` for numbers
+//! * `length(min = ..., max = ...)` for strings (the number of characters),
+//!   vectors, slices, arrays and maps
+//! * `non_empty` for the same types as `length`
+//! * `pattern = "..."` for strings (see the `pattern` module for the
+//!   supported syntax)
+//! * `custom = "path"` calling a function `fn(&T) -> Result<(), Error>`
+//!
+//! Both bounds of `range` and `length` are inclusive and either of them can
+//! be omitted. Integer literals can be used as bounds of floating point
+//! numbers. Optional fields are validated only if they contain a value.
+//! The rules are also available as functions that can be used in custom
+//! `Deserialize` implementations.
+//!
+//! The syntax of patterns is checked at compile time:
+//!
+//! ```rust,compile_fail
+//! use serde_lite_derive::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Product {
+//!     #[serde(validate(pattern = "^[A-Z"))]
+//!     sku: String,
+//! }
+//! ```
+//!
+//! # Container validation
+//!
+//! Invariants spanning multiple fields can be checked using the `validate`
+//! container attribute. The given function `fn(&Self) -> Result<(), Error>`
+//! is called after the value has been deserialized (i.e. only if there are
+//! no field errors). The derived `Update` calls the function after applying
+//! all changes and it restores the previous state of the object if the
+//! update or the validation fails, so the type must implement `Clone`:
+//!
+//! ```rust
+//! use serde_lite::{intermediate, Error, Update};
+//! use serde_lite_derive::{Deserialize, Update};
+//!
+//! #[derive(Clone, Deserialize, Update)]
+//! #[serde(validate = "validate_interval")]
+//! struct Interval {
+//!     start: u32,
+//!     end: u32,
+//! }
+//!
+//! fn validate_interval(interval: &Interval) -> Result<(), Error> {
+//!     if interval.start <= interval.end {
+//!         Ok(())
+//!     } else {
+//!         Err(Error::validation_static("start must not be greater than end"))
+//!     }
+//! }
+//!
+//! let mut interval = Interval { start: 1, end: 5 };
+//!
+//! let err = interval.update(&intermediate!({ "start": 10 })).err().unwrap();
+//!
+//! assert_eq!(err.to_string(), "start must not be greater than end");
+//! assert_eq!(interval.start, 1);
+//! ```
+
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap, fmt::Display, sync::OnceLock};
+
+use crate::{pattern::Pattern, Error};
+
+/// Value that can be validated using the `range` rule.
+pub trait Bounded {
+    /// Type of the bounds.
+    type Bound: PartialOrd + Display;
+
+    /// Get the value to be compared with the bounds (if any).
+    fn bounded_value(&self) -> Option<&Self::Bound>;
+}
+
+macro_rules! impl_bounded {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Bounded for $ty {
+                type Bound = Self;
+
+                #[inline]
+                fn bounded_value(&self) -> Option<&Self> {
+                    Some(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_bounded!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char);
+
+/// Bound that can be given as an integer literal.
+///
+/// This is used by the derived code so that integer literals can be used as
+/// bounds of floating point numbers.
+pub trait FromIntLiteral {
+    /// Type of the literal.
+    type Literal;
+
+    /// Convert a given literal.
+    fn from_int_literal(literal: Self::Literal) -> Self;
+}
+
+macro_rules! impl_from_int_literal {
+    ( $( $ty:ty ),* => $lit:ty ) => {
+        $(
+            impl FromIntLiteral for $ty {
+                type Literal = $lit;
+
+                #[inline]
+                fn from_int_literal(literal: $lit) -> Self {
+                    literal as Self
+                }
+            }
+        )*
+    };
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl_from_int_literal!($ty => $ty);
+        )*
+    };
+}
+
+impl_from_int_literal!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_from_int_literal!(f32, f64 => i64);
+
+impl<T> Bounded for Option<T>
+where
+    T: Bounded,
+{
+    type Bound = T::Bound;
+
+    #[inline]
+    fn bounded_value(&self) -> Option<&Self::Bound> {
+        self.as_ref().and_then(T::bounded_value)
+    }
+}
+
+/// Value that can be validated using the `length` and `non_empty` rules.
+pub trait Length {
+    /// Get the length (if any).
+    ///
+    /// The length of a string is the number of its characters.
+    fn length(&self) -> Option<usize>;
+}
+
+impl Length for str {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl Length for String {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.as_str().length()
+    }
+}
+
+impl Length for Cow<'_, str> {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        str::length(self)
+    }
+}
+
+impl<T> Length for [T] {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> Length for Vec<T> {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T, const N: usize> Length for [T; N] {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(N)
+    }
+}
+
+impl<K, V, S> Length for HashMap<K, V, S> {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+#[cfg(feature = "preserve-order")]
+impl<K, V, S> Length for indexmap::IndexMap<K, V, S> {
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> Length for Option<T>
+where
+    T: Length,
+{
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.as_ref().and_then(T::length)
+    }
+}
+
+/// Value that can be validated using the `pattern` rule.
+pub trait Text {
+    /// Get the text (if any).
+    fn text(&self) -> Option<&str>;
+}
+
+impl Text for str {
+    #[inline]
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Text for String {
+    #[inline]
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Text for Cow<'_, str> {
+    #[inline]
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T> Text for Option<T>
+where
+    T: Text,
+{
+    #[inline]
+    fn text(&self) -> Option<&str> {
+        self.as_ref().and_then(T::text)
+    }
+}
+
+/// Pattern compiled on first use.
+///
+/// This is used by the derived code for the `pattern` rule.
+pub struct LazyPattern {
+    source: &'static str,
+    pattern: OnceLock<Result<Pattern, Error>>,
+}
+
+impl LazyPattern {
+    /// Create a new lazily compiled pattern.
+    #[inline]
+    pub const fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            pattern: OnceLock::new(),
+        }
+    }
+
+    /// Get the compiled pattern.
+    ///
+    /// An error is returned if the pattern is not valid (e.g. it exceeds the
+    /// size limit of compiled patterns).
+    pub fn get(&self) -> Result<&Pattern, Error> {
+        self.pattern
+            .get_or_init(|| Pattern::new(self.source))
+            .as_ref()
+            .map_err(Error::clone)
+    }
+}
+
+/// Check that a given value is within given bounds (inclusive).
+pub fn range<T>(val: &T, min: Option<T::Bound>, max: Option<T::Bound>) -> Result<(), Error>
+where
+    T: Bounded + ?Sized,
+{
+    let Some(val) = val.bounded_value() else {
+        return Ok(());
+    };
+
+    // NOTE: incomparable values (e.g. NaN) are out of bounds
+    let too_small = min
+        .as_ref()
+        .map(|min| {
+            !matches!(
+                val.partial_cmp(min),
+                Some(Ordering::Greater | Ordering::Equal)
+            )
+        })
+        .unwrap_or(false);
+
+    let too_large = max
+        .as_ref()
+        .map(|max| !matches!(val.partial_cmp(max), Some(Ordering::Less | Ordering::Equal)))
+        .unwrap_or(false);
+
+    if !too_small && !too_large {
+        return Ok(());
+    }
+
+    let msg = match (min, max) {
+        (Some(min), Some(max)) => format!("value must be between {} and {}", min, max),
+        (Some(min), None) => format!("value must be at least {}", min),
+        (None, Some(max)) => format!("value must be at most {}", max),
+        (None, None) => unreachable!(),
+    };
+
+    Err(Error::validation(msg))
+}
+
+/// Check that the length of a given value is within given bounds
+/// (inclusive).
+#[inline]
+pub fn length<T>(val: &T, min: Option<usize>, max: Option<usize>) -> Result<(), Error>
+where
+    T: Length + ?Sized,
+{
+    check_length(val.length(), min, max)
+}
+
+/// Check that a given value is not empty.
+#[inline]
+pub fn non_empty<T>(val: &T) -> Result<(), Error>
+where
+    T: Length + ?Sized,
+{
+    if val.length() == Some(0) {
+        Err(Error::validation_static("value must not be empty"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a given value matches a given pattern.
+#[inline]
+pub fn pattern<T>(val: &T, pattern: &LazyPattern) -> Result<(), Error>
+where
+    T: Text + ?Sized,
+{
+    check_pattern(val.text(), pattern)
+}
+
+/// Check a given length.
+#[inline(never)]
+fn check_length(len: Option<usize>, min: Option<usize>, max: Option<usize>) -> Result<(), Error> {
+    let Some(len) = len else {
+        return Ok(());
+    };
+
+    let too_short = min.map(|min| len < min).unwrap_or(false);
+    let too_long = max.map(|max| len > max).unwrap_or(false);
+
+    if !too_short && !too_long {
+        return Ok(());
+    }
+
+    let msg = match (min, max) {
+        (Some(min), Some(max)) => format!("length must be between {} and {}", min, max),
+        (Some(min), None) => format!("length must be at least {}", min),
+        (None, Some(max)) => format!("length must be at most {}", max),
+        (None, None) => unreachable!(),
+    };
+
+    Err(Error::validation(msg))
+}
+
+/// Check a given text.
+#[inline(never)]
+fn check_pattern(text: Option<&str>, pattern: &LazyPattern) -> Result<(), Error> {
+    match text {
+        Some(text) if !pattern.get()?.is_match(text) => Err(Error::validation(format!(
+            "value does not match the pattern {}",
+            pattern.source
+        ))),
+        _ => Ok(()),
+    }
+}
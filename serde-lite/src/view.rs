@@ -0,0 +1,817 @@
+//! Read-only views of values.
+//!
+//! `ValueView` abstracts over different representations of a value (e.g.
+//! `Intermediate` or `Arena`). Types implementing `DeserializeView` can be
+//! deserialized directly from any of them:
+//!
+//! ```rust
+//! use serde_lite::{arena::Arena, intermediate, view::DeserializeView};
+//!
+//! let val = intermediate!([1, 2, 3]);
+//! let arena = Arena::from_intermediate(&val);
+//!
+//! let res = Vec::<u32>::deserialize_view(&arena.root()).unwrap();
+//!
+//! assert_eq!(res, [1, 2, 3]);
+//! ```
+//!
+//! The trait is object safe, so the deserialization code is generated only
+//! once for all representations. The `DeserializeView` trait can be derived
+//! in the same way as `Deserialize`. With the `serde-json` feature enabled,
+//! `ValueView` is also implemented for `serde_json::Value`.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    hash::Hash,
+    ops::Deref,
+    rc::Rc,
+    slice,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    arena::{self, ArenaValue},
+    map, Deserialize, Error, ErrorList, Intermediate, Map, NamedFieldError, Number,
+    UnnamedFieldError,
+};
+
+/// Kind of a value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kind {
+    None,
+    Bool,
+    Number,
+    String,
+    Bytes,
+    Array,
+    Map,
+}
+
+/// Read-only view of a value.
+pub trait ValueView {
+    /// Get kind of the value.
+    fn kind(&self) -> Kind;
+
+    /// Get the value as a bool (if possible).
+    fn as_bool(&self) -> Option<bool>;
+
+    /// Get the value as a number (if possible).
+    fn as_number(&self) -> Option<Number>;
+
+    /// Get the value as a string (if possible).
+    fn as_str(&self) -> Option<&str>;
+
+    /// Get the value as a byte string (if possible).
+    fn as_bytes(&self) -> Option<&[u8]>;
+
+    /// Get the number of array elements or map entries.
+    ///
+    /// Zero is returned for all other kinds of values.
+    fn len(&self) -> usize;
+
+    /// Check if the value is an empty array, an empty map or not a container
+    /// at all.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a map entry with a given key.
+    fn get(&self, key: &str) -> Option<ViewRef<'_>>;
+
+    /// Get an array element at a given index.
+    #[inline]
+    fn element(&self, index: usize) -> Option<ViewRef<'_>> {
+        if self.kind() == Kind::Array {
+            self.iter().nth(index).map(|(_, v)| v)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over array elements or map entries.
+    ///
+    /// Array elements do not have any key. The iterator is empty for all
+    /// other kinds of values.
+    fn iter(&self) -> ViewIter<'_>;
+}
+
+impl<'a> dyn ValueView + 'a {
+    /// Get the value as a map (if possible).
+    #[inline]
+    pub fn as_map(&self) -> Option<&Self> {
+        if self.kind() == Kind::Map {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Get the value as an array (if possible).
+    #[inline]
+    pub fn as_array(&self) -> Option<&Self> {
+        if self.kind() == Kind::Array {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reference to a value view.
+#[derive(Copy, Clone)]
+pub struct ViewRef<'a> {
+    inner: RefInner<'a>,
+}
+
+/// Internal representation of a view reference.
+#[derive(Copy, Clone)]
+enum RefInner<'a> {
+    Dyn(&'a (dyn ValueView + 'a)),
+    Arena(ArenaValue<'a>),
+}
+
+impl<'a> ViewRef<'a> {
+    /// Create a new reference to a given value view.
+    #[inline]
+    pub fn new(val: &'a (dyn ValueView + 'a)) -> Self {
+        Self {
+            inner: RefInner::Dyn(val),
+        }
+    }
+
+    /// Get the value as a string (if possible).
+    ///
+    /// Unlike `ValueView::as_str`, the string is not bound to the lifetime of
+    /// this reference.
+    #[inline]
+    pub fn as_str(self) -> Option<&'a str> {
+        match self.inner {
+            RefInner::Dyn(val) => val.as_str(),
+            RefInner::Arena(val) => val.as_str(),
+        }
+    }
+
+    /// Get the value as a byte string (if possible).
+    #[inline]
+    pub fn as_bytes(self) -> Option<&'a [u8]> {
+        match self.inner {
+            RefInner::Dyn(val) => val.as_bytes(),
+            RefInner::Arena(val) => val.as_bytes(),
+        }
+    }
+
+    /// Get a map entry with a given key.
+    #[inline]
+    pub fn get(self, key: &str) -> Option<ViewRef<'a>> {
+        match self.inner {
+            RefInner::Dyn(val) => val.get(key),
+            RefInner::Arena(val) => val.get(key).map(ViewRef::from),
+        }
+    }
+
+    /// Iterate over array elements or map entries.
+    #[inline]
+    pub fn iter(self) -> ViewIter<'a> {
+        match self.inner {
+            RefInner::Dyn(val) => val.iter(),
+            RefInner::Arena(val) => ViewIter::from(val.iter()),
+        }
+    }
+
+    /// Convert the referenced value into an intermediate value.
+    ///
+    /// Strings and byte strings are borrowed from the underlying value.
+    #[inline(never)]
+    pub fn to_intermediate(self) -> Intermediate<'a> {
+        /// Partially built array or map.
+        struct Frame<'a> {
+            value: Intermediate<'a>,
+            entries: ViewIter<'a>,
+            key: Option<&'a str>,
+        }
+
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+
+        let mut current = self;
+
+        loop {
+            let value = match current.kind() {
+                Kind::None => Intermediate::None,
+                Kind::Bool => current
+                    .as_bool()
+                    .map(Intermediate::Bool)
+                    .unwrap_or(Intermediate::None),
+                Kind::Number => current
+                    .as_number()
+                    .map(Intermediate::Number)
+                    .unwrap_or(Intermediate::None),
+                Kind::String => current
+                    .as_str()
+                    .map(|s| Intermediate::String(Cow::Borrowed(s)))
+                    .unwrap_or(Intermediate::None),
+                Kind::Bytes => current
+                    .as_bytes()
+                    .map(|b| Intermediate::Bytes(Cow::Borrowed(b)))
+                    .unwrap_or(Intermediate::None),
+                Kind::Array => Intermediate::Array(Vec::with_capacity(current.len())),
+                Kind::Map => Intermediate::Map(Map::with_capacity(current.len())),
+            };
+
+            let mut value = if current.is_container() {
+                stack.push(Frame {
+                    value,
+                    entries: current.iter(),
+                    key: None,
+                });
+
+                None
+            } else {
+                Some(value)
+            };
+
+            loop {
+                let Some(top) = stack.last_mut() else {
+                    // there are no unfinished containers, so the value is
+                    // the root value
+                    return value.unwrap_or(Intermediate::None);
+                };
+
+                if let Some(child) = value.take() {
+                    match &mut top.value {
+                        Intermediate::Array(v) => v.push(child),
+                        Intermediate::Map(v) => {
+                            if let Some(key) = top.key.take() {
+                                v.insert(Cow::Borrowed(key), child);
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+
+                if let Some((key, child)) = top.entries.next() {
+                    top.key = key;
+                    current = child;
+                    break;
+                }
+
+                value = stack.pop().map(|frame| frame.value);
+            }
+        }
+    }
+
+    /// Check if the referenced value is an array or a map.
+    #[inline]
+    fn is_container(&self) -> bool {
+        matches!(self.kind(), Kind::Array | Kind::Map)
+    }
+}
+
+impl<'a> From<ArenaValue<'a>> for ViewRef<'a> {
+    #[inline]
+    fn from(val: ArenaValue<'a>) -> Self {
+        Self {
+            inner: RefInner::Arena(val),
+        }
+    }
+}
+
+impl<'a> Deref for ViewRef<'a> {
+    type Target = dyn ValueView + 'a;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match &self.inner {
+            RefInner::Dyn(val) => *val,
+            RefInner::Arena(val) => val,
+        }
+    }
+}
+
+/// Iterator over array elements or map entries of a value view.
+pub struct ViewIter<'a> {
+    inner: IterInner<'a>,
+}
+
+/// Internal representation of a view iterator.
+enum IterInner<'a> {
+    Empty,
+    Array(slice::Iter<'a, Intermediate<'a>>),
+    Map(map::Iter<'a, 'a>),
+    Arena(arena::Iter<'a>),
+    Dyn(Box<dyn Iterator<Item = (Option<&'a str>, ViewRef<'a>)> + 'a>),
+}
+
+impl<'a> ViewIter<'a> {
+    /// Create an empty iterator.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self {
+            inner: IterInner::Empty,
+        }
+    }
+
+    /// Create a new view iterator from a given iterator.
+    #[inline]
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = (Option<&'a str>, ViewRef<'a>)> + 'a,
+    {
+        Self {
+            inner: IterInner::Dyn(Box::new(iter)),
+        }
+    }
+}
+
+impl<'a> From<arena::Iter<'a>> for ViewIter<'a> {
+    #[inline]
+    fn from(iter: arena::Iter<'a>) -> Self {
+        Self {
+            inner: IterInner::Arena(iter),
+        }
+    }
+}
+
+impl<'a> Iterator for ViewIter<'a> {
+    type Item = (Option<&'a str>, ViewRef<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            IterInner::Empty => None,
+            IterInner::Array(iter) => iter.next().map(|v| (None, ViewRef::new(v))),
+            IterInner::Map(iter) => iter.next().map(|(k, v)| (Some(&**k), ViewRef::new(v))),
+            IterInner::Arena(iter) => iter.next().map(|(k, v)| (k, ViewRef::from(v))),
+            IterInner::Dyn(iter) => iter.next(),
+        }
+    }
+}
+
+impl ValueView for Intermediate<'_> {
+    #[inline]
+    fn kind(&self) -> Kind {
+        match self {
+            Self::None => Kind::None,
+            Self::Bool(_) => Kind::Bool,
+            Self::Number(_) => Kind::Number,
+            Self::String(_) => Kind::String,
+            Self::Bytes(_) => Kind::Bytes,
+            Self::Array(_) => Kind::Array,
+            Self::Map(_) => Kind::Map,
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Intermediate::as_bool(self)
+    }
+
+    #[inline]
+    fn as_number(&self) -> Option<Number> {
+        Intermediate::as_number(self)
+    }
+
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        Intermediate::as_str(self)
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> Option<&[u8]> {
+        Intermediate::as_bytes(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Self::Array(v) => v.len(),
+            Self::Map(v) => v.len(),
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn get(&self, key: &str) -> Option<ViewRef<'_>> {
+        self.as_map()?.get(key).map(|v| ViewRef::new(v))
+    }
+
+    #[inline]
+    fn element(&self, index: usize) -> Option<ViewRef<'_>> {
+        self.as_array()?.get(index).map(|v| ViewRef::new(v))
+    }
+
+    #[inline]
+    fn iter(&self) -> ViewIter<'_> {
+        let inner = match self {
+            Self::Array(v) => IterInner::Array(v.iter()),
+            Self::Map(v) => IterInner::Map(v.iter()),
+            _ => IterInner::Empty,
+        };
+
+        ViewIter { inner }
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl ValueView for serde_json::Value {
+    #[inline]
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Null => Kind::None,
+            Self::Bool(_) => Kind::Bool,
+            Self::Number(_) => Kind::Number,
+            Self::String(_) => Kind::String,
+            Self::Array(_) => Kind::Array,
+            Self::Object(_) => Kind::Map,
+        }
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        serde_json::Value::as_bool(self)
+    }
+
+    fn as_number(&self) -> Option<Number> {
+        let n = serde_json::Value::as_number(self)?;
+
+        if let Some(v) = n.as_u64() {
+            Some(Number::UnsignedInt(v))
+        } else if let Some(v) = n.as_i64() {
+            Some(Number::SignedInt(v))
+        } else {
+            n.as_f64().map(Number::Float)
+        }
+    }
+
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        serde_json::Value::as_str(self)
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> Option<&[u8]> {
+        None
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Self::Array(v) => v.len(),
+            Self::Object(v) => v.len(),
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn get(&self, key: &str) -> Option<ViewRef<'_>> {
+        self.as_object()?.get(key).map(|v| ViewRef::new(v))
+    }
+
+    #[inline]
+    fn element(&self, index: usize) -> Option<ViewRef<'_>> {
+        self.as_array()?.get(index).map(|v| ViewRef::new(v))
+    }
+
+    fn iter(&self) -> ViewIter<'_> {
+        match self {
+            Self::Array(v) => ViewIter::new(v.iter().map(|v| (None, ViewRef::new(v)))),
+            Self::Object(v) => {
+                ViewIter::new(v.iter().map(|(k, v)| (Some(k.as_str()), ViewRef::new(v))))
+            }
+            _ => ViewIter::empty(),
+        }
+    }
+}
+
+/// Deserialize trait for value views.
+///
+/// The trait is implemented for primitive types, strings, options, vectors,
+/// maps and smart pointers. Other types implementing `Deserialize` can be
+/// deserialized from a value view using the `deserialize` function.
+pub trait DeserializeView: Sized {
+    /// Deserialize an object instance from a given value view.
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error>;
+}
+
+/// Deserialize a given value view using the `Deserialize` implementation of
+/// a given type.
+///
+/// The value view is converted into an intermediate value at first. Strings
+/// and byte strings are borrowed from the value view.
+#[inline]
+pub fn deserialize<T>(val: &dyn ValueView) -> Result<T, Error>
+where
+    T: Deserialize,
+{
+    T::deserialize(&ViewRef::new(val).to_intermediate())
+}
+
+/// Get a given scalar value view as an intermediate value.
+///
+/// Arrays and maps are converted into `Intermediate::None`.
+#[inline(never)]
+fn scalar(val: &dyn ValueView) -> Intermediate<'_> {
+    let res = match val.kind() {
+        Kind::Bool => val.as_bool().map(Intermediate::Bool),
+        Kind::Number => val.as_number().map(Intermediate::Number),
+        Kind::String => val.as_str().map(|s| Intermediate::String(Cow::Borrowed(s))),
+        Kind::Bytes => val
+            .as_bytes()
+            .map(|b| Intermediate::Bytes(Cow::Borrowed(b))),
+        _ => None,
+    };
+
+    res.unwrap_or(Intermediate::None)
+}
+
+macro_rules! deserialize_view_scalar {
+    ( $x:ty ) => {
+        impl DeserializeView for $x {
+            #[inline]
+            fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+                <$x as Deserialize>::deserialize(&scalar(val))
+            }
+        }
+    };
+}
+
+deserialize_view_scalar!(bool);
+deserialize_view_scalar!(i8);
+deserialize_view_scalar!(i16);
+deserialize_view_scalar!(i32);
+deserialize_view_scalar!(i64);
+deserialize_view_scalar!(i128);
+deserialize_view_scalar!(isize);
+deserialize_view_scalar!(u8);
+deserialize_view_scalar!(u16);
+deserialize_view_scalar!(u32);
+deserialize_view_scalar!(u64);
+deserialize_view_scalar!(u128);
+deserialize_view_scalar!(usize);
+deserialize_view_scalar!(f32);
+deserialize_view_scalar!(f64);
+deserialize_view_scalar!(char);
+deserialize_view_scalar!(());
+
+impl DeserializeView for String {
+    #[inline]
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        val.as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::invalid_value_static("string"))
+    }
+}
+
+impl DeserializeView for Intermediate<'static> {
+    #[inline]
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        Ok(ViewRef::new(val).to_intermediate().into_owned())
+    }
+}
+
+impl<T> DeserializeView for Option<T>
+where
+    T: DeserializeView,
+{
+    #[inline]
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        if val.kind() == Kind::None {
+            Ok(None)
+        } else {
+            T::deserialize_view(val).map(Some)
+        }
+    }
+}
+
+impl<T> DeserializeView for Vec<T>
+where
+    T: DeserializeView,
+{
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        let mut errors = ErrorList::new();
+
+        let res = match val.kind() {
+            Kind::Array => {
+                let mut res = Vec::with_capacity(val.len());
+
+                for (index, (_, elem)) in val.iter().enumerate() {
+                    if let Some(elem) = deserialize_element(&*elem, index, &mut errors) {
+                        res.push(elem);
+                    }
+                }
+
+                res
+            }
+            Kind::Bytes => {
+                let bytes = val.as_bytes().unwrap_or_default();
+
+                let mut res = Vec::with_capacity(bytes.len());
+
+                for (index, b) in bytes.iter().enumerate() {
+                    let elem = Intermediate::from(*b);
+
+                    if let Some(elem) = deserialize_element(&elem, index, &mut errors) {
+                        res.push(elem);
+                    }
+                }
+
+                res
+            }
+            _ => return Err(Error::invalid_value_static("array")),
+        };
+
+        if errors.is_empty() {
+            Ok(res)
+        } else {
+            Err(Error::UnnamedFieldErrors(errors))
+        }
+    }
+}
+
+impl<K, V> DeserializeView for HashMap<K, V>
+where
+    K: From<Cow<'static, str>> + Eq + Hash,
+    V: DeserializeView,
+{
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        if val.kind() != Kind::Map {
+            return Err(Error::invalid_value_static("map"));
+        }
+
+        let mut res = HashMap::with_capacity(val.len());
+
+        let mut errors = ErrorList::new();
+
+        for (name, value) in val.iter() {
+            let name = map::to_owned_key(name.unwrap_or_default());
+
+            match V::deserialize_view(&*value) {
+                Ok(v) => {
+                    res.insert(K::from(name), v);
+                }
+                Err(err) => errors.push(NamedFieldError::from_key(name, err)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(res)
+        } else {
+            Err(Error::NamedFieldErrors(errors))
+        }
+    }
+}
+
+#[cfg(feature = "preserve-order")]
+impl<K, V> DeserializeView for indexmap::IndexMap<K, V>
+where
+    K: From<Cow<'static, str>> + Eq + Hash,
+    V: DeserializeView,
+{
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        if val.kind() != Kind::Map {
+            return Err(Error::invalid_value_static("map"));
+        }
+
+        let mut res = indexmap::IndexMap::with_capacity(val.len());
+
+        let mut errors = ErrorList::new();
+
+        for (name, value) in val.iter() {
+            let name = map::to_owned_key(name.unwrap_or_default());
+
+            match V::deserialize_view(&*value) {
+                Ok(v) => {
+                    res.insert(K::from(name), v);
+                }
+                Err(err) => errors.push(NamedFieldError::from_key(name, err)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(res)
+        } else {
+            Err(Error::NamedFieldErrors(errors))
+        }
+    }
+}
+
+impl<T, const N: usize> DeserializeView for [T; N]
+where
+    T: DeserializeView,
+{
+    fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+        if val.kind() != Kind::Array || val.len() < N {
+            return Err(Error::invalid_value(format!("an array of length {}", N)));
+        }
+
+        let mut res = Vec::with_capacity(N);
+
+        let mut errors = ErrorList::new();
+
+        for (index, (_, elem)) in val.iter().take(N).enumerate() {
+            if let Some(elem) = deserialize_element(&*elem, index, &mut errors) {
+                res.push(elem);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::UnnamedFieldErrors(errors));
+        }
+
+        res.try_into()
+            .map_err(|_| Error::invalid_value(format!("an array of length {}", N)))
+    }
+}
+
+macro_rules! deserialize_view_tuple {
+    ( $len:expr => ($($n:tt $ty:ident)+) ) => {
+        impl<$($ty),+> DeserializeView for ($($ty,)+)
+        where
+            $($ty: DeserializeView,)+
+        {
+            #[allow(non_snake_case)]
+            fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+                if val.kind() != Kind::Array || val.len() < $len {
+                    return Err(Error::invalid_value_static(concat!("an array of length ", $len)));
+                }
+
+                let mut elements = val.iter();
+
+                let mut errors = ErrorList::new();
+
+                let res = (
+                    $(
+                        elements
+                            .next()
+                            .and_then(|(_, elem)| deserialize_element::<$ty>(&*elem, $n, &mut errors)),
+                    )+
+                );
+
+                match res {
+                    ($(Some($ty),)+) => Ok(($($ty,)+)),
+                    _ if errors.is_empty() => {
+                        Err(Error::invalid_value_static(concat!("an array of length ", $len)))
+                    }
+                    _ => Err(Error::UnnamedFieldErrors(errors)),
+                }
+            }
+        }
+    };
+}
+
+deserialize_view_tuple!(1 => (0 T0));
+deserialize_view_tuple!(2 => (0 T0 1 T1));
+deserialize_view_tuple!(3 => (0 T0 1 T1 2 T2));
+deserialize_view_tuple!(4 => (0 T0 1 T1 2 T2 3 T3));
+deserialize_view_tuple!(5 => (0 T0 1 T1 2 T2 3 T3 4 T4));
+deserialize_view_tuple!(6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5));
+deserialize_view_tuple!(7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6));
+deserialize_view_tuple!(8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7));
+deserialize_view_tuple!(9 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8));
+deserialize_view_tuple!(10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9));
+deserialize_view_tuple!(11 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10));
+deserialize_view_tuple!(12 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11));
+deserialize_view_tuple!(13 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12));
+deserialize_view_tuple!(14 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13));
+deserialize_view_tuple!(15 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14));
+deserialize_view_tuple!(16 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15));
+
+macro_rules! deserialize_view_wrapper {
+    ( $x:ident ) => {
+        impl<T> DeserializeView for $x<T>
+        where
+            T: DeserializeView,
+        {
+            #[inline]
+            fn deserialize_view(val: &dyn ValueView) -> Result<Self, Error> {
+                let inner = T::deserialize_view(val)?;
+
+                Ok($x::new(inner))
+            }
+        }
+    };
+}
+
+deserialize_view_wrapper!(Box);
+deserialize_view_wrapper!(Rc);
+deserialize_view_wrapper!(Arc);
+deserialize_view_wrapper!(Cell);
+deserialize_view_wrapper!(RefCell);
+deserialize_view_wrapper!(Mutex);
+
+/// Helper function.
+#[inline]
+fn deserialize_element<T>(
+    val: &dyn ValueView,
+    index: usize,
+    errors: &mut ErrorList<UnnamedFieldError>,
+) -> Option<T>
+where
+    T: DeserializeView,
+{
+    T::deserialize_view(val)
+        .map_err(|err| errors.push(UnnamedFieldError::new(index, err)))
+        .ok()
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use serde_lite::{
+    compat, intermediate, Deserialize, Error, Intermediate, SerdeCompat, Serialize, Update,
+};
+
+use serde_lite_derive::{Deserialize, Serialize, Update};
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Newtype(u32);
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i32, i32),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foreign {
+    name: String,
+    unit: Unit,
+    newtype: Newtype,
+    optional: Option<u32>,
+    shapes: Vec<Shape>,
+    tags: HashMap<String, bool>,
+    #[serde(with = "serde_bytes_like")]
+    data: Vec<u8>,
+}
+
+mod serde_bytes_like {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(val)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor;
+
+        impl serde::de::Visitor<'_> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+#[test]
+fn test_compat_round_trip() {
+    let mut tags = HashMap::new();
+
+    tags.insert(String::from("foo"), true);
+
+    let val = Foreign {
+        name: String::from("foreign"),
+        unit: Unit,
+        newtype: Newtype(10),
+        optional: None,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Point(-1, 2),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        ],
+        tags,
+        data: vec![1, 2, 3],
+    };
+
+    let data = compat::serialize(&val).unwrap();
+
+    let expected = serde_json::json!({
+        "name": "foreign",
+        "unit": null,
+        "newtype": 10,
+        "optional": null,
+        "shapes": [
+            "Empty",
+            { "Circle": 1.5 },
+            { "Point": [-1, 2] },
+            { "Rect": { "width": 3, "height": 4 } },
+        ],
+        "tags": { "foo": true },
+        "data": [1, 2, 3],
+    });
+
+    assert_eq!(serde_json::to_value(&data).unwrap(), expected);
+    assert_eq!(
+        data.as_map().unwrap().get("data").unwrap().as_bytes(),
+        Some(&[1, 2, 3][..])
+    );
+
+    let res: Foreign = compat::deserialize(&data).unwrap();
+
+    assert_eq!(res, val);
+
+    let json = expected.to_string();
+    let input: Intermediate = serde_json::from_str(&json).unwrap();
+
+    let res: Result<Foreign, _> = compat::deserialize(&input);
+
+    // serde_json does not produce byte strings
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_compat_errors() {
+    let input = intermediate!({
+        "width": "foo",
+    });
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Rect {
+        width: u32,
+    }
+
+    let res: Result<Rect, Error> = compat::deserialize(&input);
+
+    assert!(matches!(res, Err(Error::Custom(_))));
+
+    let res: Result<Shape, Error> = compat::deserialize(&intermediate!("Unknown"));
+
+    assert!(matches!(res, Err(Error::Custom(_))));
+
+    let res: Result<Shape, Error> = compat::deserialize(&intermediate!(10));
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_compat_with() {
+    #[derive(Serialize, Deserialize, Update)]
+    struct Local {
+        id: u32,
+        #[serde(with = "serde_lite::compat")]
+        shape: Shape,
+        #[serde(with = "serde_lite::compat")]
+        newtype: Option<Newtype>,
+    }
+
+    let mut val = Local {
+        id: 1,
+        shape: Shape::Circle(2.0),
+        newtype: Some(Newtype(5)),
+    };
+
+    let data = val.serialize().unwrap();
+
+    let res = Local::deserialize(&data).unwrap();
+
+    assert_eq!(res.id, 1);
+    assert_eq!(res.shape, Shape::Circle(2.0));
+    assert_eq!(res.newtype, Some(Newtype(5)));
+
+    drop(data);
+
+    let input = intermediate!({
+        "shape": {
+            "Point": [1, 2],
+        },
+        "newtype": null,
+    });
+
+    val.update(&input).unwrap();
+
+    assert_eq!(val.shape, Shape::Point(1, 2));
+    assert_eq!(val.newtype, None);
+}
+
+#[test]
+fn test_serde_compat() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        #[serde(rename = "v")]
+        value: u32,
+    }
+
+    let json = serde_json::to_string(&SerdeCompat(Inner { value: 3 })).unwrap();
+
+    assert_eq!(json, r#"{"v":3}"#);
+
+    let res: SerdeCompat<Inner> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(res.into_inner(), Inner { value: 3 });
+
+    let res = serde_json::from_str::<SerdeCompat<Inner>>(r#"{"v":"foo"}"#);
+
+    assert!(res.is_err());
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(serde_compat)]
+    struct Wrapper<T>
+    where
+        T: serde_lite::Serialize + serde_lite::Deserialize,
+    {
+        inner: T,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type", serde_compat)]
+    enum Event {
+        Started { at: u64 },
+        Stopped,
+    }
+
+    let val = Wrapper {
+        inner: Event::Started { at: 10 },
+    };
+
+    let json = serde_json::to_string(&val).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::json!({"inner": {"type": "Started", "at": 10}})
+    );
+
+    let res: Wrapper<Event> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(res, val);
+
+    let res: Wrapper<Event> = serde_json::from_str(r#"{"inner":{"type":"Stopped"}}"#).unwrap();
+
+    assert_eq!(res.inner, Event::Stopped);
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn test_json_value_view() {
+    use serde_lite::{view::Kind, DeserializeView, ValueView};
+
+    #[derive(Debug, PartialEq, serde_lite_derive::DeserializeView)]
+    struct Record {
+        id: i64,
+        name: String,
+        score: f64,
+        tags: Vec<String>,
+        parent: Option<u64>,
+    }
+
+    let value = serde_json::json!({
+        "id": -1,
+        "name": "foo",
+        "score": 0.5,
+        "tags": ["a", "b"],
+        "parent": null,
+    });
+
+    assert_eq!(value.kind(), Kind::Map);
+    assert_eq!(ValueView::len(&value), 5);
+    assert_eq!(
+        value.get("tags").unwrap().element(1).unwrap().as_str(),
+        Some("b")
+    );
+
+    let res = Record::deserialize_view(&value).unwrap();
+
+    assert_eq!(
+        res,
+        Record {
+            id: -1,
+            name: String::from("foo"),
+            score: 0.5,
+            tags: vec![String::from("a"), String::from("b")],
+            parent: None,
+        }
+    );
+
+    let value = serde_json::json!({"id": 1});
+
+    assert!(Record::deserialize_view(&value).is_err());
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use serde_lite::{
+    csv::{self, CsvReader, CsvWriter},
+    intermediate, Error, Intermediate,
+};
+
+use serde_lite_derive::{Describe, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Describe)]
+struct Person {
+    name: String,
+    age: u8,
+    #[serde(rename = "zip code")]
+    zip: String,
+    active: Option<bool>,
+    score: Option<f64>,
+}
+
+#[test]
+fn test_csv_reader() {
+    let input = "name,age,zip code,active,score\r\n\
+        \"Doe, John\",42,00123,TRUE,1.5\r\n\
+        \r\n\
+        \"Multi\n\"\"line\"\"\",7,99999,,\r\n\
+        Jane,300,12345,false,2\r\n\
+        Bob,30,54321,maybe,3\n\
+        Alice,31,11111,true,";
+
+    let res = CsvReader::<_, Person>::new(input.as_bytes()).collect::<Vec<_>>();
+
+    assert_eq!(res.len(), 5);
+
+    assert_eq!(
+        res[0].as_ref().unwrap(),
+        &Person {
+            name: String::from("Doe, John"),
+            age: 42,
+            zip: String::from("00123"),
+            active: Some(true),
+            score: Some(1.5),
+        }
+    );
+
+    assert_eq!(
+        res[1].as_ref().unwrap(),
+        &Person {
+            name: String::from("Multi\n\"line\""),
+            age: 7,
+            zip: String::from("99999"),
+            active: None,
+            score: None,
+        }
+    );
+
+    let err = res[2].as_ref().unwrap_err();
+
+    assert_eq!(err.to_string(), "line 6: age: value is out of bounds");
+
+    let err = res[3].as_ref().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "line 7: active: invalid value (bool expected)"
+    );
+
+    if let Error::Line(err) = err {
+        assert_eq!(err.line(), 7);
+    } else {
+        panic!("unexpected error: {:?}", err);
+    }
+
+    assert_eq!(res[4].as_ref().unwrap().name, "Alice");
+
+    let input = "a;b\n1;\"x;y\"\n";
+
+    let mut reader = CsvReader::<_, Intermediate>::new(input.as_bytes()).with_delimiter(b';');
+
+    assert_eq!(reader.header().unwrap(), ["a", "b"]);
+
+    let row = reader.read_row().unwrap().unwrap();
+
+    let row = row.as_map().unwrap();
+
+    assert_eq!(row.get("a").unwrap().as_str(), Some("1"));
+    assert_eq!(row.get("b").unwrap().as_str(), Some("x;y"));
+
+    assert!(reader.read_row().unwrap().is_none());
+
+    let invalid = [
+        ("a,b\n1\n", "line 2: expected 2 fields, found 1"),
+        (
+            "a\n\"x\"y\n",
+            "line 2: unexpected character after a quoted field",
+        ),
+        ("a\nx\"y\n", "line 2: unexpected quote in an unquoted field"),
+        ("a\n\"x\n", "line 2: unterminated quoted field"),
+        ("\na,b,a\n1,2,3\n", "line 2: duplicate column name \"a\""),
+    ];
+
+    for (input, msg) in invalid {
+        let mut reader = CsvReader::<_, Intermediate>::new(input.as_bytes());
+
+        let err = reader.next().unwrap().unwrap_err();
+
+        assert_eq!(err.to_string(), msg);
+
+        assert!(reader.next().is_none());
+    }
+
+    // the conversions are planned using the description of the target type
+    let input = "x,y\n1,\n2,3.5\n";
+
+    let res = CsvReader::<_, HashMap<String, Option<f64>>>::new(input.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(res[0]["x"], Some(1.0));
+    assert_eq!(res[0]["y"], None);
+    assert_eq!(res[1]["y"], Some(3.5));
+
+    #[derive(Debug, Deserialize, Describe)]
+    struct Flattened {
+        id: String,
+        #[serde(flatten)]
+        extra: Extra,
+    }
+
+    #[derive(Debug, Deserialize, Describe)]
+    struct Extra {
+        count: u32,
+    }
+
+    let input = "id,count\n007,3\n";
+
+    let res = CsvReader::<_, Flattened>::new(input.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res.id, "007");
+    assert_eq!(res.extra.count, 3);
+}
+
+#[test]
+fn test_csv_max_record_length() {
+    let input = "a,b\r\n1234,56\n\n\"1\n2\",3\n123,456";
+
+    let res = CsvReader::<_, Intermediate>::new(input.as_bytes())
+        .with_max_record_length(7)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[1].as_map().unwrap()["a"].as_str(), Some("1\n2"));
+
+    let invalid = [
+        ("a,b\n12345,67\n", "line 2: maximum record length exceeded"),
+        (
+            "a,b\n\"1\n23\",45\n",
+            "line 2: maximum record length exceeded",
+        ),
+        ("a,b\n1234,567", "line 2: maximum record length exceeded"),
+        ("abcd,efgh\n", "line 1: maximum record length exceeded"),
+    ];
+
+    for (input, msg) in invalid {
+        let mut reader =
+            CsvReader::<_, Intermediate>::new(input.as_bytes()).with_max_record_length(7);
+
+        let err = reader.next().unwrap().unwrap_err();
+
+        assert_eq!(err.to_string(), msg);
+
+        assert!(reader.next().is_none());
+    }
+}
+
+#[test]
+fn test_csv_writer() {
+    let people = vec![
+        Person {
+            name: String::from("Doe, John"),
+            age: 42,
+            zip: String::from("00123"),
+            active: Some(true),
+            score: Some(1.5),
+        },
+        Person {
+            name: String::from("Multi\n\"line\""),
+            age: 7,
+            zip: String::from("99999"),
+            active: None,
+            score: Some(f64::NAN),
+        },
+    ];
+
+    let mut output = Vec::new();
+
+    csv::to_writer(&mut output, &people).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output.clone()).unwrap(),
+        "name,age,zip code,active,score\r\n\
+        \"Doe, John\",42,00123,true,1.5\r\n\
+        \"Multi\n\"\"line\"\"\",7,99999,,NaN\r\n"
+    );
+
+    let res = CsvReader::<_, Person>::new(&output[..])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(res[0], people[0]);
+    assert!(res[1].score.unwrap().is_nan());
+
+    #[derive(Serialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        name: String,
+        address: Address,
+    }
+
+    let mut writer = CsvWriter::new(Vec::new()).with_delimiter(b'\t');
+
+    let mut row = HashMap::new();
+
+    row.insert("name", "foo\tbar");
+
+    writer.write(&row).unwrap();
+
+    let err = writer
+        .write(&Nested {
+            name: String::from("foo"),
+            address: Address {
+                city: String::from("bar"),
+            },
+        })
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "line 3: address: nested values are not supported"
+    );
+
+    let err = writer
+        .write(&intermediate!({ "tags": [1, 2] }))
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "line 3: tags: nested values are not supported"
+    );
+
+    let err = writer.write(&intermediate!({ "other": 1 })).unwrap_err();
+
+    assert_eq!(err.to_string(), "line 3: other: unknown column");
+
+    let err = writer.write(&1).unwrap_err();
+
+    assert_eq!(err.to_string(), "line 3: CSV record must be a map");
+
+    assert_eq!(writer.line(), 2);
+    assert_eq!(writer.into_inner(), b"name\r\n\"foo\tbar\"\r\n");
+}
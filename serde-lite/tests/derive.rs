@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use std::convert::TryInto;
 
 use serde_lite::{intermediate, Deserialize, Error, Intermediate, Map, Number, Serialize, Update};
@@ -35,6 +37,129 @@ fn test_struct_deserialize() {
     assert!(OuterStruct::deserialize(&Intermediate::None).is_err());
 }
 
+#[test]
+fn test_struct_deserialize_view() {
+    use serde_lite::{arena::Arena, DeserializeView};
+    use serde_lite_derive::DeserializeView;
+
+    #[derive(Debug, PartialEq, DeserializeView)]
+    struct Item {
+        id: u32,
+        #[serde(rename = "itemName")]
+        name: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(skip)]
+        cached: Option<u32>,
+        point: Point,
+        #[serde(flatten)]
+        extra: Extra,
+        #[serde(deserialize_with = "deserialize_custom")]
+        custom: u32,
+    }
+
+    #[derive(Debug, PartialEq, DeserializeView)]
+    struct Point(i32, i32);
+
+    #[derive(Debug, PartialEq, DeserializeView)]
+    struct Extra {
+        count: u32,
+    }
+
+    fn deserialize_custom(val: &Intermediate) -> Result<u32, Error> {
+        u32::deserialize(val).map(|v| v * 2)
+    }
+
+    let input = intermediate!({
+        "id": 1,
+        "itemName": "foo",
+        "point": [1, 2],
+        "count": 3,
+        "custom": 5,
+    });
+
+    let expected = Item {
+        id: 1,
+        name: String::from("foo"),
+        tags: Vec::new(),
+        cached: None,
+        point: Point(1, 2),
+        extra: Extra { count: 3 },
+        custom: 10,
+    };
+
+    let arena = Arena::from_intermediate(&input);
+
+    assert_eq!(Item::deserialize_view(&input).unwrap(), expected);
+    assert_eq!(Item::deserialize_view(&arena.root()).unwrap(), expected);
+
+    let input = intermediate!({
+        "id": "1",
+        "point": [1],
+        "count": 3,
+        "custom": 1,
+    });
+
+    let arena = Arena::from_intermediate(&input);
+
+    let err = Item::deserialize_view(&arena.root()).err().unwrap();
+    let mut leaves = err
+        .leaves()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+
+    leaves.sort();
+
+    assert_eq!(leaves, ["id", "itemName", "point"]);
+}
+
+#[test]
+fn test_borrowed_struct_deserialize() {
+    use std::borrow::Cow;
+
+    use serde_lite::DeserializeBorrowed;
+
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        title: Option<Cow<'a, str>>,
+        inner: Inner<'a>,
+        count: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct Inner<'a>(&'a str, Option<&'a str>);
+
+    let input = intermediate!({
+        "name": "foo",
+        "title": null,
+        "inner": ["bar", "baz"],
+        "count": 3,
+    });
+
+    let res = Borrowed::deserialize_borrowed(&input).unwrap();
+
+    assert_eq!(res.name, "foo");
+    assert_eq!(res.title, None);
+    assert_eq!(res.inner.0, "bar");
+    assert_eq!(res.inner.1, Some("baz"));
+    assert_eq!(res.count, 3);
+
+    let input = intermediate!({
+        "name": 1,
+        "inner": ["bar"],
+        "count": 3,
+    });
+
+    let err = Borrowed::deserialize_borrowed(&input)
+        .err()
+        .unwrap()
+        .to_string();
+
+    assert!(err.contains("name: invalid value"));
+}
+
 #[test]
 fn test_struct_update() {
     let input = intermediate!({
@@ -73,6 +198,161 @@ fn test_struct_update() {
     assert!(instance.update(&Intermediate::None).is_err());
 }
 
+#[test]
+fn test_struct_partial_update() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, Update)]
+    struct Inner {
+        a: u32,
+        b: String,
+    }
+
+    #[derive(Debug, Deserialize, Update)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+        items: Vec<Inner>,
+        map: HashMap<String, Inner>,
+    }
+
+    let inner = |a: u32, b: &str| Inner {
+        a,
+        b: String::from(b),
+    };
+
+    let mut outer = Outer {
+        name: String::from("outer"),
+        inner: inner(1, "one"),
+        items: vec![inner(1, "one"), inner(2, "two"), inner(3, "three")],
+        map: HashMap::from([(String::from("x"), inner(1, "x"))]),
+    };
+
+    let err = outer
+        .update(&intermediate!({
+            "name": "changed",
+            "inner": { "a": 10, "b": 10 },
+            "items": [{ "a": 10 }, { "b": 20 }],
+            "map": { "x": { "a": "10" }, "y": { "a": 1, "b": "y" } },
+        }))
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "inner.b: invalid value (string expected), \
+         items[1].b: invalid value (string expected), \
+         map.x.a: invalid value (unsigned integer expected)"
+    );
+
+    // the object is modified partially
+    assert_eq!(outer.name, "changed");
+    assert_eq!(outer.inner.a, 10);
+    assert_eq!(outer.items[0].a, 10);
+
+    // ... but vectors are not truncated and new map entries are not inserted
+    assert_eq!(outer.items.len(), 3);
+    assert_eq!(outer.map.len(), 1);
+}
+
+#[test]
+fn test_struct_atomic_update() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    struct Inner {
+        a: u32,
+        b: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+        items: Vec<Inner>,
+        map: HashMap<String, Inner>,
+    }
+
+    let inner = |a: u32, b: &str| Inner {
+        a,
+        b: String::from(b),
+    };
+
+    let original = Outer {
+        name: String::from("outer"),
+        inner: inner(1, "one"),
+        items: vec![inner(1, "one"), inner(2, "two"), inner(3, "three")],
+        map: HashMap::from([(String::from("x"), inner(1, "x"))]),
+    };
+
+    let mut outer = original.clone();
+
+    let err = outer
+        .try_update_atomic(&intermediate!({
+            "name": "changed",
+            "inner": { "a": 10, "b": 10 },
+            "map": { "x": { "a": "10" } },
+        }))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::NamedFieldErrors(_)));
+    assert_eq!(outer, original);
+
+    // errors in deeply nested values
+    let err = outer
+        .try_update_atomic(&intermediate!({
+            "name": "changed",
+            "items": [{ "a": 5 }, { "a": 6 }, { "a": 7 }, { "a": 8 }],
+        }))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "items[3].b: missing field");
+    assert_eq!(outer, original);
+
+    // successful updates are applied
+    outer
+        .try_update_atomic(&intermediate!({
+            "inner": { "b": "updated" },
+            "items": [{ "a": 5 }],
+            "map": { "y": { "a": 2, "b": "y" } },
+        }))
+        .unwrap();
+
+    assert_eq!(outer.inner, inner(1, "updated"));
+    assert_eq!(outer.items, [inner(5, "one")]);
+    assert_eq!(outer.map.len(), 2);
+    assert_eq!(outer.map["y"], inner(2, "y"));
+}
+
+#[test]
+fn test_vec_update() {
+    #[derive(Debug, PartialEq, Deserialize, Update)]
+    struct Inner {
+        a: u32,
+        b: String,
+    }
+
+    let inner = |a: u32, b: &str| Inner {
+        a,
+        b: String::from(b),
+    };
+
+    let mut items = vec![inner(1, "one"), inner(2, "two")];
+
+    items
+        .update(&intermediate!([{ "a": 3 }, { "a": 4 }, { "a": 5, "b": "five" }]))
+        .unwrap();
+
+    assert_eq!(items, [inner(3, "one"), inner(4, "two"), inner(5, "five")]);
+
+    // vectors are resized only if all elements have been updated
+    let err = items
+        .update(&intermediate!([{ "a": 6 }, { "a": 7 }, { "a": 8 }, { "a": 9 }]))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "[3].b: missing field");
+    assert_eq!(items.len(), 3);
+}
+
 #[test]
 fn test_tuple_struct_deserialize() {
     let input1 = intermediate!(10);
@@ -242,7 +522,7 @@ fn test_enum_deserialize() {
     assert!(TestEnum::deserialize(&input7).is_err());
 
     if let TestEnum::Variant4(arr) = output8 {
-        assert_eq!(arr.as_slice(), &[][..]);
+        assert_eq!(arr.as_slice(), &[0u32; 0][..]);
     } else {
         panic!("output8 test failed");
     }
@@ -254,6 +534,66 @@ fn test_enum_deserialize() {
     }
 }
 
+#[test]
+fn test_enum_deserialize_view() {
+    use serde_lite::{arena::Arena, DeserializeView};
+    use serde_lite_derive::DeserializeView;
+
+    #[derive(Debug, PartialEq, DeserializeView)]
+    enum Kind {
+        Plain,
+        Wrapped(u32),
+        Named { value: String },
+    }
+
+    let input = intermediate!(["Plain", {"Wrapped": 3}, {"Named": {"value": "bar"}}]);
+    let arena = Arena::from_intermediate(&input);
+
+    assert_eq!(
+        Vec::<Kind>::deserialize_view(&arena.root()).unwrap(),
+        [
+            Kind::Plain,
+            Kind::Wrapped(3),
+            Kind::Named {
+                value: String::from("bar")
+            }
+        ]
+    );
+
+    let input = intermediate!("Unknown");
+
+    assert!(Kind::deserialize_view(&input).is_err());
+}
+
+#[test]
+fn test_borrowed_enum_deserialize() {
+    use serde_lite::DeserializeBorrowed;
+
+    #[derive(Deserialize)]
+    enum Kind<'a> {
+        Named { name: &'a str },
+        Unit,
+    }
+
+    let input = intermediate!({
+        "Named": {
+            "name": "foo",
+        },
+    });
+
+    match Kind::deserialize_borrowed(&input).unwrap() {
+        Kind::Named { name } => assert_eq!(name, "foo"),
+        Kind::Unit => panic!("unexpected variant"),
+    }
+
+    let input = intermediate!("Unit");
+
+    assert!(matches!(
+        Kind::deserialize_borrowed(&input).unwrap(),
+        Kind::Unit
+    ));
+}
+
 #[test]
 fn test_enum_update() {
     let input1 = intermediate!("Variant1");
@@ -348,7 +688,7 @@ fn test_enum_update() {
     instance.update(&input8).unwrap();
 
     if let TestEnum::Variant4(arr) = &instance {
-        assert_eq!(arr.as_slice(), &[][..]);
+        assert_eq!(arr.as_slice(), &[0u32; 0][..]);
     } else {
         panic!("test failed");
     }
@@ -474,6 +814,57 @@ fn test_adjacently_tagged_enum_deserialize_and_update() {
     }
 }
 
+#[test]
+fn test_adjacently_tagged_enum_deserialize_view() {
+    use serde_lite::{arena::Arena, DeserializeView};
+    use serde_lite_derive::DeserializeView;
+
+    #[derive(Debug, PartialEq, DeserializeView)]
+    #[serde(tag = "type", content = "data")]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Rect { width: u32, height: u32 },
+    }
+
+    let input = intermediate!({
+        "type": "Rect",
+        "data": {
+            "width": 3,
+            "height": 4,
+        },
+    });
+
+    let arena = Arena::from_intermediate(&input);
+
+    assert_eq!(
+        Shape::deserialize_view(&arena.root()).unwrap(),
+        Shape::Rect {
+            width: 3,
+            height: 4,
+        }
+    );
+
+    let input = intermediate!({
+        "type": "Empty",
+    });
+
+    assert_eq!(Shape::deserialize_view(&input).unwrap(), Shape::Empty);
+
+    let input = intermediate!({
+        "type": "Circle",
+    });
+
+    let err = Shape::deserialize_view(&input).err().unwrap();
+    let leaves = err
+        .leaves()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(leaves, ["data"]);
+}
+
 #[test]
 fn test_struct_serialize() {
     #[derive(Serialize)]
@@ -518,6 +909,167 @@ fn test_struct_serialize() {
     assert_eq!(get_str_field(map, "inner2"), "world");
 }
 
+#[test]
+fn test_struct_serialize_to() {
+    use std::collections::HashMap;
+
+    fn serialize_custom(v: &u32) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::from(v.to_string()))
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        a: u32,
+        #[serde(rename = "B")]
+        b: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        #[serde(flatten)]
+        inner: Inner,
+        #[serde(skip)]
+        skipped: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        optional: Option<u32>,
+        #[serde(serialize_with = "serialize_custom")]
+        custom: u32,
+        #[serde(non_finite = "string")]
+        float: f64,
+        tuple: Tuple,
+        unit: Unit,
+        map: HashMap<String, Vec<u8>>,
+    }
+
+    #[derive(Serialize)]
+    struct Tuple(u32, &'static str);
+
+    #[derive(Serialize)]
+    struct Unit;
+
+    let mut map = HashMap::new();
+
+    map.insert(String::from("foo"), vec![1, 2]);
+    map.insert(String::from("bar"), Vec::new());
+
+    let mut val = Outer {
+        inner: Inner {
+            a: 1,
+            b: Some(String::from("quote \" and\nnew line")),
+        },
+        skipped: 2,
+        optional: None,
+        custom: 3,
+        float: f64::INFINITY,
+        tuple: Tuple(4, "five"),
+        unit: Unit,
+        map,
+    };
+
+    let expected = serialize_to_json(&val);
+
+    assert_eq!(expected["B"], "quote \" and\nnew line");
+    assert_eq!(expected["custom"], "3");
+    assert_eq!(expected["float"], "Infinity");
+    assert_eq!(expected["tuple"], serde_json::json!([4, "five"]));
+    assert!(expected.get("optional").is_none());
+    assert!(expected.get("skipped").is_none());
+
+    val.optional = Some(13);
+    val.skipped = 14;
+
+    let expected = serialize_to_json(&val);
+
+    assert_eq!(expected["optional"], 13);
+
+    #[derive(Serialize)]
+    struct BadFlatten {
+        #[serde(flatten)]
+        inner: u32,
+    }
+
+    let err = serde_lite::json::to_string(&vec![BadFlatten { inner: 1 }]).unwrap_err();
+
+    assert_eq!(err.to_string(), "[0].inner: field cannot be flattened");
+}
+
+#[test]
+fn test_serialize_to_flatten_collisions() {
+    use std::collections::HashMap;
+
+    use serde_lite::json;
+
+    #[derive(Serialize)]
+    struct Inner {
+        a: u32,
+        b: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        a: u32,
+        #[serde(flatten)]
+        inner: Inner,
+    }
+
+    #[derive(Serialize)]
+    #[serde(compact)]
+    struct CompactOuter {
+        a: u32,
+        #[serde(flatten)]
+        inner: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct WithMap {
+        x: u32,
+        #[serde(flatten)]
+        other: HashMap<String, Option<u32>>,
+    }
+
+    /// Helper.
+    fn check<T>(val: &T, expected: serde_json::Value)
+    where
+        T: Serialize,
+    {
+        let streamed = json::to_string(val).unwrap();
+
+        for key in expected.as_object().unwrap().keys() {
+            assert_eq!(streamed.matches(&format!("\"{}\":", key)).count(), 1);
+        }
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&streamed).unwrap(),
+            serde_json::to_value(val.serialize().unwrap()).unwrap()
+        );
+
+        assert_eq!(
+            serde_json::to_value(val.serialize().unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    let inner = Inner { a: 2, b: 3 };
+
+    check(
+        &Outer { a: 1, inner },
+        serde_json::json!({ "a": 2, "b": 3 }),
+    );
+
+    let inner = Inner { a: 2, b: 3 };
+
+    check(
+        &CompactOuter { a: 1, inner },
+        serde_json::json!({ "a": 2, "b": 3 }),
+    );
+
+    let mut other = HashMap::new();
+
+    other.insert(String::from("x"), None);
+
+    check(&WithMap { x: 5, other }, serde_json::json!({ "x": null }));
+}
+
 #[test]
 fn test_externally_tagged_enum_serialize() {
     #[derive(Serialize)]
@@ -617,6 +1169,36 @@ fn test_externally_tagged_enum_serialize() {
     assert_eq!(n, 50);
 }
 
+#[test]
+fn test_externally_tagged_enum_serialize_to() {
+    #[derive(Serialize)]
+    enum External {
+        Unit,
+        Newtype(Option<u32>),
+        Tuple(u32, u32),
+        Struct { x: u32 },
+    }
+
+    let val = vec![
+        External::Unit,
+        External::Newtype(None),
+        External::Newtype(Some(6)),
+        External::Tuple(7, 8),
+        External::Struct { x: 9 },
+    ];
+
+    assert_eq!(
+        serialize_to_json(&val),
+        serde_json::json!([
+            "Unit",
+            "Newtype",
+            { "Newtype": 6 },
+            { "Tuple": [7, 8] },
+            { "Struct": { "x": 9 } },
+        ])
+    );
+}
+
 #[test]
 fn test_internally_tagged_enum_serialize() {
     #[derive(Serialize)]
@@ -723,6 +1305,47 @@ fn test_internally_tagged_enum_serialize() {
     assert!(instance.serialize().is_err());
 }
 
+#[test]
+fn test_internally_tagged_enum_serialize_to() {
+    #[derive(Serialize)]
+    struct Inner {
+        a: u32,
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { x: u32 },
+        Newtype(Inner),
+    }
+
+    let val = vec![
+        Internal::Unit,
+        Internal::Struct { x: 10 },
+        Internal::Newtype(Inner { a: 11 }),
+    ];
+
+    assert_eq!(
+        serialize_to_json(&val),
+        serde_json::json!([
+            { "type": "Unit" },
+            { "type": "Struct", "x": 10 },
+            { "type": "Newtype", "a": 11 },
+        ])
+    );
+
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum BadInternal {
+        Newtype(u32),
+    }
+
+    let err = serde_lite::json::to_string(&BadInternal::Newtype(1)).unwrap_err();
+
+    assert_eq!(err.to_string(), "enum cannot be tagged internally");
+}
+
 #[test]
 fn test_adjacently_tagged_enum_serialize() {
     #[derive(Serialize)]
@@ -762,6 +1385,23 @@ fn test_adjacently_tagged_enum_serialize() {
     assert_eq!(arr[1].as_str().unwrap(), "asdf");
 }
 
+#[test]
+fn test_adjacently_tagged_enum_serialize_to() {
+    #[derive(Serialize)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(u32),
+    }
+
+    let val = vec![Adjacent::Unit, Adjacent::Newtype(12)];
+
+    assert_eq!(
+        serialize_to_json(&val),
+        serde_json::json!([{ "t": "Unit", "c": null }, { "t": "Newtype", "c": 12 }])
+    );
+}
+
 #[test]
 #[allow(unused_variables)]
 fn test_skip_deserializing() {
@@ -820,6 +1460,8 @@ fn test_skip_serializing() {
     assert_eq!(get_unsigned_int_field(map, "field1"), 1);
     assert_eq!(get_unsigned_int_field(map, "field4"), 4);
 
+    drop(data);
+
     instance.field4 = None;
 
     let data = instance.serialize().unwrap();
@@ -838,7 +1480,7 @@ fn test_serialize_with() {
         field: CustomType,
     }
 
-    fn serialize_custom_type(val: &CustomType) -> Result<Intermediate, Error> {
+    fn serialize_custom_type(val: &CustomType) -> Result<Intermediate<'_>, Error> {
         let CustomType(inner) = val;
 
         inner.serialize()
@@ -914,32 +1556,1197 @@ fn test_update_with() {
     assert_eq!(val.field.0, 15);
 }
 
-/// Helper.
-fn get_map_field<'a>(map: &'a Map, name: &str) -> &'a Map {
-    map.get(name).unwrap().as_map().unwrap()
-}
+#[test]
+fn test_bytes_serialize() {
+    #[derive(Serialize)]
+    struct TestStruct {
+        #[serde(bytes)]
+        data: Vec<u8>,
+        #[serde(bytes)]
+        fixed: [u8; 2],
+    }
 
-/// Helper.
-fn get_array_field<'a>(map: &'a Map, name: &str) -> &'a [Intermediate] {
-    map.get(name).unwrap().as_array().unwrap()
-}
+    let val = TestStruct {
+        data: vec![1, 2, 3],
+        fixed: [4, 5],
+    };
 
-/// Helper.
-fn get_bool_field(map: &Map, name: &str) -> bool {
-    map.get(name).unwrap().as_bool().unwrap()
+    let data = val.serialize().unwrap();
+    let fields = data.as_map().unwrap();
+
+    assert_eq!(fields.get("data").unwrap().as_bytes(), Some(&[1, 2, 3][..]));
+    assert_eq!(fields.get("fixed").unwrap().as_bytes(), Some(&[4, 5][..]));
 }
 
-/// Helper.
-fn get_unsigned_int_field(map: &Map, name: &str) -> u64 {
-    map.get(name)
-        .unwrap()
-        .as_number()
-        .unwrap()
-        .try_into()
-        .unwrap()
+#[test]
+fn test_bytes_deserialize() {
+    #[derive(Deserialize)]
+    struct TestStruct {
+        #[serde(bytes)]
+        data: Vec<u8>,
+        #[serde(bytes)]
+        fixed: [u8; 2],
+    }
+
+    let input = Intermediate::Map({
+        let mut map = Map::new();
+        map.insert_with_static_key("data", Intermediate::from(&[1u8, 2, 3][..]));
+        map.insert_with_static_key("fixed", Intermediate::from(&[4u8, 5][..]));
+        map
+    });
+
+    let res = TestStruct::deserialize(&input).unwrap();
+
+    assert_eq!(res.data, [1, 2, 3]);
+    assert_eq!(res.fixed, [4, 5]);
+
+    let input = intermediate!({
+        "data": [],
+        "fixed": [1, 2, 3],
+    });
+
+    assert!(TestStruct::deserialize(&input).is_err());
 }
 
-/// Helper.
-fn get_str_field<'a>(map: &'a Map, name: &str) -> &'a str {
+#[test]
+fn test_bytes_update() {
+    #[derive(Deserialize, Update)]
+    struct TestStruct {
+        #[serde(bytes)]
+        data: Vec<u8>,
+        #[serde(bytes)]
+        fixed: [u8; 2],
+    }
+
+    let mut val = TestStruct {
+        data: vec![1, 2, 3],
+        fixed: [4, 5],
+    };
+
+    let input = intermediate!({
+        "data": [6, 7],
+    });
+
+    val.update(&input).unwrap();
+
+    assert_eq!(val.data, [6, 7]);
+    assert_eq!(val.fixed, [4, 5]);
+}
+
+#[test]
+fn test_with_base64() {
+    #[derive(Serialize, Deserialize)]
+    struct TestStruct {
+        #[serde(with = "serde_lite::base64::standard")]
+        data: Vec<u8>,
+    }
+
+    let val = TestStruct {
+        data: b"foo".to_vec(),
+    };
+
+    let data = val.serialize().unwrap();
+    let fields = data.as_map().unwrap();
+
+    assert_eq!(get_str_field(fields, "data"), "Zm9v");
+
+    let res = TestStruct::deserialize(&data).unwrap();
+
+    assert_eq!(res.data, b"foo");
+}
+
+#[test]
+fn test_with_hex() {
+    #[derive(Serialize, Deserialize, Update)]
+    struct TestStruct {
+        #[serde(with = "serde_lite::hex::lower")]
+        digest: [u8; 4],
+    }
+
+    let mut val = TestStruct {
+        digest: [0xde, 0xad, 0xbe, 0xef],
+    };
+
+    let data = val.serialize().unwrap();
+    let fields = data.as_map().unwrap();
+
+    assert_eq!(get_str_field(fields, "digest"), "deadbeef");
+
+    let res = TestStruct::deserialize(&data).unwrap();
+
+    assert_eq!(res.digest, [0xde, 0xad, 0xbe, 0xef]);
+
+    let input = intermediate!({
+        "digest": "00000000",
+    });
+
+    drop(data);
+
+    val.update(&input).unwrap();
+
+    assert_eq!(val.digest, [0; 4]);
+}
+
+#[test]
+fn test_non_finite_struct_serialize() {
+    #[derive(Serialize)]
+    struct TestStruct {
+        #[serde(non_finite = "null")]
+        field1: f64,
+        #[serde(non_finite = "string")]
+        field2: Vec<f64>,
+        #[serde(non_finite = "error")]
+        field3: f32,
+        field4: f64,
+    }
+
+    let mut val = TestStruct {
+        field1: f64::NAN,
+        field2: vec![f64::INFINITY, 1.0],
+        field3: 1.0,
+        field4: f64::NAN,
+    };
+
+    let data = val.serialize().unwrap();
+    let fields = data.as_map().unwrap();
+
+    assert!(fields.get("field1").unwrap().is_none());
+    assert_eq!(
+        get_array_field(fields, "field2")[0].as_str(),
+        Some("Infinity")
+    );
+    assert!(fields.get("field4").unwrap().as_number().is_some());
+
+    drop(data);
+
+    val.field3 = f32::NEG_INFINITY;
+
+    assert!(val.serialize().is_err());
+}
+
+#[test]
+fn test_non_finite_tuple_struct_serialize() {
+    #[derive(Serialize)]
+    struct Wrapper(#[serde(non_finite = "null")] f64);
+
+    #[derive(Serialize)]
+    struct Pair(
+        #[serde(non_finite = "string")] f64,
+        #[serde(non_finite = "error")] f64,
+    );
+
+    assert!(Wrapper(f64::NAN).serialize().unwrap().is_none());
+    assert_eq!(
+        serde_lite::json::to_string(&Wrapper(f64::NAN)).unwrap(),
+        "null"
+    );
+
+    assert_eq!(
+        serde_lite::json::to_string(&Pair(f64::NEG_INFINITY, 1.0)).unwrap(),
+        r#"["-Infinity",1.0]"#
+    );
+
+    assert!(Pair(1.0, f64::NAN).serialize().is_err());
+    assert!(serde_lite::json::to_string(&Pair(1.0, f64::NAN)).is_err());
+}
+
+#[test]
+fn test_non_finite_enum_serialize() {
+    #[derive(Serialize)]
+    enum Value {
+        Pair(#[serde(non_finite = "string")] f64, f64),
+    }
+
+    let data = Value::Pair(f64::INFINITY, 2.0).serialize().unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&data).unwrap(),
+        serde_json::json!({ "Pair": ["Infinity", 2.0] })
+    );
+
+    assert_eq!(
+        serde_lite::json::to_string(&Value::Pair(f64::INFINITY, 2.0)).unwrap(),
+        r#"{"Pair":["Infinity",2.0]}"#
+    );
+}
+
+#[test]
+fn test_error_paths_deserialize() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        price: u8,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Order {
+        items: Vec<Item>,
+        totals: HashMap<String, (u8, u8)>,
+    }
+
+    let input = intermediate!({
+        "items": [
+            { "price": 1 },
+            { "price": 300 },
+            { "price": 500 },
+        ],
+        "totals": {
+            "eur": [1, 1000],
+        },
+    });
+
+    let err = Order::deserialize(&input).unwrap_err();
+
+    let paths = err
+        .leaves()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(paths, ["items[1].price", "items[2].price", "totals.eur[1]"]);
+
+    assert_eq!(
+        err.to_string(),
+        "items[1].price: value is out of bounds, items[2].price: value is out of bounds, \
+        totals.eur[1]: value is out of bounds"
+    );
+}
+
+#[test]
+fn test_error_paths_update() {
+    #[derive(Debug, Deserialize, Update)]
+    struct Item {
+        price: u8,
+    }
+
+    #[derive(Debug, Deserialize, Update)]
+    struct Order {
+        items: Vec<Item>,
+    }
+
+    let mut order = Order {
+        items: vec![Item { price: 1 }],
+    };
+
+    let input = intermediate!({
+        "items": [
+            { "price": 1 },
+            { "price": 256 },
+        ],
+    });
+
+    let err = order.update(&input).unwrap_err();
+
+    assert_eq!(err.to_string(), "items[1].price: value is out of bounds");
+}
+
+#[test]
+fn test_error_paths_serialize() {
+    fn serialize_price(_: &u8) -> Result<Intermediate<'static>, Error> {
+        Err(Error::custom_static("unable to serialize"))
+    }
+
+    #[derive(Serialize)]
+    struct Item {
+        #[serde(serialize_with = "serialize_price")]
+        price: u8,
+    }
+
+    let val = vec![Item { price: 1 }, Item { price: 2 }];
+
+    let err = val.serialize().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "[0].price: unable to serialize, [1].price: unable to serialize"
+    );
+}
+
+#[test]
+fn test_compact_struct_serialize() {
+    fn serialize_custom(v: &u32) -> Result<Intermediate<'static>, Error> {
+        Ok(Intermediate::from(v.to_string()))
+    }
+
+    #[derive(Serialize)]
+    #[serde(compact)]
+    struct Inner {
+        a: u32,
+        #[serde(rename = "B")]
+        b: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(compact)]
+    struct Outer {
+        #[serde(flatten)]
+        inner: Inner,
+        #[serde(skip)]
+        skipped: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        optional: Option<u32>,
+        #[serde(serialize_with = "serialize_custom")]
+        custom: u32,
+        #[serde(non_finite = "string")]
+        float: f64,
+        list: Vec<Inner>,
+    }
+
+    let mut val = Outer {
+        inner: Inner {
+            a: 1,
+            b: Some(String::from("foo")),
+        },
+        skipped: 2,
+        optional: None,
+        custom: 3,
+        float: f64::INFINITY,
+        list: vec![Inner { a: 4, b: None }],
+    };
+
+    assert_eq!(
+        serialize_to_json(&val),
+        serde_json::json!({
+            "a": 1,
+            "B": "foo",
+            "custom": "3",
+            "float": "Infinity",
+            "list": [{"a": 4, "B": null}],
+        })
+    );
+
+    val.optional = Some(5);
+
+    assert_eq!(serialize_to_json(&val)["optional"], 5);
+}
+
+#[test]
+fn test_compact_struct_deserialize() {
+    fn deserialize_custom(val: &Intermediate) -> Result<u32, Error> {
+        val.as_str()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| Error::invalid_value_static("number string"))
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(compact)]
+    struct Inner {
+        a: u32,
+        #[serde(rename = "B")]
+        b: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(compact)]
+    struct Outer {
+        #[serde(flatten)]
+        inner: Inner,
+        #[serde(skip)]
+        skipped: u32,
+        #[serde(default)]
+        optional: Option<u32>,
+        #[serde(deserialize_with = "deserialize_custom")]
+        custom: u32,
+        float: f64,
+        list: Vec<Inner>,
+    }
+
+    let input = intermediate!({
+        "a": 1,
+        "B": "foo",
+        "skipped": 2,
+        "custom": "3",
+        "float": "Infinity",
+        "list": [{"a": 4, "B": null}],
+    });
+
+    let res = Outer::deserialize(&input).unwrap();
+
+    assert_eq!(
+        res,
+        Outer {
+            inner: Inner {
+                a: 1,
+                b: Some(String::from("foo")),
+            },
+            skipped: 0,
+            optional: None,
+            custom: 3,
+            float: f64::INFINITY,
+            list: vec![Inner { a: 4, b: None }],
+        }
+    );
+
+    let err = Outer::deserialize(&intermediate!({
+        "a": (-1),
+        "custom": "x",
+        "list": [{"a": 1}],
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "a: value is out of bounds, B: missing field, custom: invalid value (number string expected), float: missing field, list[0].B: missing field"
+    );
+
+    assert!(Outer::deserialize(&intermediate!(1)).is_err());
+}
+
+#[test]
+fn test_compact_struct_update() {
+    fn deserialize_custom(val: &Intermediate) -> Result<u32, Error> {
+        val.as_str()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| Error::invalid_value_static("number string"))
+    }
+
+    fn update_custom(v: &mut u32, val: &Intermediate) -> Result<(), Error> {
+        *v += deserialize_custom(val)?;
+
+        Ok(())
+    }
+
+    #[derive(Deserialize, Update)]
+    #[serde(compact)]
+    struct Inner {
+        a: u32,
+        #[serde(rename = "B")]
+        b: Option<String>,
+    }
+
+    #[derive(Deserialize, Update)]
+    #[serde(compact)]
+    struct Outer {
+        #[serde(flatten)]
+        inner: Inner,
+        optional: Option<u32>,
+        #[serde(deserialize_with = "deserialize_custom", update_with = "update_custom")]
+        custom: u32,
+        float: f64,
+        list: Vec<Inner>,
+    }
+
+    let mut val = Outer {
+        inner: Inner {
+            a: 1,
+            b: Some(String::from("foo")),
+        },
+        optional: None,
+        custom: 3,
+        float: f64::INFINITY,
+        list: Vec::new(),
+    };
+
+    val.update(&intermediate!({
+        "B": null,
+        "optional": 5,
+        "custom": "10",
+        "float": 1.5,
+    }))
+    .unwrap();
+
+    assert_eq!(val.inner.a, 1);
+    assert_eq!(val.inner.b, None);
+    assert_eq!(val.optional, Some(5));
+    assert_eq!(val.custom, 13);
+    assert_eq!(val.float, 1.5);
+
+    let err = val.update(&intermediate!({"list": 1})).unwrap_err();
+
+    assert_eq!(err.to_string(), "list: invalid value (array expected)");
+}
+
+#[test]
+fn test_compact_internally_tagged_enum() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize, Update)]
+    #[serde(compact, tag = "type")]
+    enum Tagged {
+        Unit,
+        Struct {
+            x: u32,
+            #[serde(default)]
+            y: String,
+        },
+    }
+
+    let tagged = Tagged::Struct {
+        x: 1,
+        y: String::from("foo"),
+    };
+
+    assert_eq!(
+        serialize_to_json(&tagged),
+        serde_json::json!({"type": "Struct", "x": 1, "y": "foo"})
+    );
+
+    assert_eq!(
+        serde_lite::json::to_string(&Tagged::Unit).unwrap(),
+        r#"{"type":"Unit"}"#
+    );
+
+    let mut deserialized = Tagged::deserialize(&intermediate!({"type": "Struct", "x": 2})).unwrap();
+
+    assert_eq!(
+        deserialized,
+        Tagged::Struct {
+            x: 2,
+            y: String::new()
+        }
+    );
+
+    deserialized.update(&intermediate!({"y": "bar"})).unwrap();
+
+    assert_eq!(
+        deserialized,
+        Tagged::Struct {
+            x: 2,
+            y: String::from("bar")
+        }
+    );
+}
+
+#[test]
+fn test_compact_borrowed_deserialize() {
+    use serde_lite::DeserializeBorrowed;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(compact)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+
+    let val = intermediate!({"name": "foo"});
+
+    let borrowed = Borrowed::deserialize_borrowed(&val).unwrap();
+
+    assert_eq!(borrowed, Borrowed { name: "foo" });
+}
+
+#[test]
+fn test_field_validation_deserialize() {
+    fn validate_even(v: &i32) -> Result<(), Error> {
+        if v % 2 == 0 {
+            Ok(())
+        } else {
+            Err(Error::validation_static("value must be even"))
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Record {
+        #[serde(validate(range(min = -10, max = 10), custom = "validate_even"))]
+        number: i32,
+        #[serde(validate(range(min = 0.5)))]
+        ratio: Option<f64>,
+        #[serde(validate(length(min = 2, max = 4), pattern = r"^\w+$"))]
+        name: String,
+        #[serde(default, validate(non_empty, length(max = 2)))]
+        tags: Vec<String>,
+        note: String,
+    }
+
+    let res = Record::deserialize(&intermediate!({
+        "number": (-4),
+        "ratio": null,
+        "name": "ab_c",
+        "tags": ["a"],
+        "note": "",
+    }))
+    .unwrap();
+
+    assert_eq!(res.number, -4);
+    assert_eq!(res.ratio, None);
+    assert_eq!(res.name, "ab_c");
+    assert_eq!(res.tags, ["a"]);
+    assert_eq!(res.note, "");
+
+    let err = Record::deserialize(&intermediate!({
+        "number": 11,
+        "ratio": 0.25,
+        "name": "a b c d",
+        "tags": [],
+        "note": 1,
+    }))
+    .unwrap_err();
+
+    for (_, err) in err.leaves() {
+        assert!(matches!(err, Error::Validation(_) | Error::InvalidValue(_)));
+    }
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "name: length must be between 2 and 4",
+            "name: value does not match the pattern ^\\w+$",
+            "note: invalid value (string expected)",
+            "number: value must be between -10 and 10",
+            "number: value must be even",
+            "ratio: value must be at least 0.5",
+            "tags: value must not be empty",
+        ]
+    );
+
+    // default values are validated as well
+    let err = Record::deserialize(&intermediate!({
+        "number": 0,
+        "ratio": 1,
+        "name": "abc",
+        "note": "",
+    }))
+    .unwrap_err();
+
+    assert_eq!(get_sorted_errors(err), ["tags: value must not be empty"]);
+}
+
+#[test]
+fn test_field_validation_deserialize_view() {
+    use serde_lite::{arena::Arena, DeserializeView};
+    use serde_lite_derive::DeserializeView;
+
+    #[derive(Debug, DeserializeView)]
+    struct Record {
+        #[serde(validate(range(min = -10, max = 10)))]
+        number: i32,
+        #[serde(validate(length(min = 2, max = 4), pattern = r"^\w+$"))]
+        name: String,
+        #[serde(default, validate(non_empty))]
+        tags: Vec<String>,
+    }
+
+    let arena = Arena::from(&intermediate!({
+        "number": (-4),
+        "name": "ab_c",
+        "tags": ["a"],
+    }));
+
+    let res = Record::deserialize_view(&arena.root()).unwrap();
+
+    assert_eq!(res.number, -4);
+    assert_eq!(res.name, "ab_c");
+    assert_eq!(res.tags, ["a"]);
+
+    let arena = Arena::from(&intermediate!({
+        "number": 11,
+        "name": "a b c d",
+    }));
+
+    let err = Record::deserialize_view(&arena.root()).unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "name: length must be between 2 and 4",
+            "name: value does not match the pattern ^\\w+$",
+            "number: value must be between -10 and 10",
+            "tags: value must not be empty",
+        ]
+    );
+}
+
+#[test]
+fn test_field_validation_update() {
+    fn validate_even(v: &i32) -> Result<(), Error> {
+        if v % 2 == 0 {
+            Ok(())
+        } else {
+            Err(Error::validation_static("value must be even"))
+        }
+    }
+
+    #[derive(Debug, Deserialize, Update)]
+    struct Record {
+        #[serde(validate(range(min = -10, max = 10), custom = "validate_even"))]
+        number: i32,
+        #[serde(validate(range(min = 0.5)))]
+        ratio: Option<f64>,
+        #[serde(default, validate(non_empty, length(max = 2)))]
+        tags: Vec<String>,
+        note: String,
+    }
+
+    let mut record = Record {
+        number: -4,
+        ratio: None,
+        tags: Vec::new(),
+        note: String::new(),
+    };
+
+    // only updated fields are validated
+    record
+        .update(&intermediate!({ "number": 2, "ratio": 3 }))
+        .unwrap();
+
+    assert_eq!(record.number, 2);
+    assert_eq!(record.ratio, Some(3.0));
+
+    let err = record
+        .update(&intermediate!({ "number": 3, "tags": ["a", "b", "c"], "note": null }))
+        .unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "note: invalid value (string expected)",
+            "number: value must be even",
+            "tags: length must be at most 2",
+        ]
+    );
+}
+
+#[test]
+fn test_compact_field_validation() {
+    fn validate_even(v: &i32) -> Result<(), Error> {
+        if v % 2 == 0 {
+            Ok(())
+        } else {
+            Err(Error::validation_static("value must be even"))
+        }
+    }
+
+    fn deserialize_note(val: &Intermediate) -> Result<String, Error> {
+        String::deserialize(val).map(|s| s.to_uppercase())
+    }
+
+    #[derive(Debug, Deserialize, Update)]
+    #[serde(compact)]
+    struct Record {
+        #[serde(validate(range(max = 10), custom = "validate_even"))]
+        number: i32,
+        #[serde(rename = "Name", validate(length(min = 2)))]
+        name: Option<String>,
+        #[serde(default, validate(non_empty))]
+        tags: Vec<String>,
+        #[serde(deserialize_with = "deserialize_note", validate(length(max = 3)))]
+        note: String,
+    }
+
+    let err = Record::deserialize(&intermediate!({
+        "number": 4,
+        "Name": null,
+        "note": "abc",
+    }))
+    .unwrap_err();
+
+    assert_eq!(get_sorted_errors(err), ["tags: value must not be empty"]);
+
+    let err = Record::deserialize(&intermediate!({
+        "number": 12,
+        "Name": "a",
+        "tags": ["a"],
+        "note": "abcd",
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "Name: length must be at least 2",
+            "note: length must be at most 3",
+            "number: value must be at most 10",
+        ]
+    );
+
+    let err = Record::deserialize(&intermediate!({
+        "number": "12",
+        "Name": "ab",
+        "tags": ["a"],
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "note: missing field",
+            "number: invalid value (integer expected)"
+        ]
+    );
+
+    let mut record = Record::deserialize(&intermediate!({
+        "number": 2,
+        "Name": null,
+        "tags": ["a"],
+        "note": "abc",
+    }))
+    .unwrap();
+
+    assert_eq!(record.name, None);
+    assert_eq!(record.note, "ABC");
+
+    let err = record
+        .update(&intermediate!({ "number": 1, "tags": [] }))
+        .unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "number: value must be even",
+            "tags: value must not be empty"
+        ]
+    );
+}
+
+#[test]
+fn test_enum_field_validation() {
+    #[derive(Debug, Deserialize, Update)]
+    enum Shape {
+        Rect {
+            #[serde(validate(range(min = 1)))]
+            width: u32,
+        },
+    }
+
+    let err = Shape::deserialize(&intermediate!({ "Rect": { "width": 0 } })).unwrap_err();
+
+    assert_eq!(get_sorted_errors(err), ["width: value must be at least 1"]);
+
+    let mut shape = Shape::Rect { width: 1 };
+
+    let err = shape
+        .update(&intermediate!({ "Rect": { "width": 0 } }))
+        .unwrap_err();
+
+    assert_eq!(get_sorted_errors(err), ["width: value must be at least 1"]);
+}
+
+#[test]
+fn test_pattern_validation_limits() {
+    // patterns exceeding the limits are reported as errors
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Large {
+        #[serde(validate(pattern = "^a{1000000}$"))]
+        name: String,
+    }
+
+    let err = Large::deserialize(&intermediate!({ "name": "a" })).unwrap_err();
+
+    assert_eq!(err.to_string(), "name: invalid pattern (pattern too large)");
+}
+
+#[test]
+fn test_range_validation_int_bounds() {
+    #[derive(Debug, Deserialize)]
+    struct Bounds {
+        #[serde(validate(range(min = 0, max = 10)))]
+        float: f64,
+        #[serde(validate(range(min = -1)))]
+        small_float: Option<f32>,
+        #[serde(validate(range(max = 200)))]
+        int: u8,
+    }
+
+    let res = Bounds::deserialize(&intermediate!({
+        "float": 9.5,
+        "small_float": (-1),
+        "int": 200,
+    }))
+    .unwrap();
+
+    assert_eq!(res.float, 9.5);
+    assert_eq!(res.small_float, Some(-1.0));
+    assert_eq!(res.int, 200);
+
+    let err = Bounds::deserialize(&intermediate!({
+        "float": 10.5,
+        "small_float": (-1.5),
+        "int": 201,
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        get_sorted_errors(err),
+        [
+            "float: value must be between 0 and 10",
+            "int: value must be at most 200",
+            "small_float: value must be at least -1",
+        ]
+    );
+}
+
+#[test]
+fn test_container_validation_deserialize() {
+    use serde_lite::{arena::Arena, DeserializeView};
+    use serde_lite_derive::DeserializeView;
+
+    fn validate_interval(interval: &Interval) -> Result<(), Error> {
+        if interval.start <= interval.end {
+            Ok(())
+        } else {
+            Err(Error::validation_static(
+                "start must not be greater than end",
+            ))
+        }
+    }
+
+    #[derive(Debug, Deserialize, DeserializeView)]
+    #[serde(validate = "validate_interval")]
+    struct Interval {
+        start: u32,
+        #[serde(validate(range(max = 100)))]
+        end: u32,
+    }
+
+    let interval = Interval::deserialize(&intermediate!({
+        "start": 1,
+        "end": 2,
+    }))
+    .unwrap();
+
+    assert_eq!(interval.start, 1);
+    assert_eq!(interval.end, 2);
+
+    let input = intermediate!({ "start": 3, "end": 2 });
+
+    let err = Interval::deserialize(&input).unwrap_err();
+
+    assert_eq!(err.to_string(), "start must not be greater than end");
+
+    let arena = Arena::from(&input);
+
+    let err = Interval::deserialize_view(&arena.root()).unwrap_err();
+
+    assert_eq!(err.to_string(), "start must not be greater than end");
+
+    // the container is not validated if there are any field errors
+    let err = Interval::deserialize(&intermediate!({
+        "start": 3,
+        "end": 200,
+    }))
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "end: value must be at most 100");
+}
+
+#[test]
+fn test_container_validation_update() {
+    fn validate_interval(interval: &Interval) -> Result<(), Error> {
+        if interval.start <= interval.end {
+            Ok(())
+        } else {
+            Err(Error::validation_static(
+                "start must not be greater than end",
+            ))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    #[serde(validate = "validate_interval")]
+    struct Interval {
+        start: u32,
+        end: u32,
+        labels: Vec<String>,
+    }
+
+    let mut interval = Interval {
+        start: 1,
+        end: 5,
+        labels: vec![String::from("a")],
+    };
+
+    interval
+        .update(&intermediate!({ "end": 10, "labels": ["b"] }))
+        .unwrap();
+
+    assert_eq!(interval.end, 10);
+    assert_eq!(interval.labels, ["b"]);
+
+    let before = interval.clone();
+
+    // the update is rolled back if the validation fails
+    let err = interval
+        .update(&intermediate!({ "start": 20, "labels": ["c"] }))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "start must not be greater than end");
+    assert_eq!(interval, before);
+
+    // ... or if the update fails
+    let err = interval
+        .update(&intermediate!({ "start": 2, "end": "x" }))
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "end: invalid value (unsigned integer expected)"
+    );
+    assert_eq!(interval, before);
+}
+
+#[test]
+fn test_nested_container_validation() {
+    fn validate_interval(interval: &Interval) -> Result<(), Error> {
+        if interval.start <= interval.end {
+            Ok(())
+        } else {
+            Err(Error::validation_static(
+                "start must not be greater than end",
+            ))
+        }
+    }
+
+    fn validate_interval_list(list: &IntervalList) -> Result<(), Error> {
+        if list.intervals.len() <= 2 {
+            Ok(())
+        } else {
+            Err(Error::validation_static("too many intervals"))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    #[serde(validate = "validate_interval")]
+    struct Interval {
+        start: u32,
+        end: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    #[serde(compact, validate = "validate_interval_list")]
+    struct IntervalList {
+        intervals: Vec<Interval>,
+    }
+
+    let mut list = IntervalList::deserialize(&intermediate!({
+        "intervals": [
+            { "start": 1, "end": 2 },
+        ],
+    }))
+    .unwrap();
+
+    let err = IntervalList::deserialize(&intermediate!({
+        "intervals": [
+            { "start": 1, "end": 2 },
+            { "start": 3, "end": 2 },
+        ],
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "intervals[1]: start must not be greater than end"
+    );
+
+    let before = list.clone();
+
+    let err = list
+        .update(&intermediate!({
+            "intervals": [
+                { "start": 1, "end": 2 },
+                { "start": 1, "end": 2 },
+                { "start": 1, "end": 2 },
+            ],
+        }))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "too many intervals");
+    assert_eq!(list, before);
+}
+
+#[test]
+fn test_enum_container_validation() {
+    fn validate_shape(shape: &Shape) -> Result<(), Error> {
+        match shape {
+            Shape::Rect { width, height } if width * height > 100 => {
+                Err(Error::validation_static("area is too large"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Update)]
+    #[serde(tag = "type", validate = "validate_shape")]
+    enum Shape {
+        Empty,
+        Rect { width: u32, height: u32 },
+    }
+
+    let err = Shape::deserialize(&intermediate!({
+        "type": "Rect",
+        "width": 20,
+        "height": 20,
+    }))
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "area is too large");
+
+    let mut shape = Shape::Rect {
+        width: 5,
+        height: 5,
+    };
+
+    let err = shape.update(&intermediate!({ "width": 50 })).unwrap_err();
+
+    assert_eq!(err.to_string(), "area is too large");
+    assert_eq!(
+        shape,
+        Shape::Rect {
+            width: 5,
+            height: 5
+        }
+    );
+
+    shape.update(&intermediate!({ "type": "Empty" })).unwrap();
+
+    assert_eq!(shape, Shape::Empty);
+}
+
+/// Helper.
+fn get_map_field<'a>(map: &'a Map<'a>, name: &str) -> &'a Map<'a> {
+    map.get(name).unwrap().as_map().unwrap()
+}
+
+/// Helper.
+fn get_array_field<'a>(map: &'a Map<'a>, name: &str) -> &'a [Intermediate<'a>] {
+    map.get(name).unwrap().as_array().unwrap()
+}
+
+/// Helper.
+fn get_bool_field(map: &Map, name: &str) -> bool {
+    map.get(name).unwrap().as_bool().unwrap()
+}
+
+/// Helper.
+fn get_unsigned_int_field(map: &Map, name: &str) -> u64 {
+    map.get(name)
+        .unwrap()
+        .as_number()
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+/// Helper.
+fn get_str_field<'a>(map: &'a Map, name: &str) -> &'a str {
     map.get(name).unwrap().as_str().unwrap()
 }
+
+/// Helper.
+fn serialize_to_json<T>(val: &T) -> serde_json::Value
+where
+    T: Serialize,
+{
+    let streamed = serde_lite::json::to_string(val).unwrap();
+
+    let expected = serde_json::to_value(val.serialize().unwrap()).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&streamed).unwrap(),
+        expected
+    );
+
+    expected
+}
+
+/// Helper.
+fn get_sorted_errors(err: Error) -> Vec<String> {
+    let mut res = err
+        .leaves()
+        .into_iter()
+        .map(|(path, err)| format!("{}: {}", path, err))
+        .collect::<Vec<_>>();
+
+    res.sort();
+    res
+}
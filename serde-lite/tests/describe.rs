@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use serde_lite::{
+    describe::{Enum, EnumRepr, Fields, Shape, Struct},
+    Describe, Intermediate,
+};
+
+use serde_lite_derive::{Describe, Serialize};
+
+/// Helper.
+fn get_struct(shape: Shape) -> Struct {
+    if let Shape::Struct(s) = shape {
+        s
+    } else {
+        panic!("struct expected, got: {:?}", shape);
+    }
+}
+
+/// Helper.
+fn get_enum(shape: Shape) -> Enum {
+    if let Shape::Enum(e) = shape {
+        e
+    } else {
+        panic!("enum expected, got: {:?}", shape);
+    }
+}
+
+#[test]
+fn test_describe_primitives() {
+    assert!(matches!(<()>::describe(), Shape::Null));
+    assert!(matches!(bool::describe(), Shape::Bool));
+    assert!(matches!(f64::describe(), Shape::Float));
+    assert!(matches!(char::describe(), Shape::Char));
+    assert!(matches!(<&str>::describe(), Shape::String));
+    assert!(matches!(Intermediate::describe(), Shape::Any));
+
+    assert!(matches!(
+        u8::describe(),
+        Shape::Integer { min: 0, max: 255 }
+    ));
+    assert!(matches!(
+        i16::describe(),
+        Shape::Integer {
+            min: -32768,
+            max: 32767
+        }
+    ));
+
+    let Shape::Option(inner) = Option::<Box<String>>::describe() else {
+        panic!("option expected");
+    };
+
+    assert!(matches!(inner(), Shape::String));
+
+    assert!(matches!(Vec::<u32>::describe(), Shape::Seq(_)));
+    assert!(matches!(<[u32; 3]>::describe(), Shape::Array(_, 3)));
+    assert!(matches!(HashMap::<String, u32>::describe(), Shape::Map(_)));
+
+    let Shape::Tuple(elements) = <(u32, String, bool)>::describe() else {
+        panic!("tuple expected");
+    };
+
+    assert_eq!(elements.len(), 3);
+    assert!(matches!(elements[1](), Shape::String));
+}
+
+#[test]
+fn test_describe_struct() {
+    fn describe_point() -> Shape {
+        Shape::Tuple(vec![f64::describe, f64::describe])
+    }
+
+    fn serialize_point(p: &(f64, f64)) -> Result<Intermediate<'static>, serde_lite::Error> {
+        Ok(Intermediate::Array(vec![p.0.into(), p.1.into()]))
+    }
+
+    #[derive(Describe, Serialize)]
+    struct Extra {
+        note: String,
+    }
+
+    #[derive(Describe, Serialize)]
+    struct Record<T>
+    where
+        T: Describe + serde_lite::Serialize,
+    {
+        #[serde(rename = "recordId")]
+        id: u64,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(skip)]
+        cache: std::cell::Cell<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parent: Option<Box<Record<T>>>,
+        #[serde(flatten)]
+        extra: Extra,
+        #[serde(bytes)]
+        data: Vec<u8>,
+        #[serde(serialize_with = "serialize_point")]
+        custom: (f64, f64),
+        #[serde(serialize_with = "serialize_point", describe_with = "describe_point")]
+        point: (f64, f64),
+        value: T,
+    }
+
+    let record = get_struct(Record::<bool>::describe());
+
+    assert_eq!(record.name(), "Record");
+    assert!(record.type_name().ends_with("Record<bool>"));
+
+    let Fields::Named(fields) = record.fields() else {
+        panic!("named fields expected");
+    };
+
+    let names = fields.iter().map(|f| f.name()).collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        ["recordId", "tags", "cache", "parent", "extra", "data", "custom", "point", "value"]
+    );
+
+    assert!(!fields[0].is_optional());
+    assert!(matches!(fields[0].shape(), Shape::Integer { min: 0, .. }));
+
+    assert!(fields[1].is_optional());
+    assert!(fields[1].has_default());
+
+    assert!(fields[2].is_skipped());
+    assert!(fields[2].is_optional());
+    assert!(matches!(fields[2].shape(), Shape::Any));
+
+    assert!(fields[3].skip_serializing_if());
+    assert!(!fields[3].is_optional());
+
+    // recursive types are described lazily
+    let Shape::Option(parent) = fields[3].shape() else {
+        panic!("option expected");
+    };
+
+    assert_eq!(get_struct(parent()).type_name(), record.type_name());
+
+    assert!(fields[4].is_flattened());
+    assert_eq!(get_struct(fields[4].shape()).name(), "Extra");
+
+    assert!(matches!(fields[5].shape(), Shape::Bytes));
+    assert!(matches!(fields[6].shape(), Shape::Any));
+    assert!(matches!(fields[7].shape(), Shape::Tuple(_)));
+    assert!(matches!(fields[8].shape(), Shape::Bool));
+
+    #[derive(Describe)]
+    struct Unit;
+
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Newtype(String);
+
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Pair(u8, String);
+
+    assert!(matches!(
+        get_struct(Unit::describe()).fields(),
+        Fields::Unit
+    ));
+
+    let newtype = get_struct(Newtype::describe());
+
+    let Fields::Unnamed(fields) = newtype.fields() else {
+        panic!("unnamed fields expected");
+    };
+
+    assert_eq!(fields.len(), 1);
+    assert!(matches!(fields[0](), Shape::String));
+
+    let pair = get_struct(Pair::describe());
+
+    assert!(matches!(pair.fields(), Fields::Unnamed(f) if f.len() == 2));
+}
+
+#[test]
+fn test_describe_enum() {
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        #[serde(rename = "newtype")]
+        Newtype(u32),
+        Tuple(u32, String),
+        Struct {
+            #[serde(default)]
+            a: u32,
+        },
+    }
+
+    #[derive(Describe)]
+    #[serde(tag = "type")]
+    #[allow(dead_code)]
+    enum Internal {
+        A { a: u32 },
+    }
+
+    #[derive(Describe)]
+    #[serde(tag = "t", content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        A(u32),
+    }
+
+    let external = get_enum(External::describe());
+
+    assert_eq!(external.name(), "External");
+    assert_eq!(external.repr(), EnumRepr::External);
+
+    let names = external
+        .variants()
+        .iter()
+        .map(|v| v.name())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["Unit", "newtype", "Tuple", "Struct"]);
+
+    let variants = external.variants();
+
+    assert!(matches!(variants[0].fields(), Fields::Unit));
+    assert!(matches!(variants[1].fields(), Fields::Unnamed(f) if f.len() == 1));
+    assert!(matches!(variants[2].fields(), Fields::Unnamed(f) if f.len() == 2));
+
+    let Fields::Named(fields) = variants[3].fields() else {
+        panic!("named fields expected");
+    };
+
+    assert!(fields[0].has_default());
+
+    assert_eq!(
+        get_enum(Internal::describe()).repr(),
+        EnumRepr::Internal { tag: "type" }
+    );
+
+    assert_eq!(
+        get_enum(Adjacent::describe()).repr(),
+        EnumRepr::Adjacent {
+            tag: "t",
+            content: "c"
+        }
+    );
+}
@@ -0,0 +1,59 @@
+export interface Address {
+    street: string;
+    "zip code": string;
+}
+
+export type Status =
+    | "Active"
+    | { suspended: string }
+    | { Moved: [string, Address] }
+    | { Closed: { reason: string } };
+
+export interface Metadata {
+    created: number;
+}
+
+export type Event =
+    | { type: "Created" }
+    | { type: "Updated"; fields: string[] }
+    | { type: "Tagged" } & Metadata;
+
+export type Message =
+    | { kind: "Ping"; data: null }
+    | { kind: "Text"; data: string }
+    | { kind: "Move"; data: { x: number; y: number } };
+
+export interface Customer {
+    id: number;
+    name: string;
+    email?: string;
+    phone: string | null;
+    tags?: string[];
+    addresses: (Address | null)[];
+    location: [number, number];
+    pair: [boolean, string];
+    attributes: Record<string, number>;
+    avatar: number[];
+    parent: Customer | null;
+    status: Status;
+    event: Event;
+    message: Message;
+}
+
+export type Flattened = {
+    name: string;
+} & Metadata & Record<string, boolean>;
+
+export interface Page {
+    items: Customer[];
+    total: number;
+}
+
+export type Id = number;
+
+export interface Page2 {
+    items: Id[];
+    total: number;
+}
+
+export type Empty = null;
@@ -0,0 +1,776 @@
+use std::borrow::Cow;
+
+use serde::de::{
+    value::{BytesDeserializer, Error as ValueError, MapDeserializer, U128Deserializer},
+    IntoDeserializer,
+};
+use serde_lite::{
+    arena::Arena,
+    float::{self, NonFinitePolicy},
+    intermediate,
+    limits::Limits,
+    view::{self, DeserializeView, Kind, ValueView},
+    ByteBuf, Deserialize, Error, Intermediate, Map, Number, Serialize, Update,
+};
+
+#[test]
+fn test_bytes_serde() {
+    let deserializer = BytesDeserializer::<ValueError>::new(&[1, 2, 3]);
+
+    let val = <Intermediate as serde::Deserialize>::deserialize(deserializer).unwrap();
+
+    assert_eq!(val.as_bytes(), Some(&[1, 2, 3][..]));
+
+    let json = serde_json::to_string(&val).unwrap();
+
+    assert_eq!(json, "[1,2,3]");
+}
+
+#[test]
+fn test_byte_buf() {
+    let buf = ByteBuf::from(vec![1, 2, 3]);
+
+    let val = buf.serialize().unwrap();
+
+    assert!(matches!(val, Intermediate::Bytes(Cow::Borrowed(_))));
+
+    let res = ByteBuf::deserialize(&val).unwrap();
+
+    assert_eq!(res.as_slice(), &[1, 2, 3]);
+
+    let res = Vec::<u8>::deserialize(&val).unwrap();
+
+    assert_eq!(res, [1, 2, 3]);
+
+    let res = Vec::<u32>::deserialize(&val).unwrap();
+
+    assert_eq!(res, [1, 2, 3]);
+
+    let res = <[u8; 3]>::deserialize(&val).unwrap();
+
+    assert_eq!(res, [1, 2, 3]);
+
+    let res = Box::<[u8]>::deserialize(&val).unwrap();
+
+    assert_eq!(&*res, &[1, 2, 3]);
+
+    assert!(<[u8; 4]>::deserialize(&val).is_err());
+
+    let mut res = [0u8; 2];
+
+    res.update(&val).unwrap();
+
+    assert_eq!(res, [1, 2]);
+}
+
+#[test]
+fn test_base64() {
+    let cases: &[(&[u8], &str, &str)] = &[
+        (b"", "", ""),
+        (b"f", "Zg==", "Zg"),
+        (b"fo", "Zm8=", "Zm8"),
+        (b"foo", "Zm9v", "Zm9v"),
+        (b"foob", "Zm9vYg==", "Zm9vYg"),
+        (b"\xfb\xff", "+/8=", "-_8"),
+    ];
+
+    for (raw, padded, unpadded) in cases {
+        let val = serde_lite::base64::standard::serialize(*raw).unwrap();
+
+        assert_eq!(val.as_str(), Some(*padded));
+
+        let res: Vec<u8> = serde_lite::base64::standard::deserialize(&val).unwrap();
+
+        assert_eq!(res, *raw);
+
+        let val = serde_lite::base64::url_safe_no_pad::serialize(*raw).unwrap();
+
+        assert_eq!(val.as_str(), Some(*unpadded));
+
+        let res: Box<[u8]> = serde_lite::base64::url_safe_no_pad::deserialize(&val).unwrap();
+
+        assert_eq!(&*res, *raw);
+    }
+
+    let val = serde_lite::base64::url_safe::serialize(b"\xfb\xff").unwrap();
+
+    assert_eq!(val.as_str(), Some("-_8="));
+
+    // non-zero padding bits are rejected
+    let invalid = ["Zg=", "Z", "Zg===", "Z@==", "-_8=", "QR==", "Zm9=", "Zh=="];
+
+    for input in invalid {
+        let res: Result<Vec<u8>, _> =
+            serde_lite::base64::standard::deserialize(&Intermediate::from(input));
+
+        assert!(res.is_err());
+    }
+
+    let res: Vec<u8> =
+        serde_lite::base64::standard::deserialize(&Intermediate::from("QQ==")).unwrap();
+
+    assert_eq!(res, b"A");
+
+    let res: Result<Vec<u8>, _> =
+        serde_lite::base64::url_safe_no_pad::deserialize(&Intermediate::from("QR"));
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_hex() {
+    let val = serde_lite::hex::lower::serialize(&[0x01, 0xab, 0xff]).unwrap();
+
+    assert_eq!(val.as_str(), Some("01abff"));
+
+    let val = serde_lite::hex::upper::serialize(&[0x01, 0xab, 0xff]).unwrap();
+
+    assert_eq!(val.as_str(), Some("01ABFF"));
+
+    let res: [u8; 3] = serde_lite::hex::lower::deserialize(&val).unwrap();
+
+    assert_eq!(res, [0x01, 0xab, 0xff]);
+
+    let res: Result<[u8; 2], _> = serde_lite::hex::lower::deserialize(&val);
+
+    assert!(res.is_err());
+
+    for input in ["0", "0g"] {
+        let res: Result<Vec<u8>, _> =
+            serde_lite::hex::lower::deserialize(&Intermediate::from(input));
+
+        assert!(res.is_err());
+    }
+}
+
+#[test]
+fn test_128bit_numbers() {
+    let val = u128::MAX.serialize().unwrap();
+
+    assert!(matches!(
+        val.as_number(),
+        Some(Number::UnsignedInt128(u128::MAX))
+    ));
+    assert_eq!(u128::deserialize(&val).unwrap(), u128::MAX);
+    assert!(i128::deserialize(&val).is_err());
+    assert!(u64::deserialize(&val).is_err());
+
+    let val = i128::MIN.serialize().unwrap();
+
+    assert_eq!(i128::deserialize(&val).unwrap(), i128::MIN);
+    assert!(u128::deserialize(&val).is_err());
+
+    // values that fit into 64 bits are normalized
+    let val = 10u128.serialize().unwrap();
+
+    assert!(matches!(val.as_number(), Some(Number::UnsignedInt(10))));
+    assert_eq!(u8::deserialize(&val).unwrap(), 10);
+
+    let val = intermediate!({
+        "big": 170141183460469231731687303715884105727i128,
+    });
+
+    let json = serde_json::to_string(&val).unwrap();
+
+    assert_eq!(json, r#"{"big":170141183460469231731687303715884105727}"#);
+
+    let deserializer: U128Deserializer<ValueError> = u128::MAX.into_deserializer();
+
+    let val = <Intermediate as serde::Deserialize>::deserialize(deserializer).unwrap();
+
+    assert_eq!(u128::deserialize(&val).unwrap(), u128::MAX);
+
+    // serde_json parses integers that do not fit into 64 bits as floats
+    let input = u128::MAX.to_string();
+
+    let val = serde_json::from_str::<Intermediate>(&input).unwrap();
+
+    assert!(matches!(val.as_number(), Some(Number::Float(_))));
+    assert!(u128::deserialize(&val).is_err());
+
+    // ... while the json module keeps them intact
+    let val = serde_lite::json::from_str::<Intermediate>(&input).unwrap();
+
+    assert_eq!(u128::deserialize(&val).unwrap(), u128::MAX);
+    assert_eq!(serde_lite::json::to_string(&val).unwrap(), input);
+
+    let input = i128::MIN.to_string();
+
+    let val = serde_lite::json::from_str::<Intermediate>(&input).unwrap();
+
+    assert_eq!(i128::deserialize(&val).unwrap(), i128::MIN);
+}
+
+#[test]
+fn test_non_finite_policy() {
+    let input = intermediate!({
+        "nan": (f64::NAN),
+        "values": [1.5, (f64::INFINITY), (f64::NEG_INFINITY)],
+    });
+
+    let mut val = input.clone();
+
+    NonFinitePolicy::Allow.apply(&mut val).unwrap();
+
+    assert!(f64::deserialize(val.as_map().unwrap().get("nan").unwrap())
+        .unwrap()
+        .is_nan());
+
+    let mut val = input.clone();
+
+    assert!(NonFinitePolicy::Error.apply(&mut val).is_err());
+
+    let mut val = input.clone();
+
+    NonFinitePolicy::Null.apply(&mut val).unwrap();
+
+    let json = serde_json::to_value(&val).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "nan": null,
+            "values": [1.5, null, null],
+        })
+    );
+
+    let mut val = input;
+
+    NonFinitePolicy::String.apply(&mut val).unwrap();
+
+    let json = serde_json::to_value(&val).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "nan": "NaN",
+            "values": [1.5, "Infinity", "-Infinity"],
+        })
+    );
+
+    let res = Vec::<f64>::deserialize(val.as_map().unwrap().get("values").unwrap()).unwrap();
+
+    assert_eq!(res, [1.5, f64::INFINITY, f64::NEG_INFINITY]);
+
+    let res = f32::deserialize(&Intermediate::from("NaN")).unwrap();
+
+    assert!(res.is_nan());
+
+    assert!(f64::deserialize(&Intermediate::from("nan")).is_err());
+}
+
+#[test]
+fn test_strict_f32() {
+    let val = Intermediate::from(1e300);
+
+    assert_eq!(f32::deserialize(&val).unwrap(), f32::INFINITY);
+    assert!(float::strict_f32::deserialize(&val).is_err());
+
+    let val = Intermediate::from(1.5);
+
+    assert_eq!(float::strict_f32::deserialize(&val).unwrap(), 1.5);
+
+    let val = Intermediate::from("-Infinity");
+
+    assert_eq!(
+        float::strict_f32::deserialize(&val).unwrap(),
+        f32::NEG_INFINITY
+    );
+}
+
+#[test]
+fn test_limits() {
+    fn parse(limits: Limits, input: &str) -> Result<Intermediate<'_>, Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(input);
+
+        limits.deserialize(&mut deserializer)
+    }
+
+    let input = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+
+    let res = parse(Limits::new().with_max_depth(64), &input);
+
+    assert!(matches!(res, Err(Error::DepthLimitExceeded)));
+
+    let input = r#"{"a": [1, 2, 3], "b": "foo"}"#;
+
+    let res = parse(Limits::new().with_max_depth(2), input).unwrap();
+
+    assert!(Limits::new().with_max_depth(2).check(&res).is_ok());
+    assert!(matches!(
+        Limits::new().with_max_depth(1).check(&res),
+        Err(Error::DepthLimitExceeded)
+    ));
+
+    let res = parse(Limits::new().with_max_depth(1), input);
+
+    assert!(matches!(res, Err(Error::DepthLimitExceeded)));
+
+    let res = parse(Limits::new().with_max_nodes(5), input);
+
+    assert!(matches!(res, Err(Error::NodeLimitExceeded)));
+
+    let res = parse(Limits::new().with_max_nodes(6), input);
+
+    assert!(res.is_ok());
+
+    let res = parse(Limits::new().with_max_string_length(2), input);
+
+    assert!(matches!(res, Err(Error::StringLengthLimitExceeded)));
+
+    let res = parse(Limits::new().with_max_array_length(2), input);
+
+    assert!(matches!(res, Err(Error::ArrayLengthLimitExceeded)));
+
+    let res = parse(Limits::new().with_max_map_size(1), input);
+
+    assert!(matches!(res, Err(Error::MapSizeLimitExceeded)));
+
+    let res = parse(Limits::new(), "[1, 2");
+
+    assert!(matches!(res, Err(Error::Custom(_))));
+}
+
+#[test]
+fn test_limits_untrusted_hints() {
+    use serde::{
+        de::{MapAccess, SeqAccess, Visitor},
+        forward_to_deserialize_any, Deserializer,
+    };
+
+    /// Deserializer of a sequence or a map with a bogus size hint.
+    struct Hostile {
+        map: bool,
+        remaining: usize,
+    }
+
+    impl<'de> Deserializer<'de> for Hostile {
+        type Error = ValueError;
+
+        fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            if self.map {
+                visitor.visit_map(self)
+            } else {
+                visitor.visit_seq(self)
+            }
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    impl<'de> SeqAccess<'de> for Hostile {
+        type Error = ValueError;
+
+        fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where
+            T: serde::de::DeserializeSeed<'de>,
+        {
+            if self.remaining == 0 {
+                return Ok(None);
+            }
+
+            self.remaining -= 1;
+
+            seed.deserialize(1u8.into_deserializer()).map(Some)
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    impl<'de> MapAccess<'de> for Hostile {
+        type Error = ValueError;
+
+        fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where
+            K: serde::de::DeserializeSeed<'de>,
+        {
+            if self.remaining == 0 {
+                return Ok(None);
+            }
+
+            self.remaining -= 1;
+
+            let key = format!("k{}", self.remaining);
+
+            seed.deserialize(key.into_deserializer()).map(Some)
+        }
+
+        fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::DeserializeSeed<'de>,
+        {
+            seed.deserialize(1u8.into_deserializer())
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    // bogus size hints must not be trusted when preallocating
+    let res = Limits::new()
+        .deserialize(Hostile {
+            map: false,
+            remaining: 3,
+        })
+        .unwrap();
+
+    assert_eq!(res.as_array().map(|arr| arr.len()), Some(3));
+
+    let res = Limits::new()
+        .deserialize(Hostile {
+            map: true,
+            remaining: 3,
+        })
+        .unwrap();
+
+    assert_eq!(res.as_map().map(|map| map.len()), Some(3));
+
+    // strings and bytes are checked before they are copied
+    let res = Limits::new()
+        .with_max_string_length(2)
+        .deserialize("foo".into_deserializer() as serde::de::value::StrDeserializer<ValueError>);
+
+    assert!(matches!(res, Err(Error::StringLengthLimitExceeded)));
+
+    let res = Limits::new()
+        .with_max_string_length(2)
+        .deserialize(BytesDeserializer::<ValueError>::new(&[1, 2, 3]));
+
+    assert!(matches!(res, Err(Error::StringLengthLimitExceeded)));
+
+    // and so are map keys
+    let entries = vec![("foo", 1u32)].into_iter();
+
+    let res = Limits::new()
+        .with_max_string_length(2)
+        .deserialize(MapDeserializer::<_, ValueError>::new(entries));
+
+    assert!(matches!(res, Err(Error::StringLengthLimitExceeded)));
+
+    let entries = vec![(String::from("foo"), 1u32)].into_iter();
+
+    let res = Limits::new()
+        .with_max_string_length(3)
+        .deserialize(MapDeserializer::<_, ValueError>::new(entries))
+        .unwrap();
+
+    assert_eq!(res.as_map().map(|map| map.len()), Some(1));
+}
+
+#[test]
+fn test_deeply_nested_drop_and_clone() {
+    const DEPTH: usize = 1_000_000;
+
+    let mut val = Intermediate::None;
+
+    for i in 0..DEPTH {
+        val = if i % 2 == 0 {
+            Intermediate::Array(vec![val, Intermediate::from(i as u64)])
+        } else {
+            let mut map = Map::new();
+
+            map.insert_with_static_key("inner", val);
+            map.insert_with_static_key("name", Intermediate::from("foo"));
+
+            Intermediate::Map(map)
+        };
+    }
+
+    let cloned = val.clone();
+
+    let mut depth = 0;
+    let mut current = &cloned;
+
+    loop {
+        current = if let Some(arr) = current.as_array() {
+            assert_eq!(arr.len(), 2);
+
+            &arr[0]
+        } else if let Some(map) = current.as_map() {
+            assert_eq!(map.get("name").and_then(|v| v.as_str()), Some("foo"));
+
+            map.get("inner").unwrap()
+        } else {
+            break;
+        };
+
+        depth += 1;
+    }
+
+    assert_eq!(depth, DEPTH);
+
+    // into_owned must not recurse either
+    drop(val.into_owned());
+    drop(cloned);
+
+    let mut map = Map::new();
+
+    for _ in 0..DEPTH {
+        let mut outer = Map::new();
+
+        outer.insert_with_static_key("inner", Intermediate::Map(map));
+
+        map = outer;
+    }
+
+    let cloned = map.clone();
+
+    drop(map);
+    drop(cloned);
+}
+
+#[test]
+fn test_borrowed_strings() {
+    use serde_lite::DeserializeBorrowed;
+
+    let json = r#"{"name": "plain", "escaped": "a\nb", "tags": ["x", "y"], "note": null}"#;
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+
+    let val = Intermediate::from_deserializer(&mut deserializer).unwrap();
+
+    let map = val.as_map().unwrap();
+
+    assert!(matches!(
+        map.get("name"),
+        Some(Intermediate::String(Cow::Borrowed("plain")))
+    ));
+    assert!(matches!(
+        map.get("escaped"),
+        Some(Intermediate::String(Cow::Owned(_)))
+    ));
+
+    let name = <&str>::deserialize_borrowed(map.get("name").unwrap()).unwrap();
+    let escaped = Cow::<str>::deserialize_borrowed(map.get("escaped").unwrap()).unwrap();
+    let missing = Option::<&str>::deserialize_borrowed(map.get("note").unwrap()).unwrap();
+    let tags = Vec::<String>::deserialize_borrowed(map.get("tags").unwrap()).unwrap();
+
+    assert_eq!(name, "plain");
+    assert_eq!(escaped, "a\nb");
+    assert_eq!(missing, None);
+    assert_eq!(tags, ["x", "y"]);
+
+    assert!(<&str>::deserialize_borrowed(&Intermediate::from(1u32)).is_err());
+
+    let owned: Intermediate<'static> = val.clone().into_owned();
+
+    drop(val);
+
+    assert!(matches!(
+        owned.as_map().unwrap().get("name"),
+        Some(Intermediate::String(Cow::Owned(_)))
+    ));
+
+    // the Deserialize implementation does not borrow from the input
+    let val: Intermediate = serde_json::from_reader(json.as_bytes()).unwrap();
+
+    assert!(matches!(
+        val.as_map().unwrap().get("name"),
+        Some(Intermediate::String(Cow::Owned(_)))
+    ));
+
+    let val: Intermediate = serde_json::from_str(json).unwrap();
+
+    assert!(matches!(
+        val.as_map().unwrap().get("name"),
+        Some(Intermediate::String(Cow::Owned(_)))
+    ));
+
+    // owned data is moved rather than copied
+    let val = intermediate!({ "nested": [[(String::from("foo"))]] });
+
+    let ptr = val.as_map().unwrap()["nested"].as_array().unwrap()[0]
+        .as_array()
+        .unwrap()[0]
+        .as_str()
+        .unwrap()
+        .as_ptr();
+
+    let owned = val.into_owned();
+
+    let nested = owned.as_map().unwrap()["nested"].as_array().unwrap()[0]
+        .as_array()
+        .unwrap();
+
+    assert_eq!(nested[0].as_str().map(str::as_ptr), Some(ptr));
+}
+
+#[test]
+fn test_arena() {
+    use std::collections::HashMap;
+
+    let val = intermediate!({
+        "name": "foo",
+        "tags": ["a", "b"],
+        "scores": {
+            "x": 1,
+            "y": 2,
+        },
+        "nested": [[1, 2], [], [3]],
+        "note": null,
+    });
+
+    let val = Intermediate::Map({
+        let mut map = val.into_map().unwrap();
+        map.insert_with_static_key("data", Intermediate::from(&[1u8, 2, 3][..]));
+        map
+    });
+
+    let arena = Arena::from_intermediate(&val);
+    let root = arena.root();
+
+    assert_eq!(root.kind(), Kind::Map);
+    assert_eq!(root.len(), 6);
+    assert_eq!(root.get("name").unwrap().as_str(), Some("foo"));
+    assert_eq!(root.get("data").unwrap().as_bytes(), Some(&[1, 2, 3][..]));
+    assert!(root.get("missing").is_none());
+
+    let nested = root.get("nested").unwrap();
+
+    assert_eq!(
+        nested
+            .element(2)
+            .unwrap()
+            .element(0)
+            .unwrap()
+            .as_number()
+            .map(u64::try_from)
+            .unwrap()
+            .unwrap(),
+        3
+    );
+    assert!(nested.element(3).is_none());
+
+    let roundtrip = arena.to_intermediate();
+
+    assert_eq!(
+        serde_json::to_value(&roundtrip).unwrap(),
+        serde_json::to_value(&val).unwrap()
+    );
+
+    for input in [&root as &dyn ValueView, &val as &dyn ValueView] {
+        let tags = input.get("tags").unwrap();
+        let scores = input.get("scores").unwrap();
+        let nested = input.get("nested").unwrap();
+        let note = input.get("note").unwrap();
+        let data = input.get("data").unwrap();
+
+        assert_eq!(Vec::<String>::deserialize_view(&*tags).unwrap(), ["a", "b"]);
+        assert_eq!(
+            HashMap::<String, u32>::deserialize_view(&*scores).unwrap(),
+            HashMap::from([("x".to_string(), 1), ("y".to_string(), 2)])
+        );
+        assert_eq!(
+            Vec::<Vec<u8>>::deserialize_view(&*nested).unwrap(),
+            [vec![1, 2], vec![], vec![3]]
+        );
+        assert_eq!(Option::<u32>::deserialize_view(&*note).unwrap(), None);
+        assert_eq!(Vec::<u8>::deserialize_view(&*data).unwrap(), [1, 2, 3]);
+
+        let pair: (u8, u8) = view::deserialize(&*nested.iter().next().unwrap().1).unwrap();
+
+        assert_eq!(pair, (1, 2));
+
+        let first = nested.element(0).unwrap();
+
+        assert_eq!(<(u8, u8)>::deserialize_view(&*first).unwrap(), (1, 2));
+        assert_eq!(<(u8,)>::deserialize_view(&*first).unwrap(), (1,));
+        assert_eq!(<[u8; 2]>::deserialize_view(&*first).unwrap(), [1, 2]);
+        assert_eq!(<[u8; 0]>::deserialize_view(&*first).unwrap(), [0u8; 0]);
+        assert!(<(u8, u8, u8)>::deserialize_view(&*first).is_err());
+        assert!(<[u8; 3]>::deserialize_view(&*first).is_err());
+        assert!(<[u8; 1]>::deserialize_view(&*note).is_err());
+
+        let err = <(String, u8)>::deserialize_view(&*first)
+            .err()
+            .unwrap()
+            .to_string();
+
+        assert!(err.starts_with("[0]: invalid value"));
+
+        let err = <[String; 2]>::deserialize_view(&*first)
+            .err()
+            .unwrap()
+            .to_string();
+
+        assert!(err.starts_with("[0]: invalid value"));
+
+        let err = Vec::<u8>::deserialize_view(&*tags)
+            .err()
+            .unwrap()
+            .to_string();
+
+        assert!(err.starts_with("[0]: invalid value"));
+    }
+
+    drop(roundtrip);
+
+    let mut arena = arena;
+
+    arena.load(&intermediate!([true, 1.5]));
+
+    let res = Vec::<Intermediate>::deserialize_view(&arena.root()).unwrap();
+
+    assert_eq!(res[0].as_bool(), Some(true));
+
+    arena.clear();
+
+    assert_eq!(arena.root().kind(), Kind::None);
+
+    // load the arena directly from a deserializer
+    let json = r#"{"name":"foo","tags":["a","b"],"scores":{"x":1,"y":[{"z":2}]},"nested":[[1,2],[],[3]],"note":null}"#;
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+
+    arena.load_from(&mut deserializer).unwrap();
+
+    let root = arena.root();
+
+    assert_eq!(
+        root.get("tags").unwrap().element(1).unwrap().as_str(),
+        Some("b")
+    );
+    assert_eq!(
+        serde_json::to_value(arena.to_intermediate()).unwrap(),
+        serde_json::from_str::<serde_json::Value>(json).unwrap()
+    );
+
+    let arena: Arena = serde_json::from_str("[1, \"foo\"]").unwrap();
+
+    assert_eq!(arena.root().element(1).unwrap().as_str(), Some("foo"));
+
+    let mut arena = arena;
+
+    let mut deserializer = serde_json::Deserializer::from_str("[1, ");
+
+    assert!(arena.load_from(&mut deserializer).is_err());
+    assert_eq!(arena.root().kind(), Kind::None);
+}
+
+#[test]
+fn test_arena_duplicate_keys() {
+    let json = r#"{"a":1,"b":{"c":true},"a":[2,3],"b":null}"#;
+
+    let val: Intermediate = serde_json::from_str(json).unwrap();
+    let arena: Arena = serde_json::from_str(json).unwrap();
+
+    let root = arena.root();
+
+    assert_eq!(root.len(), 2);
+    assert_eq!(root.get("a").unwrap().len(), 2);
+    assert_eq!(root.get("b").unwrap().kind(), Kind::None);
+    assert_eq!(
+        serde_json::to_value(arena.to_intermediate()).unwrap(),
+        serde_json::to_value(&val).unwrap()
+    );
+}
@@ -0,0 +1,412 @@
+use serde_lite::{
+    json::{self, JsonReader},
+    jsonl::{self, Codec, JsonLinesReader, JsonLinesWriter, NativeCodec, OnError},
+    limits::Limits,
+    Error, Intermediate, Number,
+};
+
+use serde_lite_derive::{Deserialize, Serialize};
+
+#[test]
+fn test_read_value() {
+    let inputs = [
+        "null",
+        "true",
+        " false ",
+        "0",
+        "-0",
+        "42",
+        "-42",
+        "18446744073709551615",
+        "-9223372036854775808",
+        "3.25",
+        "-1.5e3",
+        "1E-2",
+        r#""""#,
+        r#""foo \" \\ \/ \b \f \n \r \t \u00e9 \ud83d\ude00 bar""#,
+        "[]",
+        "{}",
+        r#"[1, [2, [3, {"a": []}]], {"b": {"c": null}}]"#,
+        r#"{"foo": 1, "bar": [true, false], "baz": {"qux": "quux"}}"#,
+    ];
+
+    for input in inputs {
+        let val: Intermediate = json::from_str(input).unwrap();
+
+        let expected = serde_json::from_str::<serde_json::Value>(input).unwrap();
+
+        assert_eq!(serde_json::to_value(&val).unwrap(), expected, "{}", input);
+    }
+
+    let val: Intermediate = json::from_str("340282366920938463463374607431768211455").unwrap();
+
+    assert!(matches!(
+        val.as_number(),
+        Some(Number::UnsignedInt128(u128::MAX))
+    ));
+
+    let val: Intermediate = json::from_str("-170141183460469231731687303715884105728").unwrap();
+
+    assert!(matches!(
+        val.as_number(),
+        Some(Number::SignedInt128(i128::MIN))
+    ));
+
+    let invalid = [
+        "",
+        "nul",
+        "01",
+        "1.",
+        ".5",
+        "-",
+        "1e",
+        "+1",
+        "[1,]",
+        "[1 2]",
+        "{\"a\" 1}",
+        "{\"a\": 1,}",
+        "{1: 2}",
+        "\"foo",
+        "\"\\x\"",
+        "\"\\ud83d\"",
+        "\"\t\"",
+        "[1] 2",
+    ];
+
+    for input in invalid {
+        assert!(json::from_str::<Intermediate>(input).is_err(), "{}", input);
+    }
+
+    let err = json::from_str::<Intermediate>("[1, 2 3]").unwrap_err();
+
+    assert_eq!(err.to_string(), "expected `,` or `]` at byte 6");
+
+    let input = "[".repeat(100_000);
+
+    assert!(json::from_str::<Intermediate>(&input).is_err());
+}
+
+#[test]
+fn test_read_value_with_limits() {
+    let limits = Limits::new().with_max_depth(2).with_max_string_length(3);
+
+    let mut reader = JsonReader::new(&b"[[1], \"foo\"]"[..]).with_limits(limits);
+
+    assert!(reader.read_value().is_ok());
+
+    let mut reader = JsonReader::new(&b"[[[1]]]"[..]).with_limits(limits);
+
+    assert!(matches!(
+        reader.read_value(),
+        Err(Error::DepthLimitExceeded)
+    ));
+
+    let mut reader = JsonReader::new(&b"{\"fooo\": 1}"[..]).with_limits(limits);
+
+    assert!(matches!(
+        reader.read_value(),
+        Err(Error::StringLengthLimitExceeded)
+    ));
+
+    let limits = Limits::new().with_max_nodes(3).with_max_map_size(1);
+
+    let mut reader = JsonReader::new(&b"[1, 2, 3]"[..]).with_limits(limits);
+
+    assert!(matches!(reader.read_value(), Err(Error::NodeLimitExceeded)));
+
+    let mut reader = JsonReader::new(&b"{\"a\": 1, \"b\": 2}"[..]).with_limits(limits);
+
+    assert!(matches!(
+        reader.read_value(),
+        Err(Error::MapSizeLimitExceeded)
+    ));
+
+    // skipped values are subject to the limits as well
+    let input = r#"{"skipped": [[1, 2, 3, 4]], "records": [1, 2]}"#;
+
+    let limits = Limits::new().with_max_nodes(4);
+
+    let mut iter = JsonReader::new(input.as_bytes())
+        .with_limits(limits)
+        .into_array_iter::<u32>("/records");
+
+    assert!(matches!(iter.next(), Some(Err(Error::NodeLimitExceeded))));
+
+    // numbers cannot be arbitrarily long
+    let input = format!("[1{}]", "0".repeat(100_000));
+
+    let mut reader = JsonReader::new(input.as_bytes());
+
+    let err = reader.read_value().err().unwrap();
+
+    assert!(err.to_string().contains("number too long"));
+
+    let input = format!("0.{}1", "0".repeat(1000));
+
+    let mut reader = JsonReader::new(input.as_bytes());
+
+    assert!(reader.read_value().is_ok());
+}
+
+#[test]
+fn test_iter_array() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    let mut input = String::from("[");
+
+    for id in 0..1000 {
+        if id > 0 {
+            input.push(',');
+        }
+
+        if id == 17 {
+            input.push_str(r#"{"id": -1, "name": "bad"}"#);
+        } else {
+            input.push_str(&format!(r#"{{"id": {}, "name": "record {}"}}"#, id, id));
+        }
+    }
+
+    input.push(']');
+
+    let res = json::iter_array::<_, Record>(input.as_bytes()).collect::<Vec<_>>();
+
+    assert_eq!(res.len(), 1000);
+
+    for (id, res) in res.iter().enumerate() {
+        if id == 17 {
+            let err = res.as_ref().unwrap_err();
+
+            assert_eq!(err.to_string(), "[17].id: value is out of bounds");
+        } else {
+            let expected = Record {
+                id: id as u32,
+                name: format!("record {}", id),
+            };
+
+            assert_eq!(res.as_ref().unwrap(), &expected);
+        }
+    }
+
+    let input = r#"
+        {
+            "meta": {"skip": ["]", {"}": [1, 2]}]},
+            "a/b": {
+                "records~": [
+                    [],
+                    [{"id": 1, "name": "foo"}, {"id": 2, "name": "bar"}]
+                ]
+            }
+        }
+    "#;
+
+    let res = json::iter_array_at::<_, Record>(input.as_bytes(), "/a~1b/records~0/1")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[1].name, "bar");
+
+    let mut iter = json::iter_array_at::<_, Record>(input.as_bytes(), "/a~1b/records~0/0");
+
+    assert!(iter.next().is_none());
+
+    let mut iter = json::iter_array_at::<_, Record>(input.as_bytes(), "/a~1b/missing");
+
+    let err = iter.next().unwrap().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "JSON pointer \"/a~1b/missing\" does not reference any value"
+    );
+
+    assert!(iter.next().is_none());
+
+    let mut iter = json::iter_array_at::<_, Record>(input.as_bytes(), "/meta");
+
+    assert!(iter.next().unwrap().is_err());
+
+    let input = r#"[1, 2, {"a": 3 4]"#;
+
+    let res = json::iter_array::<_, u32>(input.as_bytes()).collect::<Vec<_>>();
+
+    assert_eq!(res.len(), 3);
+    assert!(res[0].is_ok());
+    assert!(res[1].is_ok());
+
+    let err = res[2].as_ref().unwrap_err();
+
+    assert_eq!(err.to_string(), "[2]: expected `,` or `}` at byte 15");
+
+    let limits = Limits::new().with_max_array_length(2).with_max_depth(1);
+
+    let res = JsonReader::new(&b"[[1], [2], [3]]"[..])
+        .with_limits(limits)
+        .into_array_iter::<Vec<u32>>("")
+        .collect::<Vec<_>>();
+
+    assert_eq!(res.len(), 3);
+    assert!(res[1].is_ok());
+    assert!(matches!(res[2], Err(Error::ArrayLengthLimitExceeded)));
+
+    let res = json::iter_array::<_, u32>(&b"[1] 2"[..]).collect::<Vec<_>>();
+
+    assert!(res[0].is_ok());
+    assert!(res[1].is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LogRecord {
+    level: String,
+    code: u8,
+}
+
+/// Helper.
+fn test_json_lines_with_codec<C>(codec: C)
+where
+    C: Codec + Copy,
+{
+    let records = vec![
+        LogRecord {
+            level: String::from("info"),
+            code: 1,
+        },
+        LogRecord {
+            level: String::from("multi\nline"),
+            code: 2,
+        },
+    ];
+
+    let mut writer = JsonLinesWriter::with_codec(Vec::new(), codec);
+
+    writer.write_all(&records).unwrap();
+
+    assert_eq!(writer.line(), 2);
+
+    let mut output = writer.into_inner();
+
+    assert_eq!(output.iter().filter(|&&b| b == b'\n').count(), 2);
+
+    output.extend_from_slice(b"\r\n   \n");
+    output.extend_from_slice(b"{\"level\": \"warn\", \"code\": 300}\n");
+    output.extend_from_slice(b"{\"level\": \"warn\"\n");
+    output.extend_from_slice(b"\xff\n");
+    output.extend_from_slice(b"{\"level\": \"error\", \"code\": 3}\r\n");
+
+    let res =
+        JsonLinesReader::<_, LogRecord, _>::with_codec(&output[..], codec).collect::<Vec<_>>();
+
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0].as_ref().unwrap(), &records[0]);
+    assert_eq!(res[1].as_ref().unwrap(), &records[1]);
+
+    let err = res[2].as_ref().unwrap_err();
+
+    assert_eq!(err.to_string(), "line 5: code: value is out of bounds");
+
+    if let Error::Line(err) = err {
+        assert_eq!(err.line(), 5);
+
+        let paths = err
+            .error()
+            .leaves()
+            .into_iter()
+            .map(|(path, _)| path.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, ["code"]);
+    } else {
+        panic!("unexpected error: {:?}", err);
+    }
+
+    let mut reader =
+        JsonLinesReader::<_, LogRecord, _>::with_codec(&output[..], codec).on_error(OnError::Skip);
+
+    let res = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[2].level, "error");
+    assert_eq!(reader.skipped(), 3);
+    assert_eq!(reader.line(), 8);
+}
+
+#[test]
+fn test_json_lines() {
+    test_json_lines_with_codec(NativeCodec::new());
+
+    let codec = NativeCodec::new().with_limits(Limits::new().with_max_string_length(5));
+
+    let mut reader = JsonLinesReader::<_, LogRecord, _>::with_codec(
+        &b"{\"level\": \"info\", \"code\": 1}\n{\"level\": \"verbose\", \"code\": 2}\n"[..],
+        codec,
+    );
+
+    assert!(reader.next().unwrap().is_ok());
+
+    let err = reader.next().unwrap().unwrap_err();
+
+    assert_eq!(err.to_string(), "line 2: maximum string length exceeded");
+
+    assert!(reader.next().is_none());
+
+    fn serialize_bad(_: &u32) -> Result<Intermediate<'static>, Error> {
+        Err(Error::custom_static("unable to serialize"))
+    }
+
+    #[derive(Serialize)]
+    struct Bad {
+        #[serde(serialize_with = "serialize_bad")]
+        value: u32,
+    }
+
+    let mut output = Vec::new();
+
+    jsonl::to_writer(&mut output, [1, 2]).unwrap();
+
+    let mut writer = JsonLinesWriter::new(&mut output);
+
+    writer.write(&3).unwrap();
+
+    let err = writer.write(&Bad { value: 4 }).unwrap_err();
+
+    assert_eq!(err.to_string(), "line 2: value: unable to serialize");
+
+    assert_eq!(output, b"1\n2\n3\n");
+
+    // lines exceeding the maximum length are bad lines
+    let mut input = b"[1]\n[22]\n[".to_vec();
+
+    input.extend(b"3".repeat(100_000));
+    input.extend_from_slice(b"]\n[4]\n[55555]");
+
+    let mut reader = JsonLinesReader::<_, (u32,)>::new(&input[..])
+        .with_max_line_length(4)
+        .on_error(OnError::Skip);
+
+    let res = reader
+        .by_ref()
+        .map(|res| res.unwrap().0)
+        .collect::<Vec<_>>();
+
+    assert_eq!(res, [1, 22, 4]);
+    assert_eq!(reader.skipped(), 2);
+    assert_eq!(reader.line(), 5);
+
+    let mut reader = JsonLinesReader::<_, (u32,)>::new(&input[..]).with_max_line_length(4);
+
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+
+    let err = reader.next().unwrap().unwrap_err();
+
+    assert_eq!(err.to_string(), "line 3: maximum line length exceeded");
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn test_json_lines_serde_json() {
+    test_json_lines_with_codec(jsonl::SerdeJsonCodec::new());
+}
@@ -0,0 +1,88 @@
+use serde_lite::pattern::Pattern;
+
+#[test]
+fn test_pattern() {
+    let cases = [
+        ("abc", "xabcx", true),
+        ("^abc$", "xabc", false),
+        ("^a.c$", "abc", true),
+        ("^a.c$", "a\nc", false),
+        ("^[a-c]+$", "abcab", true),
+        ("^[^a-c]+$", "xyz", true),
+        ("^[^a-c]+$", "xaz", false),
+        ("^[]a]+$", "a]a", true),
+        ("^[a-]+$", "a-a", true),
+        (r"^\d{3}-\d{2,}$", "123-45", true),
+        (r"^\d{3}-\d{2,}$", "123-4", false),
+        (r"^\w+@\w+\.com$", "foo_1@bar.com", true),
+        (r"^\w+@\w+\.com$", "foo@bar.org", false),
+        (r"^\S+\s\S+$", "foo bar", true),
+        (r"^[\d.]+$", "1.5", true),
+        ("^(ab|cd)*$", "abcdab", true),
+        ("^(ab|cd)*$", "abc", false),
+        ("^(?:a|b)?c$", "c", true),
+        ("^a{2,3}$", "aaaa", false),
+        ("^a{2,3}$", "aaa", true),
+        ("^(a*)*b$", "aaab", true),
+        ("^colou?r$", "color", true),
+        ("", "anything", true),
+        ("^$", "", true),
+    ];
+
+    for (pattern, input, expected) in cases {
+        let res = Pattern::new(pattern).unwrap().is_match(input);
+
+        assert_eq!(res, expected, "pattern: {}, input: {:?}", pattern, input);
+    }
+
+    let invalid = [
+        "(ab", "ab)", "[ab", "*a", "a{2", "a{3,2}", "[z-a]", r"\p", "(?=a)",
+    ];
+
+    for pattern in invalid {
+        assert!(Pattern::new(pattern).is_err(), "pattern: {}", pattern);
+    }
+}
+
+#[test]
+fn test_pattern_untrusted() {
+    // patterns that take exponential time with a backtracking matcher
+    let input = format!("{}!", "a".repeat(100_000));
+
+    for pattern in [
+        "^(a+)+$",
+        "^(a|a)*$",
+        "^(a*)*$",
+        "(a|aa)+b",
+        "^(a?){50}a{50}$",
+    ] {
+        let pattern = Pattern::new(pattern).unwrap();
+
+        assert!(!pattern.is_match(&input), "pattern: {}", pattern);
+    }
+
+    let input = "a".repeat(100_000);
+
+    assert!(Pattern::new("^(a+)+$").unwrap().is_match(&input));
+    assert!(Pattern::new("^(a|b)*$").unwrap().is_match(&input));
+    assert!(Pattern::new("a$").unwrap().is_match(&input));
+    assert!(!Pattern::new("^b").unwrap().is_match(&input));
+
+    // empty repetitions must not loop forever
+    assert!(Pattern::new("^(){1000000000}$").unwrap().is_match(""));
+
+    // the size and the nesting of patterns are limited
+    let too_large = ["a{1000000}", "(a{1000}){1000}", "a{0,100000}"];
+
+    for pattern in too_large {
+        assert!(Pattern::new(pattern).is_err(), "pattern: {}", pattern);
+    }
+
+    let nested = format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000));
+
+    assert!(Pattern::new(&nested).is_err());
+
+    let nested = format!("{}a{}", "(".repeat(10), ")".repeat(10));
+
+    assert!(Pattern::new(&nested).unwrap().is_match("a"));
+}
@@ -0,0 +1,502 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use serde_lite::{
+    intermediate,
+    schema::{self, Validator},
+    Describe, Error, Intermediate, Serialize,
+};
+
+use serde_lite_derive::{Describe, Serialize};
+
+/// Helper.
+fn json_schema<T>() -> serde_json::Value
+where
+    T: Describe,
+{
+    serde_json::to_value(schema::json_schema::<T>()).unwrap()
+}
+
+#[test]
+fn test_struct_schema() {
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Extra {
+        note: String,
+    }
+
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Item {
+        #[serde(rename = "itemId")]
+        id: u8,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(skip)]
+        cache: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+        #[serde(skip_deserializing)]
+        created: bool,
+        #[serde(flatten)]
+        extra: Extra,
+        #[serde(flatten)]
+        other: HashMap<String, f64>,
+        position: [i8; 2],
+        pair: (char, ()),
+    }
+
+    let expected = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Item",
+        "type": "object",
+        "properties": {
+            "itemId": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "comment": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+            "created": { "type": "boolean" },
+            "note": { "type": "string" },
+            "position": {
+                "type": "array",
+                "items": { "type": "integer", "minimum": -128, "maximum": 127 },
+                "minItems": 2,
+                "maxItems": 2,
+            },
+            "pair": {
+                "type": "array",
+                "prefixItems": [
+                    { "type": "string", "minLength": 1, "maxLength": 1 },
+                    { "type": "null" },
+                ],
+                "minItems": 2,
+                "maxItems": 2,
+            },
+        },
+        "required": ["itemId", "note", "position", "pair"],
+        "additionalProperties": { "type": "number" },
+    });
+
+    assert_eq!(json_schema::<Item>(), expected);
+}
+
+#[test]
+fn test_recursive_schema() {
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Node<T>
+    where
+        T: Describe,
+    {
+        value: T,
+        children: Vec<Node<T>>,
+        next: Option<Box<Link>>,
+        other: Option<Box<Node<u8>>>,
+    }
+
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    struct Link {
+        target: Node<String>,
+    }
+
+    let expected = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Node",
+        "type": "object",
+        "properties": {
+            "value": { "type": "string" },
+            "children": { "type": "array", "items": { "$ref": "#" } },
+            "next": { "anyOf": [{ "$ref": "#/$defs/Link" }, { "type": "null" }] },
+            "other": { "anyOf": [{ "$ref": "#/$defs/Node2" }, { "type": "null" }] },
+        },
+        "required": ["value", "children", "next", "other"],
+        "$defs": {
+            "Link": {
+                "title": "Link",
+                "type": "object",
+                "properties": {
+                    "target": { "$ref": "#" },
+                },
+                "required": ["target"],
+            },
+            "Node2": {
+                "title": "Node",
+                "type": "object",
+                "properties": {
+                    "value": { "type": "integer", "minimum": 0, "maximum": 255 },
+                    "children": { "type": "array", "items": { "$ref": "#/$defs/Node2" } },
+                    "next": { "anyOf": [{ "$ref": "#/$defs/Link" }, { "type": "null" }] },
+                    "other": { "anyOf": [{ "$ref": "#/$defs/Node2" }, { "type": "null" }] },
+                },
+                "required": ["value", "children", "next", "other"],
+            },
+        },
+    });
+
+    assert_eq!(json_schema::<Node<String>>(), expected);
+
+    // non-root types are always referenced
+    let schema = json_schema::<Vec<Link>>();
+
+    assert_eq!(schema["items"], json!({ "$ref": "#/$defs/Link" }));
+    assert_eq!(
+        schema["$defs"]["Link"]["properties"]["target"]["$ref"],
+        "#/$defs/Node"
+    );
+}
+
+#[test]
+fn test_enum_schema() {
+    #[derive(Describe)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        #[serde(rename = "newtype")]
+        Newtype(bool),
+        Struct {
+            a: bool,
+        },
+    }
+
+    let expected = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "External",
+        "oneOf": [
+            { "const": "Unit" },
+            {
+                "type": "object",
+                "properties": { "newtype": { "type": "boolean" } },
+                "required": ["newtype"],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "Struct": {
+                        "type": "object",
+                        "properties": { "a": { "type": "boolean" } },
+                        "required": ["a"],
+                    },
+                },
+                "required": ["Struct"],
+            },
+        ],
+    });
+
+    assert_eq!(json_schema::<External>(), expected);
+
+    #[derive(Describe)]
+    #[serde(tag = "type")]
+    #[allow(dead_code)]
+    enum Internal {
+        Unit,
+        Map(HashMap<String, bool>),
+        Struct { a: bool },
+    }
+
+    let expected = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Internal",
+        "oneOf": [
+            {
+                "type": "object",
+                "properties": { "type": { "const": "Unit" } },
+                "required": ["type"],
+            },
+            {
+                "type": "object",
+                "properties": { "type": { "const": "Map" } },
+                "required": ["type"],
+                "allOf": [{ "type": "object", "additionalProperties": { "type": "boolean" } }],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "type": { "const": "Struct" },
+                    "a": { "type": "boolean" },
+                },
+                "required": ["type", "a"],
+            },
+        ],
+    });
+
+    assert_eq!(json_schema::<Internal>(), expected);
+
+    #[derive(Describe)]
+    #[serde(tag = "t", content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        Unit,
+        Tuple(bool, String),
+    }
+
+    let expected = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Adjacent",
+        "oneOf": [
+            {
+                "type": "object",
+                "properties": {
+                    "t": { "const": "Unit" },
+                    "c": { "type": "null" },
+                },
+                "required": ["t"],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "t": { "const": "Tuple" },
+                    "c": {
+                        "type": "array",
+                        "prefixItems": [{ "type": "boolean" }, { "type": "string" }],
+                        "minItems": 2,
+                        "maxItems": 2,
+                    },
+                },
+                "required": ["t", "c"],
+            },
+        ],
+    });
+
+    assert_eq!(json_schema::<Adjacent>(), expected);
+}
+
+/// Helper.
+fn violations(schema: &Intermediate, value: &Intermediate) -> Vec<String> {
+    let Err(err) = Validator::new(schema).validate(value) else {
+        return Vec::new();
+    };
+
+    let mut res = err
+        .leaves()
+        .into_iter()
+        .map(|(path, err)| format!("{}: {}", path.to_json_pointer(), err))
+        .collect::<Vec<_>>();
+
+    res.sort();
+    res
+}
+
+#[test]
+fn test_validate() {
+    let schema = intermediate!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "integer", "minimum": 1, "exclusiveMaximum": 100 },
+            "name": { "type": "string", "minLength": 2, "maxLength": 4, "pattern": "^[a-z]+$" },
+            "ratio": { "type": ["number", "null"], "exclusiveMinimum": 0, "maximum": 1.5 },
+            "tags": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/Tag" },
+                "maxItems": 2,
+            },
+            "point": {
+                "type": "array",
+                "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+                "items": false,
+            },
+            "kind": { "enum": ["a", "b"] },
+            "version": { "const": 2 },
+            "a/b~c": { "type": "boolean" },
+            "missing": true,
+        },
+        "required": ["id", "name", "missing"],
+        "additionalProperties": false,
+        "$defs": {
+            "Tag": { "type": "string", "minLength": 1 },
+        },
+    });
+
+    let value = intermediate!({
+        "id": 1,
+        "name": "abc",
+        "ratio": null,
+        "tags": ["x"],
+        "point": [1, 2],
+        "kind": "a",
+        "version": 2.0,
+        "a/b~c": true,
+        "missing": null,
+    });
+
+    assert_eq!(violations(&schema, &value), Vec::<String>::new());
+
+    let value = intermediate!({
+        "id": 100,
+        "name": "Abcde",
+        "ratio": 0,
+        "tags": ["", 1, "z"],
+        "point": [1.5, 2, 3],
+        "kind": "c",
+        "version": 3,
+        "a/b~c": "true",
+        "other": 1,
+    });
+
+    assert_eq!(
+        violations(&schema, &value),
+        [
+            "/a~1b~0c: invalid value (boolean expected)",
+            "/id: value must be less than 100",
+            "/kind: value is not one of the allowed values",
+            "/missing: missing field",
+            "/name: string does not match the pattern ^[a-z]+$",
+            "/name: string must be at most 4 characters long",
+            "/other: unknown field",
+            "/point/0: invalid value (integer expected)",
+            "/point/2: value is not allowed",
+            "/ratio: value must be greater than 0",
+            "/tags/0: string must be at least 1 characters long",
+            "/tags/1: invalid value (string expected)",
+            "/tags: array must contain at most 2 items",
+            "/version: value does not match the constant",
+        ]
+    );
+
+    let err = Validator::new(&schema)
+        .validate(&intermediate!(1))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "invalid value (object expected)");
+
+    let schema = intermediate!({ "$ref": "#/$defs/Missing" });
+
+    assert_eq!(
+        violations(&schema, &intermediate!(1)),
+        [": invalid schema reference (#/$defs/Missing)"]
+    );
+}
+
+#[test]
+fn test_validate_combinators() {
+    let schema = intermediate!({
+        "allOf": [
+            { "type": "object", "required": ["a"] },
+            { "type": "object", "required": ["b"] },
+        ],
+        "properties": {
+            "any": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+            "one": { "oneOf": [{ "type": "number" }, { "type": "integer" }] },
+        },
+    });
+
+    let value = intermediate!({ "a": 1, "any": null, "one": 1.5 });
+
+    assert_eq!(violations(&schema, &value), ["/b: missing field"]);
+
+    let value = intermediate!({ "a": 1, "b": 1, "any": 1, "one": 1 });
+
+    assert_eq!(
+        violations(&schema, &value),
+        [
+            "/any: value does not match any of the allowed schemas",
+            "/one: value matches 2 schemas but exactly one is allowed",
+        ]
+    );
+
+    // root violations are kept next to the field violations
+    let schema = intermediate!({
+        "enum": [{ "a": "x" }],
+        "properties": {
+            "a": { "type": "string" },
+        },
+    });
+
+    let value = intermediate!({ "a": 1 });
+
+    assert_eq!(
+        violations(&schema, &value),
+        [
+            "/a: invalid value (string expected)",
+            ": value is not one of the allowed values",
+        ]
+    );
+
+    let validator = Validator::new(&schema);
+
+    for _ in 0..2 {
+        let err = validator.validate(&value).unwrap_err();
+
+        assert!(matches!(err, Error::Multiple(ref errors) if errors.len() == 2));
+    }
+
+    // recursive schemas
+    let schema = intermediate!({
+        "type": "object",
+        "properties": {
+            "value": { "type": "integer" },
+            "children": { "type": "array", "items": { "$ref": "#" } },
+        },
+    });
+
+    let value = intermediate!({
+        "value": 1,
+        "children": [{ "value": 2, "children": [{ "value": "3" }] }],
+    });
+
+    assert_eq!(
+        violations(&schema, &value),
+        ["/children/0/children/0/value: invalid value (integer expected)"]
+    );
+
+    // infinite recursion
+    let schema = intermediate!({ "$ref": "#" });
+
+    let err = Validator::new(&schema)
+        .validate(&intermediate!(1))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::DepthLimitExceeded));
+}
+
+#[test]
+fn test_validate_generated_schema() {
+    #[derive(Describe, Serialize)]
+    struct Node {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        weight: Option<u8>,
+        children: Vec<Node>,
+        kind: Kind,
+    }
+
+    #[derive(Describe, Serialize)]
+    #[serde(tag = "type")]
+    enum Kind {
+        Leaf,
+        Branch { depth: u32 },
+    }
+
+    let schema = schema::json_schema::<Node>();
+
+    let node = Node {
+        name: String::from("root"),
+        weight: None,
+        children: vec![Node {
+            name: String::from("child"),
+            weight: Some(3),
+            children: Vec::new(),
+            kind: Kind::Leaf,
+        }],
+        kind: Kind::Branch { depth: 1 },
+    };
+
+    let value = node.serialize().unwrap();
+
+    assert_eq!(violations(&schema, &value), Vec::<String>::new());
+
+    let value = intermediate!({
+        "name": "root",
+        "weight": 300,
+        "children": [{ "name": "child", "children": [], "kind": { "type": "Other" } }],
+        "kind": { "type": "Branch" },
+    });
+
+    assert_eq!(
+        violations(&schema, &value),
+        [
+            "/children/0/kind: value does not match any of the allowed schemas",
+            "/kind: value does not match any of the allowed schemas",
+            "/weight: value does not match any of the allowed schemas",
+        ]
+    );
+}
@@ -0,0 +1,134 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde_lite::{typescript::Declarations, Describe};
+
+use serde_lite_derive::Describe;
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Address {
+    street: String,
+    #[serde(rename = "zip code")]
+    zip: String,
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Metadata {
+    created: u64,
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Customer {
+    id: u32,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    phone: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip)]
+    cache: u32,
+    #[serde(skip_serializing)]
+    password: String,
+    addresses: Vec<Option<Address>>,
+    location: [f64; 2],
+    pair: (bool, char),
+    attributes: HashMap<String, i64>,
+    #[serde(bytes)]
+    avatar: Vec<u8>,
+    parent: Option<Box<Customer>>,
+    status: Status,
+    event: Event,
+    message: Message,
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Flattened {
+    name: String,
+    #[serde(flatten)]
+    metadata: Metadata,
+    #[serde(flatten)]
+    other: HashMap<String, bool>,
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    #[serde(rename = "suspended")]
+    Suspended(String),
+    Moved(String, Address),
+    Closed {
+        reason: String,
+    },
+}
+
+#[derive(Describe)]
+#[serde(tag = "type")]
+#[allow(dead_code)]
+enum Event {
+    Created,
+    Updated { fields: Vec<String> },
+    Tagged(Metadata),
+}
+
+#[derive(Describe)]
+#[serde(tag = "kind", content = "data")]
+#[allow(dead_code)]
+enum Message {
+    Ping,
+    Text(String),
+    Move { x: i32, y: i32 },
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Page<T>
+where
+    T: Describe,
+{
+    items: Vec<T>,
+    total: usize,
+}
+
+#[derive(Describe)]
+#[allow(dead_code)]
+struct Id(u64);
+
+#[derive(Describe)]
+struct Empty;
+
+#[test]
+fn test_golden_declarations() {
+    let mut declarations = Declarations::new();
+
+    declarations
+        .add::<Customer>()
+        .add::<Flattened>()
+        .add::<Page<Customer>>()
+        .add::<Page<Id>>()
+        .add::<Empty>()
+        .add::<Status>();
+
+    let dir = std::env::temp_dir().join(format!("serde-lite-typescript-{}", std::process::id()));
+
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("types.d.ts");
+
+    declarations
+        .to_writer(fs::File::create(&path).unwrap())
+        .unwrap();
+
+    let generated = fs::read_to_string(&path).unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/types.d.ts");
+    let golden = fs::read_to_string(golden).unwrap();
+
+    assert_eq!(generated, golden);
+}
@@ -0,0 +1,36 @@
+//! Benchmark program deriving the benchmark types using serde-lite with the
+//! `compact` container attribute.
+//!
+//! Every input line has the form `<type name> <JSON>`. The JSON document is
+//! deserialized into the given type, serialized back and printed.
+
+use std::io::BufRead;
+
+use serde_lite::{Deserialize, Error, Intermediate, Serialize};
+
+size_bench::bench_types!(
+    derive(serde_lite_derive::Serialize, serde_lite_derive::Deserialize),
+    bound(serde_lite::Serialize + serde_lite::Deserialize),
+    container(serde(compact))
+);
+
+#[inline(never)]
+fn roundtrip<T>(input: &str) -> String
+where
+    T: Serialize + Deserialize,
+{
+    let val = serde_json::from_str::<Intermediate>(input)
+        .map_err(Error::custom)
+        .and_then(|val| T::deserialize(&val));
+
+    val.and_then(|val| {
+        let val = val.serialize()?;
+
+        serde_json::to_string(&val).map_err(Error::custom)
+    })
+    .unwrap_or_else(|err| err.to_string())
+}
+
+fn main() {
+    size_bench::run(std::io::stdin().lock().lines(), dispatch);
+}
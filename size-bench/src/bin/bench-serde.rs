@@ -0,0 +1,27 @@
+//! Benchmark program deriving the benchmark types using serde.
+//!
+//! Every input line has the form `<type name> <JSON>`. The JSON document is
+//! deserialized into the given type, serialized back and printed.
+
+use std::io::BufRead;
+
+// serde derive adds bounds of generic parameters on its own
+size_bench::bench_types!(
+    derive(serde::Serialize, serde::Deserialize),
+    bound(),
+    container()
+);
+
+#[inline(never)]
+fn roundtrip<T>(input: &str) -> String
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    serde_json::from_str::<T>(input)
+        .and_then(|val| serde_json::to_string(&val))
+        .unwrap_or_else(|err| err.to_string())
+}
+
+fn main() {
+    size_bench::run(std::io::stdin().lock().lines(), dispatch);
+}
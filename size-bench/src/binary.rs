@@ -0,0 +1,98 @@
+use std::{collections::HashSet, path::Path};
+
+use object::{Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+
+use crate::Error;
+
+/// Function symbol.
+#[derive(Debug, Clone)]
+pub struct Function {
+    /// Demangled name without the hash suffix.
+    pub name: String,
+    /// Size in bytes.
+    pub size: u64,
+}
+
+/// Code size information extracted from an executable.
+///
+/// Function sizes are taken from the symbol table, so the executable must
+/// not be stripped. Only formats that record symbol sizes (e.g. ELF) give
+/// meaningful function sizes.
+#[derive(Debug, Clone)]
+pub struct Binary {
+    text: u64,
+    functions: Vec<Function>,
+}
+
+impl Binary {
+    /// Read a given executable.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let data = std::fs::read(path)
+            .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+
+        Self::parse(&data)
+    }
+
+    /// Parse a given executable.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let file = object::File::parse(data)?;
+
+        let text = file
+            .sections()
+            .filter(|section| section.kind() == SectionKind::Text)
+            .map(|section| section.size())
+            .sum();
+
+        // aliases share the same address, count them only once
+        let mut addresses = HashSet::new();
+
+        let functions = file
+            .symbols()
+            .filter(|symbol| symbol.kind() == SymbolKind::Text && symbol.size() > 0)
+            .filter(|symbol| addresses.insert(symbol.address()))
+            .filter_map(|symbol| {
+                let name = symbol.name().ok()?;
+
+                let res = Function {
+                    name: format!("{:#}", rustc_demangle::demangle(name)),
+                    size: symbol.size(),
+                };
+
+                Some(res)
+            })
+            .collect();
+
+        let res = Self { text, functions };
+
+        Ok(res)
+    }
+
+    /// Get the total size of all executable sections.
+    #[inline]
+    pub fn text_size(&self) -> u64 {
+        self.text
+    }
+
+    /// Get all function symbols.
+    #[inline]
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// Get the total size of all functions matching a given predicate.
+    pub fn size_of<F>(&self, mut predicate: F) -> u64
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.functions
+            .iter()
+            .filter(|function| predicate(&function.name))
+            .map(|function| function.size)
+            .sum()
+    }
+}
@@ -0,0 +1,403 @@
+//! Binary size benchmark comparing code generated by serde and serde-lite.
+//!
+//! The `bench-serde` and `bench-serde-lite` programs derive the same set of
+//! types (see `bench_types!`) using serde and serde-lite respectively. The
+//! `bench-serde-lite-compact` program uses serde-lite with the `compact`
+//! container attribute. All programs use `serde_json` as the data format, so
+//! the difference between them is the derived code and the code it
+//! instantiates.
+//!
+//! The programs are built using the `size-bench` profile defined in the
+//! workspace manifest (optimized for size, LTO, abort on panic) and with the
+//! v0 symbol mangling, so that generic instantiations can be attributed to
+//! the benchmark types. The size of a type is the total size of all
+//! functions whose name mentions the type. Code inlined into a function
+//! counts towards that function. Functions mentioning more than one type
+//! (e.g. `Page<Order>`) count towards all of them.
+//!
+//! Run the benchmark using:
+//!
+//! ```text
+//! cargo run -p size-bench            # print the report
+//! cargo run -p size-bench -- --check # fail if any threshold is exceeded
+//! cargo run -p size-bench -- --bless # update the thresholds
+//! ```
+//!
+//! The thresholds are stored in `size-bench/thresholds.txt`. Sizes depend on
+//! the compiler version and the target, so the thresholds need to be blessed
+//! again after upgrading the toolchain.
+//!
+//! The threshold check is also available as the `test_size_thresholds` test.
+//! The test is ignored by default because it builds all benchmark programs
+//! in release mode and because its result depends on the toolchain, so it
+//! is not part of the regular test run (and CI). Run it explicitly on the
+//! toolchain the thresholds were blessed with before merging changes to the
+//! derive macros or the runtime:
+//!
+//! ```text
+//! cargo test -p size-bench -- --ignored
+//! ```
+
+mod binary;
+mod types;
+
+use std::{
+    env,
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+pub use crate::binary::{Binary, Function};
+
+/// Error type.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Names of all benchmark types.
+pub const TYPES: &[&str] = &[
+    "Address",
+    "Customer",
+    "OrderItem",
+    "Status",
+    "Event",
+    "Message",
+    "Order",
+    "Settings",
+    "Page",
+];
+
+/// Headroom (in percent) added to the measured sizes when blessing
+/// thresholds.
+pub const HEADROOM: u64 = 5;
+
+/// Main loop of the benchmark programs.
+///
+/// Every line has the form `<type name> <JSON>`. The result of the dispatch
+/// function is printed for every line.
+pub fn run<I>(lines: I, dispatch: fn(&str, &str) -> Option<String>)
+where
+    I: IntoIterator<Item = io::Result<String>>,
+{
+    for line in lines.into_iter().map_while(Result::ok) {
+        let (name, input) = line.split_once(' ').unwrap_or((&line, ""));
+
+        match dispatch(name, input) {
+            Some(output) => println!("{}", output),
+            None => println!("unknown type: {}", name),
+        }
+    }
+}
+
+/// Code sizes of a single benchmark program.
+#[derive(Debug, Clone)]
+pub struct Sizes {
+    /// Size of all executable sections.
+    pub text: u64,
+    /// Size of all functions mentioning any benchmark type.
+    pub derived: u64,
+    /// Size of functions mentioning a given benchmark type.
+    pub types: Vec<(&'static str, u64)>,
+}
+
+impl Sizes {
+    /// Measure a given benchmark program. The benchmark types are expected
+    /// to be defined in the root module of a given crate.
+    pub fn measure(binary: &Binary, krate: &str) -> Self {
+        let paths = TYPES
+            .iter()
+            .map(|name| format!("{}::{}", krate, name))
+            .collect::<Vec<_>>();
+
+        let derived = binary.size_of(|name| paths.iter().any(|path| mentions(name, path)));
+
+        let types = TYPES
+            .iter()
+            .zip(&paths)
+            .map(|(&name, path)| (name, binary.size_of(|f| mentions(f, path))))
+            .collect();
+
+        Self {
+            text: binary.text_size(),
+            derived,
+            types,
+        }
+    }
+
+    /// Get a size by its threshold key (`text`, `derived` or a type name).
+    pub fn get(&self, key: &str) -> Option<u64> {
+        match key {
+            "text" => Some(self.text),
+            "derived" => Some(self.derived),
+            _ => self
+                .types
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, size)| *size),
+        }
+    }
+}
+
+/// Check if a given function name mentions a given path.
+fn mentions(name: &str, path: &str) -> bool {
+    name.match_indices(path).any(|(idx, _)| {
+        let end = name[idx + path.len()..].chars().next();
+
+        !matches!(end, Some(c) if c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// Sizes of all benchmark programs.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub serde: Sizes,
+    pub serde_lite: Sizes,
+    pub serde_lite_compact: Sizes,
+}
+
+impl Measurement {
+    /// Build all benchmark programs and measure them.
+    pub fn run() -> Result<Self, Error> {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+        let mut rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+
+        rustflags.push_str(" -C symbol-mangling-version=v0");
+
+        let output = Command::new(cargo)
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(manifest)
+            .args(["--profile", "size-bench", "-p", "size-bench"])
+            .args(["--bin", "bench-serde", "--bin", "bench-serde-lite"])
+            .args(["--bin", "bench-serde-lite-compact"])
+            .arg("--message-format=json-render-diagnostics")
+            .env("RUSTFLAGS", rustflags.trim())
+            .env_remove("CARGO_ENCODED_RUSTFLAGS")
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        if !output.status.success() {
+            return Err("unable to build the benchmark programs".into());
+        }
+
+        let mut serde = None;
+        let mut serde_lite = None;
+        let mut serde_lite_compact = None;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let msg = serde_json::from_str::<serde_json::Value>(line)?;
+
+            if msg["reason"] != "compiler-artifact" {
+                continue;
+            }
+
+            let Some(executable) = msg["executable"].as_str() else {
+                continue;
+            };
+
+            match msg["target"]["name"].as_str() {
+                Some("bench-serde") => serde = Some(PathBuf::from(executable)),
+                Some("bench-serde-lite") => serde_lite = Some(PathBuf::from(executable)),
+                Some("bench-serde-lite-compact") => {
+                    serde_lite_compact = Some(PathBuf::from(executable))
+                }
+                _ => (),
+            }
+        }
+
+        let (Some(serde), Some(serde_lite), Some(serde_lite_compact)) =
+            (serde, serde_lite, serde_lite_compact)
+        else {
+            return Err("benchmark programs not found in the cargo output".into());
+        };
+
+        let res = Self {
+            serde: Sizes::measure(&Binary::open(serde)?, "bench_serde"),
+            serde_lite: Sizes::measure(&Binary::open(serde_lite)?, "bench_serde_lite"),
+            serde_lite_compact: Sizes::measure(
+                &Binary::open(serde_lite_compact)?,
+                "bench_serde_lite_compact",
+            ),
+        };
+
+        Ok(res)
+    }
+}
+
+impl Measurement {
+    /// Get a size by its threshold key.
+    ///
+    /// Keys of the serde-lite program are `text`, `derived` or a type name.
+    /// Keys of the compact program have the `compact.` prefix.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        if let Some(key) = key.strip_prefix("compact.") {
+            self.serde_lite_compact.get(key)
+        } else {
+            self.serde_lite.get(key)
+        }
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12} {:>10} {:>12} {:>8} {:>10} {:>8}",
+            "", "serde", "serde-lite", "ratio", "compact", "ratio"
+        )?;
+
+        let rows = [("text", ".text"), ("derived", "derived")]
+            .into_iter()
+            .chain(TYPES.iter().map(|&name| (name, name)));
+
+        for (key, label) in rows {
+            // all values are always present for the keys above
+            let serde = self.serde.get(key).unwrap_or(0);
+            let serde_lite = self.serde_lite.get(key).unwrap_or(0);
+            let compact = self.serde_lite_compact.get(key).unwrap_or(0);
+
+            writeln!(
+                f,
+                "{:<12} {:>10} {:>12} {:>8} {:>10} {:>8}",
+                label,
+                serde,
+                serde_lite,
+                ratio(serde_lite, serde),
+                compact,
+                ratio(compact, serde)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format a given size as a percentage of a given base.
+fn ratio(size: u64, base: u64) -> String {
+    if base > 0 {
+        format!("{:.1}%", size as f64 * 100.0 / base as f64)
+    } else {
+        String::from("-")
+    }
+}
+
+/// Maximum allowed sizes of the serde-lite benchmark programs.
+///
+/// The thresholds file contains one `<key> = <bytes>` pair per line (see
+/// `Measurement::get` for the keys). Empty lines and lines starting with `#`
+/// are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thresholds {
+    entries: Vec<(String, u64)>,
+}
+
+impl Thresholds {
+    /// Get the path of the thresholds file.
+    pub fn path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("thresholds.txt")
+    }
+
+    /// Load the thresholds file.
+    pub fn load() -> Result<Self, Error> {
+        let path = Self::path();
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+
+        Self::parse(&content)
+    }
+
+    /// Parse thresholds.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut entries = Vec::new();
+
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = line
+                .split_once('=')
+                .and_then(|(key, val)| Some((key.trim(), val.trim().parse().ok()?)));
+
+            let Some((key, val)) = entry else {
+                return Err(format!("invalid threshold on line {}", idx + 1).into());
+            };
+
+            entries.push((String::from(key), val));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Create thresholds from a given measurement adding the `HEADROOM`.
+    pub fn from_measurement(measurement: &Measurement) -> Self {
+        let keys = ["text", "derived"].into_iter().chain(TYPES.iter().copied());
+
+        let entries = keys
+            .clone()
+            .map(String::from)
+            .chain(keys.map(|key| format!("compact.{}", key)))
+            .map(|key| {
+                // all values are always present for the keys above
+                let size = measurement.get(&key).unwrap_or(0);
+
+                (key, size + size * HEADROOM / 100)
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Save the thresholds file.
+    pub fn save(&self) -> Result<(), Error> {
+        std::fs::write(Self::path(), self.to_string())?;
+
+        Ok(())
+    }
+
+    /// Check a given measurement. The method returns a description of every
+    /// exceeded threshold.
+    pub fn check(&self, measurement: &Measurement) -> Vec<String> {
+        let mut res = Vec::new();
+
+        for (key, max) in &self.entries {
+            match measurement.get(key) {
+                Some(size) if size > *max => res.push(format!(
+                    "{}: {} bytes exceeds the threshold of {} bytes",
+                    key, size, max
+                )),
+                Some(_) => (),
+                None => res.push(format!("{}: unknown threshold", key)),
+            }
+        }
+
+        res
+    }
+}
+
+impl Display for Thresholds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "# Maximum code sizes (in bytes) of the serde-lite benchmark programs."
+        )?;
+        writeln!(
+            f,
+            "# Generated by `cargo run -p size-bench -- --bless`, the values"
+        )?;
+        writeln!(f, "# include {}% headroom.", HEADROOM)?;
+        writeln!(f)?;
+
+        for (key, val) in &self.entries {
+            writeln!(f, "{} = {}", key, val)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+//! Build the benchmark programs and report their code sizes.
+//!
+//! Usage: `cargo run -p size-bench [-- --check | --bless]`
+
+use std::process::ExitCode;
+
+use size_bench::{Error, Measurement, Thresholds};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {}", err);
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<bool, Error> {
+    let arg = std::env::args().nth(1);
+
+    if !matches!(arg.as_deref(), None | Some("--check") | Some("--bless")) {
+        return Err("usage: size-bench [--check | --bless]".into());
+    }
+
+    let measurement = Measurement::run()?;
+
+    print!("{}", measurement);
+
+    match arg.as_deref() {
+        Some("--check") => {
+            let violations = Thresholds::load()?.check(&measurement);
+
+            for violation in &violations {
+                eprintln!("{}", violation);
+            }
+
+            Ok(violations.is_empty())
+        }
+        Some("--bless") => {
+            Thresholds::from_measurement(&measurement).save()?;
+
+            Ok(true)
+        }
+        _ => Ok(true),
+    }
+}
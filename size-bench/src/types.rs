@@ -0,0 +1,149 @@
+/// Define the benchmark types using given derive macros.
+///
+/// The types are supposed to resemble what a typical application would
+/// (de)serialize: nested structs, optional fields, enums in all tagging
+/// modes, a generic container and a large flat struct. The `bound` argument
+/// is used as a bound of the generic parameter (it may be empty). The
+/// `container` arguments are added as attributes to all types. The macro
+/// also defines a `dispatch` function passing the input to `roundtrip::<T>`
+/// based on a given type name. The `roundtrip` function must be defined by
+/// the caller.
+#[macro_export]
+macro_rules! bench_types {
+    (derive($($derive:path),*), bound($($bound:tt)*), container($($attr:meta),*)) => {
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct Address {
+            pub street: String,
+            pub city: String,
+            #[serde(rename = "zipCode")]
+            pub zip: String,
+            #[serde(default)]
+            pub country: Option<String>,
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct Customer {
+            pub id: u64,
+            pub name: String,
+            pub email: Option<String>,
+            pub billing: Address,
+            #[serde(default)]
+            pub shipping: Option<Address>,
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct OrderItem {
+            pub sku: String,
+            pub quantity: u32,
+            pub price: f64,
+            #[serde(default, skip_serializing_if = "Vec::is_empty")]
+            pub tags: Vec<String>,
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub enum Status {
+            Pending,
+            Paid(f64),
+            Shipped { carrier: String, tracking: String },
+            #[serde(rename = "canceled")]
+            Cancelled { reason: Option<String> },
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        #[serde(tag = "type")]
+        pub enum Event {
+            Created { order: u64 },
+            Updated { order: u64, status: Status },
+            Deleted { order: u64, reason: String },
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        #[serde(tag = "kind", content = "data")]
+        pub enum Message {
+            Ping,
+            Text(String),
+            Event(Event),
+            Batch(Vec<Event>),
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct Order {
+            pub id: u64,
+            pub customer: Customer,
+            pub items: Vec<OrderItem>,
+            pub status: Status,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub note: Option<String>,
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct Settings {
+            pub name: String,
+            pub description: Option<String>,
+            pub enabled: bool,
+            pub debug: bool,
+            pub verbose: bool,
+            pub port: u16,
+            pub workers: u32,
+            pub max_connections: u32,
+            pub timeout_ms: u64,
+            pub retry_delay_ms: u64,
+            pub max_retries: u8,
+            pub ratio: f64,
+            pub threshold: f32,
+            pub host: String,
+            pub prefix: String,
+            pub log_file: Option<String>,
+            pub log_level: String,
+            pub tags: Vec<String>,
+            pub allowed: Vec<String>,
+            pub denied: Vec<String>,
+            pub proxy: Option<String>,
+            pub user_agent: String,
+            #[serde(default)]
+            pub compression: bool,
+            #[serde(default)]
+            pub cache_size: u64,
+        }
+
+        #[derive($($derive),*)]
+        $(#[$attr])*
+        pub struct Page<T>
+        where
+            T: $($bound)*,
+        {
+            pub items: Vec<T>,
+            pub total: u64,
+            pub next: Option<String>,
+        }
+
+        /// Pass a given input to `roundtrip::<T>` where `T` is a benchmark
+        /// type with a given name.
+        #[inline(never)]
+        pub fn dispatch(name: &str, input: &str) -> Option<String> {
+            let res = match name {
+                "Address" => roundtrip::<Address>(input),
+                "Customer" => roundtrip::<Customer>(input),
+                "OrderItem" => roundtrip::<OrderItem>(input),
+                "Status" => roundtrip::<Status>(input),
+                "Event" => roundtrip::<Event>(input),
+                "Message" => roundtrip::<Message>(input),
+                "Order" => roundtrip::<Order>(input),
+                "Settings" => roundtrip::<Settings>(input),
+                "Page<Order>" => roundtrip::<Page<Order>>(input),
+                "Page<Message>" => roundtrip::<Page<Message>>(input),
+                _ => return None,
+            };
+
+            Some(res)
+        }
+    };
+}
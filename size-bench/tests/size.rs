@@ -0,0 +1,93 @@
+use size_bench::{Binary, Measurement, Sizes, Thresholds, TYPES};
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_binary() {
+    let binary = Binary::open(std::env::current_exe().unwrap()).unwrap();
+
+    assert!(binary.text_size() > 0);
+
+    let size = binary.size_of(|name| name == "size::test_binary");
+
+    assert!(size > 0);
+    assert!(size <= binary.text_size());
+
+    let size = binary.size_of(|name| name.starts_with("size_bench::Thresholds::"));
+
+    assert!(size > 0);
+}
+
+#[test]
+fn test_thresholds() {
+    let thresholds = Thresholds::parse(
+        "# comment\n\
+        \n\
+        text = 1000\n\
+        Order = 100\n",
+    )
+    .unwrap();
+
+    let sizes = Sizes {
+        text: 1000,
+        derived: 500,
+        types: TYPES.iter().map(|&name| (name, 100)).collect(),
+    };
+
+    let mut measurement = Measurement {
+        serde: sizes.clone(),
+        serde_lite: sizes.clone(),
+        serde_lite_compact: sizes,
+    };
+
+    assert!(thresholds.check(&measurement).is_empty());
+
+    measurement.serde_lite.text = 1001;
+    measurement.serde_lite.types[6].1 = 200;
+
+    assert_eq!(
+        thresholds.check(&measurement),
+        [
+            "text: 1001 bytes exceeds the threshold of 1000 bytes",
+            "Order: 200 bytes exceeds the threshold of 100 bytes",
+        ]
+    );
+
+    let thresholds = Thresholds::parse("compact.Order = 100\n").unwrap();
+
+    assert!(thresholds.check(&measurement).is_empty());
+
+    measurement.serde_lite_compact.types[6].1 = 101;
+
+    assert_eq!(
+        thresholds.check(&measurement),
+        ["compact.Order: 101 bytes exceeds the threshold of 100 bytes"]
+    );
+
+    let blessed = Thresholds::from_measurement(&measurement);
+
+    assert_eq!(Thresholds::parse(&blessed.to_string()).unwrap(), blessed);
+    assert!(blessed.check(&measurement).is_empty());
+
+    let thresholds = Thresholds::parse("Foo = 1\n").unwrap();
+
+    assert_eq!(thresholds.check(&measurement), ["Foo: unknown threshold"]);
+
+    assert!(Thresholds::parse("text 1000\n").is_err());
+    assert!(Thresholds::parse("text = x\n").is_err());
+}
+
+/// Build the benchmark programs in release mode and check the serde-lite
+/// sizes (including the compact ones) against the thresholds. Run it using
+/// `cargo test -p size-bench -- --ignored` (see the crate documentation for
+/// why it is ignored by default).
+#[test]
+#[ignore]
+fn test_size_thresholds() {
+    let measurement = Measurement::run().unwrap();
+
+    println!("{}", measurement);
+
+    let violations = Thresholds::load().unwrap().check(&measurement);
+
+    assert!(violations.is_empty(), "{}", violations.join("\n"));
+}